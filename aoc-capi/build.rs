@@ -0,0 +1,18 @@
+/// Regenerate the C header from the extern functions of the crate. It goes in OUT_DIR since a
+/// build must not write in the sources, a test checks that include/aoc.h is the same
+fn main() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR is not set");
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is not set");
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir))
+        .expect("Could not read cbindgen.toml");
+
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("Could not generate the C header")
+        .write_to_file(format!("{}/aoc.h", out_dir));
+
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+}
@@ -0,0 +1,43 @@
+#ifndef AOC_H
+#define AOC_H
+
+/* Generated by cbindgen from aoc-capi/src/lib.rs, do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The input is not valid (null pointer, unknown part)
+#define AOC_ERROR_INVALID_ARGUMENT -1
+
+// The input is not valid UTF-8
+#define AOC_ERROR_INVALID_UTF8 -2
+
+// The solver returned an error, its message is written in the output buffer, truncated to fit
+#define AOC_ERROR_SOLVER -3
+
+// The output buffer is too small for the answer
+#define AOC_ERROR_BUFFER_TOO_SMALL -4
+
+// The solver panicked, usually because of a malformed input
+#define AOC_ERROR_PANIC -5
+
+// Solve a part (1 or 2) of a day.
+//
+// On success the answer is written in `out_buf` as a NUL terminated string and its length
+// (without the NUL byte) is returned, otherwise one of the negative `AOC_ERROR_*` codes is
+// returned.
+//
+// # Safety
+//
+// `input_ptr` must point to `input_len` readable bytes and `out_buf` to `out_buf_len`
+// writable bytes.
+int32_t aoc_solve(uint8_t day,
+                  uint8_t part,
+                  const uint8_t *input_ptr,
+                  uintptr_t input_len,
+                  uint8_t *out_buf,
+                  uintptr_t out_buf_len);
+
+#endif /* AOC_H */
@@ -0,0 +1,149 @@
+//! C ABI of the solutions, to embed them in C or C++ programs.
+//!
+//! Link against the `aoc` shared (or static) library and include `include/aoc.h`:
+//!
+//! ```c
+//! char answer[64];
+//! int32_t len = aoc_solve(5, 2, input, input_len, answer, sizeof(answer));
+//! if (len >= 0) printf("%s\n", answer);
+//! ```
+use aoc2023::Part;
+use std::panic;
+
+/// The input is not valid (null pointer, unknown part)
+pub const AOC_ERROR_INVALID_ARGUMENT: i32 = -1;
+/// The input is not valid UTF-8
+pub const AOC_ERROR_INVALID_UTF8: i32 = -2;
+/// The solver returned an error, its message is written in the output buffer, truncated to fit
+pub const AOC_ERROR_SOLVER: i32 = -3;
+/// The output buffer is too small for the answer
+pub const AOC_ERROR_BUFFER_TOO_SMALL: i32 = -4;
+/// The solver panicked, usually because of a malformed input
+pub const AOC_ERROR_PANIC: i32 = -5;
+
+/// Solve a part (1 or 2) of a day.
+///
+/// On success the answer is written in `out_buf` as a NUL terminated string and its length
+/// (without the NUL byte) is returned, otherwise one of the negative `AOC_ERROR_*` codes is
+/// returned.
+///
+/// # Safety
+///
+/// `input_ptr` must point to `input_len` readable bytes and `out_buf` to `out_buf_len`
+/// writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(
+    day: u8,
+    part: u8,
+    input_ptr: *const u8,
+    input_len: usize,
+    out_buf: *mut u8,
+    out_buf_len: usize,
+) -> i32 {
+    if input_ptr.is_null() || out_buf.is_null() {
+        return AOC_ERROR_INVALID_ARGUMENT;
+    }
+    let part = match part {
+        1 => Part::Part1,
+        2 => Part::Part2,
+        _ => return AOC_ERROR_INVALID_ARGUMENT,
+    };
+    let input = std::slice::from_raw_parts(input_ptr, input_len);
+    let input = match std::str::from_utf8(input) {
+        Ok(input) => input,
+        Err(_) => return AOC_ERROR_INVALID_UTF8,
+    };
+    let out_buf = std::slice::from_raw_parts_mut(out_buf, out_buf_len);
+
+    // A panic must not unwind into the C caller
+    match panic::catch_unwind(|| aoc2023::solve(day, part, input)) {
+        Ok(Ok(answer)) => write_c_string(&answer.to_string(), out_buf),
+        Ok(Err(error)) => {
+            write_truncated_c_string(&error.to_string(), out_buf);
+            AOC_ERROR_SOLVER
+        }
+        Err(_) => AOC_ERROR_PANIC,
+    }
+}
+
+/// Copy the string and its NUL terminator in the buffer, returning its length
+fn write_c_string(value: &str, out_buf: &mut [u8]) -> i32 {
+    let bytes = value.as_bytes();
+    if bytes.len() >= out_buf.len() {
+        return AOC_ERROR_BUFFER_TOO_SMALL;
+    }
+    out_buf[..bytes.len()].copy_from_slice(bytes);
+    out_buf[bytes.len()] = 0;
+    bytes.len() as i32
+}
+
+/// Copy as much of the string as fits in the buffer, cut between two characters, and always
+/// NUL terminate it unless the buffer is empty
+fn write_truncated_c_string(value: &str, out_buf: &mut [u8]) {
+    let Some(max_len) = out_buf.len().checked_sub(1) else {
+        return;
+    };
+    let mut len = value.len().min(max_len);
+    while !value.is_char_boundary(len) {
+        len -= 1;
+    }
+    out_buf[..len].copy_from_slice(&value.as_bytes()[..len]);
+    out_buf[len] = 0;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_aoc_solve() {
+        let input = "1abc2\npqr3stu8vwx";
+        let mut out_buf = [0u8; 8];
+        let len = unsafe {
+            aoc_solve(
+                1,
+                1,
+                input.as_ptr(),
+                input.len(),
+                out_buf.as_mut_ptr(),
+                out_buf.len(),
+            )
+        };
+        assert_eq!(len, 2);
+        assert_eq!(&out_buf[..3], b"50\0");
+
+        let len = unsafe { aoc_solve(1, 1, input.as_ptr(), input.len(), out_buf.as_mut_ptr(), 2) };
+        assert_eq!(len, AOC_ERROR_BUFFER_TOO_SMALL);
+
+        let len = unsafe { aoc_solve(1, 3, input.as_ptr(), input.len(), out_buf.as_mut_ptr(), 8) };
+        assert_eq!(len, AOC_ERROR_INVALID_ARGUMENT);
+
+        // The error messages are cut to the buffer instead of being left unterminated
+        let input = "Time: 7\n";
+        let mut out_buf = [0xffu8; 8];
+        let len = unsafe { aoc_solve(6, 1, input.as_ptr(), input.len(), out_buf.as_mut_ptr(), 8) };
+        assert_eq!(len, AOC_ERROR_SOLVER);
+        assert_eq!(&out_buf, b"No dist\0");
+    }
+
+    #[test]
+    fn test_write_truncated_c_string() {
+        let mut out_buf = [0xffu8; 4];
+        write_truncated_c_string("ab", &mut out_buf);
+        assert_eq!(&out_buf, b"ab\0\xff");
+        // "é" is two bytes, it does not fit after "ab"
+        write_truncated_c_string("abé", &mut out_buf);
+        assert_eq!(&out_buf[..3], b"ab\0");
+        write_truncated_c_string("abc", &mut out_buf[..0]);
+    }
+
+    #[test]
+    fn test_header_is_up_to_date() {
+        // The header is generated in OUT_DIR, the committed copy must match it
+        assert_eq!(
+            include_str!(concat!(env!("OUT_DIR"), "/aoc.h")),
+            include_str!("../include/aoc.h"),
+            "include/aoc.h is outdated, copy the header generated by build.rs over it"
+        );
+    }
+}
@@ -0,0 +1,49 @@
+//! WebAssembly bindings of the solutions, to run them in a web page.
+//!
+//! Build with `wasm-pack build aoc-wasm --target web`, then from JavaScript:
+//!
+//! ```js
+//! import init, { days, solve } from "./pkg/aoc_wasm.js";
+//!
+//! await init();
+//! console.log(days());                 // Uint8Array [1, 2, ..., 25]
+//! console.log(solve(5, 2, inputText)); // the answer as a string, throws on invalid input
+//! ```
+//!
+//! Every day of `aoc2023` is included by default, `days()` lists the ones of the registry so a
+//! build with fewer day features gives fewer days.
+use aoc2023::{Part, PuzzleId};
+use wasm_bindgen::prelude::*;
+
+/// Solve a part (1 or 2) of a day with the given input, the answer is returned as a string
+/// because it may not fit in a JavaScript number
+#[wasm_bindgen]
+pub fn solve(day: u8, part: u8, input: &str) -> Result<String, JsError> {
+    let part = match part {
+        1 => Part::Part1,
+        2 => Part::Part2,
+        _ => return Err(JsError::new(&format!("Invalid part {}", part))),
+    };
+    aoc2023::solve(day, part, input)
+        .map(|answer| answer.to_string())
+        .map_err(|error| JsError::new(&error.to_string()))
+}
+
+/// The days available in this build
+#[wasm_bindgen]
+pub fn days() -> Vec<u8> {
+    aoc2023::registry()
+        .days_of(aoc2023::YEAR)
+        .map(|id: PuzzleId| id.day)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_days() {
+        assert_eq!(days(), (1..=25).collect::<Vec<u8>>());
+    }
+}
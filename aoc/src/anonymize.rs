@@ -0,0 +1,258 @@
+//! Rewrite a puzzle input into a structurally equivalent one with shuffled values, so it can be
+//! shared (in a bug report for example) without redistributing the real input.
+use aocstd::{Error, Result};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Anonymize the input of a 2023 day, the rng makes the output reproducible for a given seed
+pub fn anonymize(day: u8, input: &str, rng: &mut StdRng) -> Result<String> {
+    let lines: Vec<&str> = input.lines().collect();
+    let anonymized = match day {
+        1 => {
+            let mut lines: Vec<String> = lines.iter().map(|l| permute_digits(l, rng)).collect();
+            lines.shuffle(rng);
+            lines
+        }
+        2 => lines
+            .iter()
+            .map(|l| shuffle_game_sets(l, rng))
+            .collect::<Result<_>>()?,
+        3 => {
+            // The same permutation must be used for the whole schematic, otherwise a number
+            // split on two lines would not be consistent
+            let permutation = digit_permutation(rng);
+            lines
+                .iter()
+                .map(|line| apply_digit_permutation(line, &permutation))
+                .collect()
+        }
+        4 => anonymize_cards(&lines, rng)?,
+        5 => anonymize_almanac(&lines, rng)?,
+        6 => anonymize_races(&lines, rng)?,
+        _ => return Err(Error::new(format!("No anonymizer for day {}", day))),
+    };
+    Ok(anonymized.join("\n") + "\n")
+}
+
+/// Permutation of the digits 1 to 9, 0 is kept in place so that no number gains a leading zero
+fn digit_permutation(rng: &mut StdRng) -> [char; 10] {
+    let mut digits = ['1', '2', '3', '4', '5', '6', '7', '8', '9'];
+    digits.shuffle(rng);
+    let mut permutation = ['0'; 10];
+    permutation[1..].copy_from_slice(&digits);
+    permutation
+}
+
+fn apply_digit_permutation(line: &str, permutation: &[char; 10]) -> String {
+    line.chars()
+        .map(|c| match c.to_digit(10) {
+            Some(digit) => permutation[digit as usize],
+            None => c,
+        })
+        .collect()
+}
+
+/// Day01: the digits of every line are permuted and the lines shuffled, the spelled digits and
+/// the noise are kept
+fn permute_digits(line: &str, rng: &mut StdRng) -> String {
+    apply_digit_permutation(line, &digit_permutation(rng))
+}
+
+/// Day02: the sets of each game and the cubes of each set are shuffled, the ids are kept in order
+fn shuffle_game_sets(line: &str, rng: &mut StdRng) -> Result<String> {
+    let (header, body) = line
+        .split_once(':')
+        .ok_or_else(|| Error::new(format!("Invalid game: {}", line)))?;
+    let mut sets: Vec<String> = body
+        .split(';')
+        .map(|set| {
+            let mut cubes: Vec<&str> = set.split(',').map(str::trim).collect();
+            cubes.shuffle(rng);
+            cubes.join(", ")
+        })
+        .collect();
+    sets.shuffle(rng);
+    Ok(format!("{}: {}", header, sets.join("; ")))
+}
+
+fn anonymize_cards(lines: &[&str], rng: &mut StdRng) -> Result<Vec<String>> {
+    // Use the same bijection of the numbers for every card so that the matches are kept
+    let mut bijection: Vec<u32> = (1..100).collect();
+    bijection.shuffle(rng);
+
+    let mut anonymized = Vec::with_capacity(lines.len());
+    for line in lines {
+        let (header, body) = line
+            .split_once(':')
+            .ok_or_else(|| Error::new(format!("Invalid card: {}", line)))?;
+        let mut parts = Vec::new();
+        for part in body.split('|') {
+            let mut numbers = part
+                .split_whitespace()
+                .map(|n| match n.parse::<usize>() {
+                    Ok(n) if (1..100).contains(&n) => Ok(bijection[n - 1]),
+                    _ => Err(Error::new(format!("Invalid card number {}", n))),
+                })
+                .collect::<Result<Vec<u32>>>()?;
+            numbers.shuffle(rng);
+            let numbers: Vec<String> = numbers.iter().map(|n| format!("{:2}", n)).collect();
+            parts.push(numbers.join(" "));
+        }
+        anonymized.push(format!("{}: {}", header, parts.join(" | ")));
+    }
+    Ok(anonymized)
+}
+
+fn anonymize_almanac(lines: &[&str], rng: &mut StdRng) -> Result<Vec<String>> {
+    // Shifting every start of range by the same offset keeps every mapping (and thus the
+    // structure of the puzzle) while changing all the values
+    let offset: u64 = rng.gen_range(0..1 << 32);
+    let shift = |n: &str| -> Result<u64> {
+        n.parse::<u64>()
+            .map_err(|_| Error::new(format!("Invalid almanac number {}", n)))?
+            .checked_add(offset)
+            .ok_or_else(|| {
+                Error::new(format!(
+                    "The almanac number {} is too large to be shifted",
+                    n
+                ))
+            })
+    };
+
+    let mut anonymized = Vec::with_capacity(lines.len());
+    let mut current_map: Vec<String> = Vec::new();
+    for line in lines {
+        if let Some(seeds) = line.strip_prefix("seeds:") {
+            // The seeds are either seeds or (start, length) pairs, only the starts are shifted
+            let mut seeds_line = String::from("seeds:");
+            for (index, n) in seeds.split_whitespace().enumerate() {
+                let n = if index % 2 == 0 {
+                    shift(n)?.to_string()
+                } else {
+                    n.to_string()
+                };
+                seeds_line.push(' ');
+                seeds_line.push_str(&n);
+            }
+            anonymized.push(seeds_line);
+        } else if line.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+            let numbers: Vec<&str> = line.split_whitespace().collect();
+            if numbers.len() != 3 {
+                return Err(Error::new(format!("Invalid transformation: {}", line)));
+            }
+            current_map.push(format!(
+                "{} {} {}",
+                shift(numbers[0])?,
+                shift(numbers[1])?,
+                numbers[2]
+            ));
+        } else {
+            // The transformations of a map do not overlap, so their order does not matter
+            current_map.shuffle(rng);
+            anonymized.append(&mut current_map);
+            anonymized.push(line.to_string());
+        }
+    }
+    current_map.shuffle(rng);
+    anonymized.append(&mut current_map);
+    Ok(anonymized)
+}
+
+fn anonymize_races(lines: &[&str], rng: &mut StdRng) -> Result<Vec<String>> {
+    let values: Vec<(&str, Vec<&str>)> = lines
+        .iter()
+        .map(|line| {
+            line.split_once(':')
+                .map(|(header, values)| (header, values.split_whitespace().collect()))
+                .ok_or_else(|| Error::new(format!("Invalid race line: {}", line)))
+        })
+        .collect::<Result<_>>()?;
+    let nb_of_races = values.first().map(|(_, v)| v.len()).unwrap_or(0);
+
+    // Shuffle the races (the columns), which changes the kerned race of part 2
+    let mut order: Vec<usize> = (0..nb_of_races).collect();
+    order.shuffle(rng);
+
+    Ok(values
+        .iter()
+        .map(|(header, values)| {
+            let columns: Vec<String> = order
+                .iter()
+                .map(|i| format!("{:>6}", values.get(*i).copied().unwrap_or_default()))
+                .collect();
+            // The header is padded so that the values never touch it, like in the real inputs
+            format!("{:<11}{}", format!("{}:", header), columns.join(" "))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aocstd::Part;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_anonymized_cards_keep_the_answers() {
+        aocstd::init_tests();
+
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53\n\
+                     Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19\n\
+                     Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1\n\
+                     Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83\n\
+                     Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36\n\
+                     Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+        let mut rng = StdRng::seed_from_u64(42);
+        let anonymized = anonymize(4, input, &mut rng).unwrap();
+        assert_ne!(anonymized, input);
+        for part in [Part::Part1, Part::Part2] {
+            assert_eq!(
+                aoc2023::solve(4, part, &anonymized).unwrap(),
+                aoc2023::solve(4, part, input).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_anonymized_almanac_is_shifted() {
+        aocstd::init_tests();
+
+        let input = "seeds: 79 14 55 13\n\
+                     \n\
+                     seed-to-soil map:\n\
+                     50 98 2\n\
+                     52 50 48\n\
+                     \n\
+                     soil-to-fertilizer map:\n\
+                     0 15 37\n\
+                     37 52 2\n\
+                     39 0 15";
+        let mut rng = StdRng::seed_from_u64(42);
+        let anonymized = anonymize(5, input, &mut rng).unwrap();
+        let original_seed: u64 = 79;
+        let shifted_seed: u64 = anonymized
+            .split_whitespace()
+            .nth(1)
+            .unwrap()
+            .parse()
+            .unwrap();
+        let offset = shifted_seed - original_seed;
+        let answer = aoc2023::solve(5, Part::Part2, &anonymized).unwrap();
+        let original_answer = aoc2023::solve(5, Part::Part2, input).unwrap();
+        match (answer, original_answer) {
+            (aocstd::Answer::Unsigned(answer), aocstd::Answer::Unsigned(original_answer)) => {
+                assert_eq!(answer, original_answer + offset)
+            }
+            _ => panic!("Unexpected answer type"),
+        }
+    }
+
+    #[test]
+    fn test_anonymized_almanac_overflow() {
+        aocstd::init_tests();
+
+        let mut rng = StdRng::seed_from_u64(42);
+        assert!(anonymize(5, "seeds: 18446744073709551615 1", &mut rng).is_err());
+    }
+}
@@ -0,0 +1,244 @@
+use aocstd::registry::{PuzzleId, Registry};
+use aocstd::Part;
+use clap::{Parser, Subcommand};
+use rand::SeedableRng;
+use std::path::PathBuf;
+use std::time::Instant;
+
+mod anonymize;
+mod rpc;
+mod serve;
+mod tui;
+
+/// Unified runner for every registered Advent of Code solution
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    /// Year of the puzzles
+    #[arg(short, long, global = true, default_value_t = aoc2023::YEAR)]
+    year: u16,
+    #[arg(short, long, global = true)]
+    verbose: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve one part of a day
+    Run {
+        day: u8,
+        #[arg(value_enum)]
+        part: Part,
+        /// Defaults to the input file of the day in the workspace
+        #[arg(short, long)]
+        input_file: Option<PathBuf>,
+        /// Write the intermediate steps of the solver to this file, as JSON Lines
+        #[arg(long)]
+        trace: Option<PathBuf>,
+        /// Report the allocations, heap peak and peak RSS of the solver along with its timing
+        #[arg(long)]
+        mem: bool,
+        /// Sample the CPU during the solve and write a flamegraph SVG to this file (needs the
+        /// profile feature)
+        #[arg(long)]
+        profile: Option<PathBuf>,
+    },
+    /// List the registered days of the year
+    List,
+    /// Rewrite an input with shuffled values but the same structure, and solve it
+    Anonymize {
+        day: u8,
+        /// Defaults to the input file of the day in the workspace
+        #[arg(short, long)]
+        input_file: Option<PathBuf>,
+        /// Defaults to stdout
+        #[arg(short, long)]
+        output_file: Option<PathBuf>,
+        /// Seed of the shuffling, to get a reproducible output
+        #[arg(short, long)]
+        seed: Option<u64>,
+    },
+    /// Speak JSON-RPC on stdin/stdout, one request per line
+    Rpc,
+    /// Dashboard running every day of the year
+    Tui,
+    /// Expose the solvers as a REST API
+    Serve {
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
+        /// Address to listen on, 0.0.0.0 to accept the requests of other machines
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+    },
+}
+
+/// Every year of the workspace registers its days here
+fn build_registry() -> Registry {
+    let mut registry = Registry::new();
+    aoc2023::register(&mut registry);
+    registry
+}
+
+fn read_input(id: PuzzleId, input_file: Option<PathBuf>) -> String {
+    let input_file = input_file.unwrap_or_else(|| id.input_path());
+    match std::fs::read_to_string(&input_file) {
+        Ok(input) => input,
+        Err(error) => {
+            log::error!("Could not read {}: {}", input_file.display(), error);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run(
+    registry: &Registry,
+    id: PuzzleId,
+    part: Part,
+    input_file: Option<PathBuf>,
+    trace: Option<PathBuf>,
+    mem: bool,
+    profile: Option<PathBuf>,
+) {
+    let input = read_input(id, input_file);
+    if let Some(trace) = trace {
+        match std::fs::File::create(&trace) {
+            Ok(file) => aocstd::trace::set_sink(std::io::BufWriter::new(file)),
+            Err(error) => {
+                log::error!("Could not create {}: {}", trace.display(), error);
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Some(profile) = &profile {
+        if let Err(error) = aocstd::profile::start(profile) {
+            log::error!("{}", error);
+            std::process::exit(1);
+        }
+    }
+    aocstd::memory::reset();
+    let start = Instant::now();
+    let answer = registry.solve(id, part, &input);
+    let elapsed = start.elapsed();
+    match aocstd::profile::finish() {
+        Ok(Some(path)) => log::info!("Flamegraph written to {}", path.display()),
+        Ok(None) => (),
+        Err(error) => log::error!("{}", error),
+    }
+    if mem {
+        // Asked for explicitly, so not hidden behind --verbose like the timing alone
+        eprintln!(
+            "{} {} solved in {:?}, {}",
+            id,
+            part,
+            elapsed,
+            aocstd::memory::stats()
+        );
+    } else {
+        log::info!("{} {} solved in {:?}", id, part, elapsed);
+    }
+    aocstd::report_answer(part, answer);
+}
+
+fn anonymize(
+    registry: &Registry,
+    id: PuzzleId,
+    input_file: Option<PathBuf>,
+    output_file: Option<PathBuf>,
+    seed: Option<u64>,
+) {
+    if id.year != aoc2023::YEAR {
+        log::error!("The anonymizer only knows the {} inputs", aoc2023::YEAR);
+        std::process::exit(1);
+    }
+    let input = read_input(id, input_file);
+    let mut rng = match seed {
+        Some(seed) => rand::rngs::StdRng::seed_from_u64(seed),
+        None => rand::rngs::StdRng::from_entropy(),
+    };
+    let anonymized = match anonymize::anonymize(id.day, &input, &mut rng) {
+        Ok(anonymized) => anonymized,
+        Err(error) => {
+            log::error!("Could not anonymize the input: {}", error);
+            std::process::exit(1);
+        }
+    };
+
+    match output_file {
+        Some(output_file) => {
+            if let Err(error) = std::fs::write(&output_file, &anonymized) {
+                log::error!("Could not write {}: {}", output_file.display(), error);
+                std::process::exit(1);
+            }
+        }
+        None => print!("{}", anonymized),
+    }
+
+    // The answers changed with the values, give the new ones along with the input
+    for part in [Part::Part1, Part::Part2] {
+        match registry.solve(id, part, &anonymized) {
+            Ok(answer) => log::info!("{} of the anonymized input: {}", part, answer),
+            Err(error) => log::error!("{} of the anonymized input failed: {}", part, error),
+        }
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    aocstd::init_logger_with_verbosity(cli.verbose);
+    let registry = build_registry();
+
+    match cli.command {
+        Command::Run {
+            day,
+            part,
+            input_file,
+            trace,
+            mem,
+            profile,
+        } => run(
+            &registry,
+            PuzzleId::new(cli.year, day),
+            part,
+            input_file,
+            trace,
+            mem,
+            profile,
+        ),
+        Command::Anonymize {
+            day,
+            input_file,
+            output_file,
+            seed,
+        } => anonymize(
+            &registry,
+            PuzzleId::new(cli.year, day),
+            input_file,
+            output_file,
+            seed,
+        ),
+        Command::Serve { port, host } => {
+            if let Err(error) = serve::serve(&registry, &host, port) {
+                log::error!("{}", error);
+                std::process::exit(1);
+            }
+        }
+        Command::Rpc => {
+            if let Err(error) = rpc::run_stdio(&registry, cli.year) {
+                log::error!("{}", error);
+                std::process::exit(1);
+            }
+        }
+        Command::Tui => {
+            if let Err(error) = tui::run(&registry, cli.year) {
+                log::error!("{}", error);
+                std::process::exit(1);
+            }
+        }
+        Command::List => {
+            for id in registry.days_of(cli.year) {
+                println!("{}", id);
+            }
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! JSON-RPC 2.0 over stdio, one request per line, for the editors and test harnesses that
+//! want to call the solvers repeatedly without spawning a process each time.
+//!
+//! Methods:
+//!   listDays  {"year"?}                          -> [{"year", "day"}]
+//!   solve     {"year"?, "day", "part", "input"}  -> {"answer", "elapsed_us"}
+//!   parseOnly {"year"?, "day", "input"}          -> {"summary", "elapsed_us"}
+use aocstd::registry::{PuzzleId, Registry};
+use aocstd::Part;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+use std::time::Instant;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SOLVER_ERROR: i64 = -32000;
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    id: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct YearParams {
+    year: Option<u16>,
+}
+
+#[derive(Deserialize)]
+struct PuzzleParams {
+    year: Option<u16>,
+    day: u8,
+    part: Option<u8>,
+    input: String,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+pub fn run_stdio(registry: &Registry, default_year: u16) -> aocstd::Result<()> {
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle_line(registry, default_year, &line) {
+            writeln!(stdout, "{}", response)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Handle one request, returning the response to send (nothing for the notifications)
+fn handle_line(registry: &Registry, default_year: u16, line: &str) -> Option<Value> {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => {
+            return Some(error_response(
+                Value::Null,
+                RpcError::new(PARSE_ERROR, e.to_string()),
+            ))
+        }
+    };
+    let request: Request = match serde_json::from_value(request) {
+        Ok(request) => request,
+        Err(e) => {
+            let error = RpcError::new(INVALID_REQUEST, e.to_string());
+            return Some(error_response(Value::Null, error));
+        }
+    };
+    if request.jsonrpc != "2.0" {
+        let error = RpcError::new(INVALID_REQUEST, "Only JSON-RPC 2.0 is supported");
+        return Some(error_response(request.id.unwrap_or(Value::Null), error));
+    }
+
+    let result = handle_request(registry, default_year, &request.method, request.params);
+    let id = request.id?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "result": result, "id": id }),
+        Err(error) => error_response(id, error),
+    })
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "error": { "code": error.code, "message": error.message },
+        "id": id,
+    })
+}
+
+fn parse_params<T: for<'de> Deserialize<'de>>(params: Value) -> Result<T, RpcError> {
+    // The params are optional for the methods without required fields
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+fn handle_request(
+    registry: &Registry,
+    default_year: u16,
+    method: &str,
+    params: Value,
+) -> Result<Value, RpcError> {
+    match method {
+        "listDays" => {
+            let params: YearParams = parse_params(params)?;
+            let days: Vec<Value> = registry
+                .days_of(params.year.unwrap_or(default_year))
+                .map(|id| json!({ "year": id.year, "day": id.day }))
+                .collect();
+            Ok(Value::from(days))
+        }
+        "solve" => {
+            let params: PuzzleParams = parse_params(params)?;
+            let id = PuzzleId::new(params.year.unwrap_or(default_year), params.day);
+            let part = match params.part {
+                Some(1) => Part::Part1,
+                Some(2) => Part::Part2,
+                _ => return Err(RpcError::new(INVALID_PARAMS, "The part must be 1 or 2")),
+            };
+            let start = Instant::now();
+            let answer = aocstd::catch_panic(|| registry.solve(id, part, &params.input))
+                .map_err(|e| RpcError::new(SOLVER_ERROR, e.to_string()))?;
+            Ok(json!({
+                "answer": answer.to_string(),
+                "elapsed_us": start.elapsed().as_micros() as u64,
+            }))
+        }
+        "parseOnly" => {
+            let params: PuzzleParams = parse_params(params)?;
+            let id = PuzzleId::new(params.year.unwrap_or(default_year), params.day);
+            let start = Instant::now();
+            let summary = aocstd::catch_panic(|| registry.parse_only(id, &params.input))
+                .map_err(|e| RpcError::new(SOLVER_ERROR, e.to_string()))?;
+            Ok(json!({
+                "summary": summary,
+                "elapsed_us": start.elapsed().as_micros() as u64,
+            }))
+        }
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Unknown method {}", method),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_handle_line() {
+        aocstd::init_tests();
+        let registry = aoc2023::registry();
+
+        let response = handle_line(
+            &registry,
+            2023,
+            r#"{"jsonrpc": "2.0", "method": "solve", "params": {"day": 1, "part": 1, "input": "1abc2"}, "id": 1}"#,
+        )
+        .unwrap();
+        assert_eq!(response["result"]["answer"], "12");
+        assert_eq!(response["id"], 1);
+
+        let response = handle_line(
+            &registry,
+            2023,
+            r#"{"jsonrpc": "2.0", "method": "parseOnly", "params": {"day": 6, "input": "Time:      7  15\nDistance:  9  40"}, "id": "a"}"#,
+        )
+        .unwrap();
+        assert_eq!(response["result"]["summary"], "2 races");
+
+        let response = handle_line(
+            &registry,
+            2023,
+            r#"{"jsonrpc": "2.0", "method": "listDays", "id": 2}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            response["result"].as_array().unwrap().len(),
+            registry.days_of(2023).count()
+        );
+        assert_eq!(response["result"][0]["day"], 1);
+
+        let response = handle_line(
+            &registry,
+            2023,
+            r#"{"jsonrpc": "2.0", "method": "nope", "id": 3}"#,
+        );
+        assert_eq!(response.unwrap()["error"]["code"], METHOD_NOT_FOUND);
+
+        // Notifications do not get a response
+        assert!(handle_line(
+            &registry,
+            2023,
+            r#"{"jsonrpc": "2.0", "method": "listDays"}"#
+        )
+        .is_none());
+        assert_eq!(
+            handle_line(&registry, 2023, "{").unwrap()["error"]["code"],
+            PARSE_ERROR
+        );
+    }
+}
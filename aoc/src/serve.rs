@@ -0,0 +1,160 @@
+//! HTTP server exposing the registered solvers as a REST API:
+//!   GET  /<year>                        -> the registered days of the year
+//!   POST /<year>/day/<day>/part/<part>  -> solve the part with the request body as input
+use aocstd::registry::{PuzzleId, Registry};
+use aocstd::Part;
+use serde_json::{json, Value};
+use std::io::Read;
+use std::time::Instant;
+use tiny_http::{Header, Method, Response, Server};
+
+/// The largest input accepted, the puzzle inputs are a few dozen KiB
+const MAX_INPUT_BYTES: u64 = 1024 * 1024;
+
+pub fn serve(registry: &Registry, host: &str, port: u16) -> aocstd::Result<()> {
+    let server = Server::http((host, port)).map_err(|error| {
+        aocstd::Error::new(format!("Could not listen on {}:{}: {}", host, port, error))
+    })?;
+    log::info!("Listening on http://{}:{}", host, port);
+
+    for mut request in server.incoming_requests() {
+        let method = request.method().clone();
+        let url = request.url().to_string();
+        let (status, body) = handle(registry, &method, &url, request.as_reader());
+        log::info!("{} {} -> {}", request.method(), request.url(), status);
+        let content_type = Header::from_bytes("Content-Type", "application/json")
+            .expect("Invalid content type header");
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(content_type);
+        if let Err(error) = request.respond(response) {
+            log::warn!("Could not send the response: {}", error);
+        }
+    }
+    Ok(())
+}
+
+fn error(status: u16, message: impl Into<String>) -> (u16, Value) {
+    (status, json!({ "error": message.into() }))
+}
+
+/// The body is only read for the routes that take an input, and never past MAX_INPUT_BYTES
+fn handle(registry: &Registry, method: &Method, url: &str, body: impl Read) -> (u16, Value) {
+    let segments: Vec<&str> = url.trim_matches('/').split('/').collect();
+    match (method, segments.as_slice()) {
+        (Method::Get, [year]) => match year.parse::<u16>() {
+            Ok(year) => {
+                let days: Vec<u8> = registry.days_of(year).map(|id| id.day).collect();
+                (200, json!({ "year": year, "days": days }))
+            }
+            Err(_) => error(404, "Unknown route"),
+        },
+        (Method::Post, [year, "day", day, "part", part]) => {
+            let (Ok(year), Ok(day)) = (year.parse::<u16>(), day.parse::<u8>()) else {
+                return error(404, "Unknown route");
+            };
+            let part = match *part {
+                "1" => Part::Part1,
+                "2" => Part::Part2,
+                _ => return error(404, "Unknown route"),
+            };
+            let id = PuzzleId::new(year, day);
+            if registry.get(id).is_none() {
+                return error(404, format!("No solver registered for {}", id));
+            }
+
+            // One byte more than the limit to tell a full input from a truncated one
+            let mut input = String::new();
+            if let Err(e) = body.take(MAX_INPUT_BYTES + 1).read_to_string(&mut input) {
+                return error(400, format!("Could not read the input: {}", e));
+            }
+            if input.len() as u64 > MAX_INPUT_BYTES {
+                return error(
+                    413,
+                    format!("The input is larger than {} bytes", MAX_INPUT_BYTES),
+                );
+            }
+            solve(registry, id, part, &input)
+        }
+        (_, [_, "day", _, "part", _]) => error(405, "Use POST with the input as body"),
+        _ => error(404, "Unknown route"),
+    }
+}
+
+fn solve(registry: &Registry, id: PuzzleId, part: Part, input: &str) -> (u16, Value) {
+    let start = Instant::now();
+    // The solvers panic on some malformed inputs, which must not bring the server down
+    let answer = aocstd::catch_panic(|| registry.solve(id, part, input));
+    let elapsed = start.elapsed();
+
+    match answer {
+        Ok(answer) => (
+            200,
+            json!({
+                "year": id.year,
+                "day": id.day,
+                "part": part as u8 + 1,
+                "answer": answer.to_string(),
+                "elapsed_us": elapsed.as_micros() as u64,
+            }),
+        ),
+        Err(e) => error(400, e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_handle() {
+        aocstd::init_tests();
+        let registry = aoc2023::registry();
+
+        let (status, response) = handle(
+            &registry,
+            &Method::Post,
+            "/2023/day/1/part/1",
+            &b"1abc2"[..],
+        );
+        assert_eq!(status, 200);
+        assert_eq!(response["answer"], "12");
+        assert_eq!(response["part"], 1);
+
+        let (status, response) = handle(&registry, &Method::Get, "/2023", std::io::empty());
+        assert_eq!(status, 200);
+        assert_eq!(
+            response["days"].as_array().unwrap().len(),
+            registry.days_of(2023).count()
+        );
+
+        let (status, _) = handle(
+            &registry,
+            &Method::Get,
+            "/2023/day/1/part/1",
+            std::io::empty(),
+        );
+        assert_eq!(status, 405);
+        let (status, _) = handle(
+            &registry,
+            &Method::Post,
+            "/2023/day/1/part/3",
+            std::io::empty(),
+        );
+        assert_eq!(status, 404);
+        let (status, response) = handle(
+            &registry,
+            &Method::Post,
+            "/1999/day/1/part/1",
+            std::io::empty(),
+        );
+        assert_eq!(status, 404);
+        assert!(response["error"].as_str().unwrap().starts_with("No solver"));
+
+        // The body is not read past the limit
+        let huge = std::io::repeat(b'1');
+        let (status, response) = handle(&registry, &Method::Post, "/2023/day/1/part/1", huge);
+        assert_eq!(status, 413);
+        assert!(response["error"].as_str().unwrap().contains("larger than"));
+    }
+}
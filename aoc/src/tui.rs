@@ -0,0 +1,296 @@
+//! Terminal dashboard running every day of a year, with their answers, runtimes and check status.
+//!
+//! Keys: arrows/hjkl to move, `r` re-run the selected part, `a` run everything, `o` open the
+//! input of the selected day in $EDITOR, `q` to quit.
+use aocstd::registry::{PuzzleId, Registry};
+use aocstd::{Part, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Gauge, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+const PARTS: [Part; 2] = [Part::Part1, Part::Part2];
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+enum SolveState {
+    Running(Instant),
+    Done {
+        answer: std::result::Result<String, String>,
+        elapsed: Duration,
+    },
+}
+
+struct SolveResult {
+    id: PuzzleId,
+    part: Part,
+    answer: std::result::Result<String, String>,
+    elapsed: Duration,
+}
+
+struct Dashboard<'a> {
+    registry: &'a Registry,
+    days: Vec<PuzzleId>,
+    states: HashMap<(PuzzleId, Part), SolveState>,
+    expected: HashMap<(PuzzleId, Part), String>,
+    selected_day: usize,
+    selected_part: usize,
+    /// Number of solves started and finished since the last time everything was idle
+    batch: (usize, usize),
+    sender: Sender<SolveResult>,
+    receiver: Receiver<SolveResult>,
+}
+
+pub fn run(registry: &Registry, year: u16) -> Result<()> {
+    let mut terminal = ratatui::init();
+    let result = Dashboard::new(registry, year).event_loop(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl<'a> Dashboard<'a> {
+    fn new(registry: &'a Registry, year: u16) -> Self {
+        let days: Vec<PuzzleId> = registry.days_of(year).collect();
+        let mut expected = HashMap::new();
+        for id in &days {
+            for (part, answer) in PARTS.iter().zip(read_expected_answers(*id)) {
+                expected.insert((*id, *part), answer);
+            }
+        }
+        let (sender, receiver) = channel();
+        Dashboard {
+            registry,
+            days,
+            states: HashMap::new(),
+            expected,
+            selected_day: 0,
+            selected_part: 0,
+            batch: (0, 0),
+            sender,
+            receiver,
+        }
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        self.run_all();
+        loop {
+            while let Ok(result) = self.receiver.try_recv() {
+                self.batch.1 += 1;
+                self.states.insert(
+                    (result.id, result.part),
+                    SolveState::Done {
+                        answer: result.answer,
+                        elapsed: result.elapsed,
+                    },
+                );
+            }
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if !event::poll(Duration::from_millis(100))? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.selected_day = self.selected_day.saturating_sub(1)
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.selected_day = (self.selected_day + 1).min(self.days.len().max(1) - 1)
+                }
+                KeyCode::Left | KeyCode::Char('h') => self.selected_part = 0,
+                KeyCode::Right | KeyCode::Char('l') => self.selected_part = 1,
+                KeyCode::Char('r') | KeyCode::Enter => {
+                    if let Some(id) = self.days.get(self.selected_day).copied() {
+                        self.start(id, PARTS[self.selected_part]);
+                    }
+                }
+                KeyCode::Char('a') => self.run_all(),
+                KeyCode::Char('o') => {
+                    if let Some(id) = self.days.get(self.selected_day) {
+                        ratatui::restore();
+                        open_in_editor(*id);
+                        *terminal = ratatui::init();
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn run_all(&mut self) {
+        for id in self.days.clone() {
+            for part in PARTS {
+                self.start(id, part);
+            }
+        }
+    }
+
+    /// Solve a part in a background thread, the result is collected by the event loop
+    fn start(&mut self, id: PuzzleId, part: Part) {
+        if matches!(self.states.get(&(id, part)), Some(SolveState::Running(_))) {
+            return;
+        }
+        if self.batch.0 == self.batch.1 {
+            self.batch = (0, 0);
+        }
+        self.batch.0 += 1;
+        self.states
+            .insert((id, part), SolveState::Running(Instant::now()));
+
+        let solver = self.registry.get(id).map(|solver| solver.part(part));
+        let sender = self.sender.clone();
+        std::thread::spawn(move || {
+            let start = Instant::now();
+            let answer = match (solver, std::fs::read_to_string(id.input_path())) {
+                (None, _) => Err(String::from("no solver")),
+                (_, Err(e)) => Err(format!("no input: {}", e)),
+                (Some(solver), Ok(input)) => aocstd::catch_panic(|| solver(&input))
+                    .map(|answer| answer.to_string())
+                    .map_err(|e| e.to_string()),
+            };
+            // The dashboard may be closed before the end of a long solve
+            let _ = sender.send(SolveResult {
+                id,
+                part,
+                answer,
+                elapsed: start.elapsed(),
+            });
+        });
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [table_area, gauge_area, help_area] = Layout::vertical([
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let header = Row::new(["Day", "Part 1", "Time", "Part 2", "Time"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        let rows = self.days.iter().enumerate().map(|(day_index, id)| {
+            let mut cells = vec![Cell::from(format!("{:02}", id.day))];
+            for (part_index, part) in PARTS.iter().enumerate() {
+                let (answer, time) = self.cells(*id, *part);
+                let answer = if (day_index, part_index) == (self.selected_day, self.selected_part) {
+                    answer.reversed()
+                } else {
+                    answer
+                };
+                cells.push(answer);
+                cells.push(time);
+            }
+            Row::new(cells)
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(4),
+                Constraint::Percentage(35),
+                Constraint::Length(12),
+                Constraint::Percentage(35),
+                Constraint::Length(12),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" Advent of Code {} ", self.year())),
+        );
+        frame.render_widget(table, table_area);
+
+        let (started, finished) = self.batch;
+        let ratio = if started == 0 {
+            1.0
+        } else {
+            finished as f64 / started as f64
+        };
+        let gauge = Gauge::default()
+            .block(Block::default().borders(Borders::ALL).title(" Progress "))
+            .gauge_style(Style::default().fg(Color::Green))
+            .ratio(ratio)
+            .label(format!("{}/{} solves", finished, started));
+        frame.render_widget(gauge, gauge_area);
+
+        let help = Paragraph::new(Line::from(vec![Span::raw(
+            "arrows: move  r: re-run  a: run all  o: open input  q: quit",
+        )]))
+        .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(help, help_area);
+    }
+
+    fn year(&self) -> u16 {
+        self.days.first().map(|id| id.year).unwrap_or_default()
+    }
+
+    /// The answer cell (colored with the check status) and the runtime cell of a part
+    fn cells(&self, id: PuzzleId, part: Part) -> (Cell<'static>, Cell<'static>) {
+        match self.states.get(&(id, part)) {
+            None => (Cell::from("-"), Cell::from("")),
+            Some(SolveState::Running(started)) => {
+                let elapsed = started.elapsed();
+                let spinner = SPINNER[(elapsed.as_millis() / 100) as usize % SPINNER.len()];
+                (
+                    Cell::from(format!("{} running", spinner))
+                        .style(Style::default().fg(Color::Yellow)),
+                    Cell::from(format!("{:.1?}", elapsed)),
+                )
+            }
+            Some(SolveState::Done { answer, elapsed }) => {
+                let time = Cell::from(format!("{:.1?}", elapsed));
+                let answer = match answer {
+                    Err(e) => {
+                        Cell::from(format!("error: {}", e)).style(Style::default().fg(Color::Red))
+                    }
+                    Ok(answer) => match self.expected.get(&(id, part)) {
+                        Some(expected) if expected == answer => Cell::from(format!("{} ✓", answer))
+                            .style(Style::default().fg(Color::Green)),
+                        Some(expected) => {
+                            Cell::from(format!("{} ✗ (expected {})", answer, expected))
+                                .style(Style::default().fg(Color::Red))
+                        }
+                        None => Cell::from(answer.clone()),
+                    },
+                };
+                (answer, time)
+            }
+        }
+    }
+}
+
+/// The known answers of a day are stored next to its input, one part per line
+/// (ex: day05/answers-day05.txt), the day is unchecked when the file does not exist
+fn read_expected_answers(id: PuzzleId) -> Vec<String> {
+    let path = id
+        .input_path()
+        .with_file_name(format!("answers-day{:02}.txt", id.day));
+    std::fs::read_to_string(path)
+        .map(|answers| {
+            answers
+                .lines()
+                .map(|line| line.trim().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn open_in_editor(id: PuzzleId) {
+    let editor = std::env::var("EDITOR").unwrap_or_else(|_| String::from("vi"));
+    if let Err(error) = std::process::Command::new(&editor)
+        .arg(id.input_path())
+        .status()
+    {
+        log::warn!("Could not run {}: {}", editor, error);
+    }
+}
@@ -0,0 +1,104 @@
+//! Advent of Code 2023 solutions behind a single dependency.
+//!
+//! Every day is an optional feature (all enabled by default) exposing `part1` and `part2`:
+//!
+//! ```no_run
+//! let input = std::fs::read_to_string("input-day05.txt").unwrap();
+//! let answer = aoc2023::day05::part2(&input).unwrap();
+//! println!("{}", answer);
+//! ```
+
+pub use aocstd::registry::{PuzzleId, Registry};
+pub use aocstd::{Answer, Error, Part, Result};
+
+/// The year of every solution of this crate, used as the first part of the registry keys
+pub const YEAR: u16 = 2023;
+
+/// A module per day behind its feature, forwarding to the solutions of the day crate, and
+/// `register` adding the enabled ones to a registry
+macro_rules! days {
+    ($($day:literal, $feature:literal => $krate:ident::$module:ident,)*) => {
+        $(
+            #[cfg(feature = $feature)]
+            pub mod $krate {
+                use aocstd::{Answer, Result};
+
+                pub fn part1(input: &str) -> Result<Answer> {
+                    ::$krate::$module::solve_part1(input)
+                }
+
+                pub fn part2(input: &str) -> Result<Answer> {
+                    ::$krate::$module::solve_part2(input)
+                }
+
+                pub fn parse_only(input: &str) -> Result<String> {
+                    ::$krate::$module::parse_only(input)
+                }
+            }
+        )*
+
+        /// Register every enabled day into a registry shared with the other years
+        pub fn register(registry: &mut Registry) {
+            $(
+                #[cfg(feature = $feature)]
+                registry
+                    .register(PuzzleId::new(YEAR, $day), $krate::part1, $krate::part2)
+                    .with_parser($krate::parse_only);
+            )*
+        }
+    };
+}
+
+days! {
+    1, "day01" => day01::trebuchet,
+    2, "day02" => day02::cube_conundrum,
+    3, "day03" => day03::gear_ratios,
+    4, "day04" => day04::scratchcards,
+    5, "day05" => day05::giveaseedafertilizer,
+    6, "day06" => day06::waitforit,
+    7, "day07" => day07::camel_cards,
+    8, "day08" => day08::haunted_wasteland,
+    9, "day09" => day09::mirage_maintenance,
+    10, "day10" => day10::pipe_maze,
+    11, "day11" => day11::cosmic_expansion,
+    12, "day12" => day12::hot_springs,
+    13, "day13" => day13::point_of_incidence,
+    14, "day14" => day14::parabolic_reflector_dish,
+    15, "day15" => day15::lens_library,
+    16, "day16" => day16::floor_will_be_lava,
+    17, "day17" => day17::clumsy_crucible,
+    18, "day18" => day18::lavaduct_lagoon,
+    19, "day19" => day19::aplenty,
+    20, "day20" => day20::pulse_propagation,
+    21, "day21" => day21::step_counter,
+    22, "day22" => day22::sand_slabs,
+    23, "day23" => day23::a_long_walk,
+    24, "day24" => day24::never_tell_me_the_odds,
+    25, "day25" => day25::snowverload,
+}
+
+/// A registry containing only the 2023 days
+pub fn registry() -> Registry {
+    let mut registry = Registry::new();
+    register(&mut registry);
+    registry
+}
+
+/// Solve a part of a day, failing if the day is unknown or its feature is disabled
+pub fn solve(day: u8, part: Part, input: &str) -> Result<Answer> {
+    registry().solve(PuzzleId::new(YEAR, day), part, input)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_solve_dispatch() {
+        aocstd::init_tests();
+
+        let answer = solve(1, Part::Part1, "1abc2\npqr3stu8vwx").unwrap();
+        assert_eq!(answer, Answer::Unsigned(50));
+        assert!(solve(26, Part::Part1, "").is_err());
+    }
+}
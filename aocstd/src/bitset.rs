@@ -0,0 +1,131 @@
+//! A set of small integers stored as bits, for the visited tracking of the searches where hashing
+//! every point is the bottleneck (number the cells with `y * width + x`)
+
+/// Grows to the largest value inserted, one bit per value below it
+#[derive(Debug, Clone, Default)]
+pub struct BitSet {
+    blocks: Vec<u64>,
+}
+
+const BITS: usize = u64::BITS as usize;
+
+impl BitSet {
+    pub fn new() -> Self {
+        BitSet::default()
+    }
+
+    /// Room for the values below `bits` without growing
+    pub fn with_capacity(bits: usize) -> Self {
+        BitSet {
+            blocks: Vec::with_capacity(bits.div_ceil(BITS)),
+        }
+    }
+
+    /// Returns whether the value was not in the set yet
+    pub fn insert(&mut self, value: usize) -> bool {
+        let (block, mask) = (value / BITS, 1 << (value % BITS));
+        if block >= self.blocks.len() {
+            self.blocks.resize(block + 1, 0);
+        }
+        let added = self.blocks[block] & mask == 0;
+        self.blocks[block] |= mask;
+        added
+    }
+
+    /// Returns whether the value was in the set
+    pub fn remove(&mut self, value: usize) -> bool {
+        let (block, mask) = (value / BITS, 1 << (value % BITS));
+        match self.blocks.get_mut(block) {
+            Some(bits) if *bits & mask != 0 => {
+                *bits &= !mask;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        self.blocks
+            .get(value / BITS)
+            .is_some_and(|bits| bits & (1 << (value % BITS)) != 0)
+    }
+
+    /// Number of values in the set
+    pub fn len(&self) -> usize {
+        self.blocks
+            .iter()
+            .map(|bits| bits.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.iter().all(|bits| *bits == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+    }
+
+    /// The values in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.blocks.iter().enumerate().flat_map(|(block, bits)| {
+            let mut bits = *bits;
+            std::iter::from_fn(move || {
+                if bits == 0 {
+                    return None;
+                }
+                let bit = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                Some(block * BITS + bit)
+            })
+        })
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(values: I) -> Self {
+        let mut set = BitSet::new();
+        set.extend(values);
+        set
+    }
+}
+
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, values: I) {
+        for value in values {
+            self.insert(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bitset() {
+        crate::init_tests();
+
+        let mut set = BitSet::with_capacity(100);
+        assert!(set.is_empty());
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        assert!(set.insert(64));
+        assert!(set.insert(1000));
+        assert!(set.contains(64));
+        assert!(!set.contains(65));
+        assert!(!set.contains(1_000_000));
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![3, 64, 1000]);
+
+        assert!(set.remove(1000));
+        assert!(!set.remove(1000));
+        assert!(!set.remove(5000));
+        assert_eq!(set.len(), 2);
+        set.clear();
+        assert!(set.is_empty());
+
+        let set: BitSet = [63, 0, 127, 128].into_iter().collect();
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 63, 127, 128]);
+    }
+}
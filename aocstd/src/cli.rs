@@ -0,0 +1,235 @@
+use crate::grid::Grid;
+use crate::parallel;
+use crate::profile;
+use crate::repl::{self, Queryable};
+use crate::trace;
+use crate::viz::{Frame, ImageRenderer, Renderer};
+use crate::{init_logger_with_verbosity, Answer, Error, Part, Result};
+use clap::Parser;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+pub struct Cli {
+    #[arg(value_enum)]
+    pub part: Part,
+    #[arg(short, long)]
+    pub input_file: Option<String>,
+    #[arg(short, long)]
+    pub verbose: bool,
+    /// Parse the input and query the parsed structures instead of solving
+    #[arg(long)]
+    pub repl: bool,
+    /// Format of the input, json reads the structured form written by --dump-parsed
+    #[arg(long, value_enum, default_value_t = InputFormat::Text)]
+    pub input_format: InputFormat,
+    /// Write the parsed input as JSON to this file instead of solving
+    #[arg(long)]
+    pub dump_parsed: Option<PathBuf>,
+    /// Number of threads of the solvers that run in parallel, every core by default
+    #[arg(long)]
+    pub threads: Option<usize>,
+    /// Write the intermediate steps of the solver to this file, as JSON Lines
+    #[arg(long)]
+    pub trace: Option<PathBuf>,
+    /// Sample the CPU during the solve and write a flamegraph SVG to this file (needs the profile
+    /// feature)
+    #[arg(long)]
+    pub profile: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum InputFormat {
+    Text,
+    Json,
+}
+
+pub fn get_input_stream(cli: &Cli) -> Box<dyn BufRead> {
+    match &cli.input_file {
+        Some(file_name) => {
+            let f = File::open(file_name).expect("Could not open input file");
+            Box::new(BufReader::new(f))
+        }
+        None => Box::new(BufReader::new(std::io::stdin())),
+    }
+}
+
+/// The whole input, from the file given with --input-file or from stdin. Exits when it cannot be
+/// read
+pub fn get_input_string(cli: &Cli) -> String {
+    use std::io::Read;
+
+    let mut input = String::new();
+    if let Err(error) = get_input_stream(cli).read_to_string(&mut input) {
+        log::error!("Could not read the input: {}", error);
+        std::process::exit(1);
+    }
+    input
+}
+
+/// The lines of the input without their line ending, for the days that keep them around
+pub fn get_input_lines(cli: &Cli) -> Vec<String> {
+    crate::input::lines(&get_input_string(cli))
+        .map(String::from)
+        .collect()
+}
+
+/// One cell per character of the input, the lines shorter than the longest one padded with
+/// spaces
+pub fn get_input_grid(cli: &Cli) -> Grid<char> {
+    Grid::from_lines(&get_input_string(cli), ' ', |c| c)
+}
+
+/// Log the answer of a solver, or the error and exit with a failure status
+pub fn report_answer(part: Part, answer: Result<Answer>) {
+    trace::close();
+    finish_profile();
+    match answer {
+        Ok(answer) => log::info!("{}: {}", part, answer),
+        Err(error) => {
+            log::error!("{}: {}", part, error);
+            std::process::exit(1);
+        }
+    }
+}
+
+pub fn init_logger(cli: &Cli) {
+    init_logger_with_verbosity(cli.verbose);
+}
+
+/// Size the thread pool of the parallel solvers with --threads, if given
+pub fn init_threads(cli: &Cli) {
+    if let Some(threads) = cli.threads {
+        if let Err(error) = parallel::set_threads(threads) {
+            log::error!("{}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Open the trace file given with --trace, if any, it is closed by `report_answer`
+pub fn init_trace(cli: &Cli) {
+    if let Some(path) = &cli.trace {
+        match File::create(path) {
+            Ok(file) => trace::set_sink(BufWriter::new(file)),
+            Err(error) => {
+                log::error!("Could not create {}: {}", path.display(), error);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+/// Start the profiler asked with --profile, if any, the flamegraph is written by `report_answer`
+pub fn init_profile(cli: &Cli) {
+    if let Some(path) = &cli.profile {
+        if let Err(error) = profile::start(path) {
+            log::error!("{}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn finish_profile() {
+    match profile::finish() {
+        Ok(Some(path)) => log::info!("Flamegraph written to {}", path.display()),
+        Ok(None) => (),
+        Err(error) => log::error!("{}", error),
+    }
+}
+
+/// Run the REPL of a day over its parsed input, the input must come from a file since stdin is
+/// used for the queries
+pub fn start_repl(cli: &Cli, queryable: Result<Box<dyn Queryable>>) {
+    if cli.input_file.is_none() {
+        log::error!("The REPL reads the queries from stdin, use --input-file for the input");
+        std::process::exit(1);
+    }
+    let result = queryable.and_then(|queryable| {
+        repl::run(
+            queryable.as_ref(),
+            std::io::stdin().lock(),
+            std::io::stdout(),
+        )
+    });
+    if let Err(error) = result {
+        log::error!("{}", error);
+        std::process::exit(1);
+    }
+}
+
+/// Print the rendering of a day for --visualize or --explain, or the error and exit with a
+/// failure status
+pub fn print_visualization(visualization: Result<String>) {
+    match visualization {
+        Ok(visualization) => println!("{}", visualization),
+        Err(error) => {
+            log::error!("{}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Write the SVG rendering of a day for --svg, or log the error and exit with a failure status
+pub fn write_svg(path: &Path, svg: Result<String>) {
+    write_document("SVG", path, svg)
+}
+
+/// Write the HTML page of a day for --export-html, or log the error and exit with a failure
+/// status
+pub fn write_html(path: &Path, html: Result<String>) {
+    write_document("HTML page", path, html)
+}
+
+/// Write the DOT graph of a day for --dot, or log the error and exit with a failure status
+pub fn write_dot(path: &Path, dot: Result<String>) {
+    write_document("DOT graph", path, dot)
+}
+
+fn write_document(kind: &str, path: &Path, document: Result<String>) {
+    match document.and_then(|document| Ok(std::fs::write(path, document)?)) {
+        Ok(()) => log::info!("{} written to {}", kind, path.display()),
+        Err(error) => {
+            log::error!("{}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Write the frames of a day for --image, or log the error and exit with a failure status
+pub fn write_image(path: &Path, frames: Result<Vec<Frame>>) {
+    let result = frames.and_then(|frames| {
+        let mut renderer = ImageRenderer::new(path);
+        for frame in &frames {
+            renderer.submit(frame)?;
+        }
+        renderer.finish()
+    });
+    match result {
+        Ok(()) => log::info!("Image written to {}", path.display()),
+        Err(error) => {
+            log::error!("{}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Write the structured form of an input as JSON, for the external tools that do not want to
+/// parse the text themselves
+pub fn dump_parsed<T: serde::Serialize>(path: &Path, parsed: Result<T>) {
+    let result = parsed.and_then(|parsed| {
+        let json = serde_json::to_string_pretty(&parsed)
+            .map_err(|e| Error::new(format!("Could not serialize the parsed input: {}", e)))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    });
+    match result {
+        Ok(()) => log::info!("Parsed input written to {}", path.display()),
+        Err(error) => {
+            log::error!("{}", error);
+            std::process::exit(1);
+        }
+    }
+}
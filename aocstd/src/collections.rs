@@ -0,0 +1,169 @@
+//! Containers missing from std for the puzzles
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::collections::hash_map;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A multiset, how many times each item was seen (ex: the cards of a hand, the cubes of a set).
+/// The items are never stored with a count of 0
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent, bound(deserialize = "T: Deserialize<'de> + Eq + Hash"))]
+pub struct Counter<T> {
+    counts: HashMap<T, u64>,
+}
+
+impl<T: Eq + Hash> Counter<T> {
+    pub fn new() -> Self {
+        Counter {
+            counts: HashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, item: T, count: u64) {
+        if count > 0 {
+            *self.counts.entry(item).or_insert(0) += count;
+        }
+    }
+
+    /// 0 for the items never added
+    pub fn get<Q>(&self, item: &Q) -> u64
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    /// Sum of the counts
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// Number of distinct items
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&T, u64)> {
+        self.counts.iter().map(|(item, count)| (item, *count))
+    }
+
+    /// The items from the most to the least seen, the ties in any order
+    pub fn most_common(&self) -> Vec<(&T, u64)> {
+        let mut items: Vec<(&T, u64)> = self.iter().collect();
+        items.sort_by(|(_, a), (_, b)| b.cmp(a));
+        items
+    }
+
+    /// Add the counts of the other counter to this one
+    pub fn merge(&mut self, other: &Counter<T>)
+    where
+        T: Clone,
+    {
+        for (item, count) in other.iter() {
+            self.add(item.clone(), count);
+        }
+    }
+
+    /// Keep the larger count of every item of both counters (the union of the multisets)
+    pub fn merge_max(&mut self, other: &Counter<T>)
+    where
+        T: Clone,
+    {
+        for (item, count) in other.iter() {
+            let current = self.counts.entry(item.clone()).or_insert(0);
+            *current = (*current).max(count);
+        }
+    }
+}
+
+impl<T: Eq + Hash> PartialEq for Counter<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.counts == other.counts
+    }
+}
+
+impl<T: Eq + Hash> Eq for Counter<T> {}
+
+impl<T: Eq + Hash> Default for Counter<T> {
+    fn default() -> Self {
+        Counter::new()
+    }
+}
+
+/// Each item counts once
+impl<T: Eq + Hash> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(items: I) -> Self {
+        let mut counter = Counter::new();
+        counter.extend(items);
+        counter
+    }
+}
+
+impl<T: Eq + Hash> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        for item in items {
+            self.add(item, 1);
+        }
+    }
+}
+
+impl<T> IntoIterator for Counter<T> {
+    type Item = (T, u64);
+    type IntoIter = hash_map::IntoIter<T, u64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_counter() {
+        crate::init_tests();
+
+        let hand: Counter<char> = "32T3K".chars().collect();
+        assert_eq!(hand.get(&'3'), 2);
+        assert_eq!(hand.get(&'A'), 0);
+        assert_eq!(hand.len(), 4);
+        assert_eq!(hand.total(), 5);
+        let most_common = hand.most_common();
+        assert_eq!(most_common[0], (&'3', 2));
+        assert!(most_common[1..].iter().all(|(_, count)| *count == 1));
+
+        let mut first = Counter::new();
+        first.add(String::from("red"), 4);
+        first.add(String::from("blue"), 3);
+        first.add(String::from("green"), 0);
+        assert_eq!(first.len(), 2);
+        let mut second = Counter::new();
+        second.add(String::from("red"), 1);
+        second.add(String::from("green"), 2);
+
+        let mut maxima = first.clone();
+        maxima.merge_max(&second);
+        assert_eq!(
+            ["red", "green", "blue"].map(|color| maxima.get(color)),
+            [4, 2, 3]
+        );
+        first.merge(&second);
+        assert_eq!(
+            ["red", "green", "blue"].map(|color| first.get(color)),
+            [5, 2, 3]
+        );
+
+        let json = serde_json::to_string(&second).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Counter<String>>(&json).unwrap(),
+            second
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! Lazy enumerations of the subsets and orderings of a slice, for the brute force puzzles. Only the
+//! current indexes are kept, the items are given back by reference in the order of the slice
+use std::iter::FusedIterator;
+
+/// The k-combinations of the items, in lexicographic order of their positions
+pub fn combinations<T>(items: &[T], k: usize) -> Combinations<'_, T> {
+    Combinations {
+        items,
+        indexes: (0..k).collect(),
+        done: k > items.len(),
+    }
+}
+
+/// Every pair of distinct items (the 2-combinations without the Vec), ex: the pairs of galaxies
+pub fn pairs<T>(items: &[T]) -> impl Iterator<Item = (&T, &T)> {
+    items.iter().enumerate().flat_map(move |(index, first)| {
+        items[index + 1..].iter().map(move |second| (first, second))
+    })
+}
+
+/// Every ordering of the items, in lexicographic order of their positions
+pub fn permutations<T>(items: &[T]) -> Permutations<'_, T> {
+    Permutations {
+        items,
+        indexes: (0..items.len()).collect(),
+        done: false,
+    }
+}
+
+pub struct Combinations<'a, T> {
+    items: &'a [T],
+    indexes: Vec<usize>,
+    done: bool,
+}
+
+impl<'a, T> Iterator for Combinations<'a, T> {
+    type Item = Vec<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let combination = self
+            .indexes
+            .iter()
+            .map(|index| &self.items[*index])
+            .collect();
+        // Move the last index which can still move, and pack the next ones right after it
+        let (n, k) = (self.items.len(), self.indexes.len());
+        match (0..k).rev().find(|i| self.indexes[*i] < n - k + i) {
+            Some(i) => {
+                self.indexes[i] += 1;
+                for j in i + 1..k {
+                    self.indexes[j] = self.indexes[j - 1] + 1;
+                }
+            }
+            None => self.done = true,
+        }
+        Some(combination)
+    }
+}
+
+impl<T> FusedIterator for Combinations<'_, T> {}
+
+pub struct Permutations<'a, T> {
+    items: &'a [T],
+    indexes: Vec<usize>,
+    done: bool,
+}
+
+impl<'a, T> Iterator for Permutations<'a, T> {
+    type Item = Vec<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let permutation = self
+            .indexes
+            .iter()
+            .map(|index| &self.items[*index])
+            .collect();
+        // The next permutation: swap the last ascent with the smallest greater index after it,
+        // then reverse the tail
+        let indexes = &mut self.indexes;
+        match (1..indexes.len())
+            .rev()
+            .find(|i| indexes[i - 1] < indexes[*i])
+        {
+            Some(i) => {
+                let pivot = i - 1;
+                let greater = (i..indexes.len())
+                    .rev()
+                    .find(|j| indexes[*j] > indexes[pivot])
+                    .unwrap();
+                indexes.swap(pivot, greater);
+                indexes[i..].reverse();
+            }
+            None => self.done = true,
+        }
+        Some(permutation)
+    }
+}
+
+impl<T> FusedIterator for Permutations<'_, T> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_combinations() {
+        crate::init_tests();
+
+        let items = ['a', 'b', 'c', 'd'];
+        let as_strings = |iterator: Combinations<char>| -> Vec<String> {
+            iterator.map(|items| items.into_iter().collect()).collect()
+        };
+        assert_eq!(
+            as_strings(combinations(&items, 2)),
+            vec!["ab", "ac", "ad", "bc", "bd", "cd"]
+        );
+        assert_eq!(as_strings(combinations(&items, 4)), vec!["abcd"]);
+        assert_eq!(as_strings(combinations(&items, 0)), vec![""]);
+        assert!(as_strings(combinations(&items, 5)).is_empty());
+        assert_eq!(combinations(&[0; 10], 3).count(), 120);
+
+        let pairs: Vec<(&char, &char)> = pairs(&items).collect();
+        assert_eq!(pairs.len(), 6);
+        assert_eq!(pairs[0], (&'a', &'b'));
+        assert_eq!(pairs[5], (&'c', &'d'));
+    }
+
+    #[test]
+    fn test_permutations() {
+        crate::init_tests();
+
+        let orderings: Vec<String> = permutations(&['a', 'b', 'c'])
+            .map(|items| items.into_iter().collect())
+            .collect();
+        assert_eq!(orderings, vec!["abc", "acb", "bac", "bca", "cab", "cba"]);
+        assert_eq!(permutations(&[0; 6]).count(), 720);
+        assert_eq!(permutations::<u8>(&[]).count(), 1);
+        // Lazy, the first ones come without going through the 20! others
+        assert_eq!(permutations(&[0; 20]).take(3).count(), 3);
+    }
+}
@@ -0,0 +1,180 @@
+//! Cycle detection for states iterated by a function, to skip a billion steps of a simulation once
+//! it starts repeating itself. The states are numbered from the initial one (step 0)
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The states from step `start` repeat every `length` steps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: u64,
+    pub length: u64,
+}
+
+impl Cycle {
+    /// The first step reaching the same state as `step`
+    pub fn reduce(&self, step: u64) -> u64 {
+        if step < self.start {
+            step
+        } else {
+            self.start + (step - self.start) % self.length
+        }
+    }
+}
+
+/// Floyd's tortoise and hare, only two states are kept but every step is computed three times
+pub fn floyd<S: Clone + PartialEq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // Find a meeting point, the hare moves twice as fast
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let next = step(&hare);
+        hare = step(&next);
+    }
+    // The meeting point is a multiple of the length away from the start: restart the tortoise
+    // and move both at the same speed until they meet at the start of the cycle
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Brent's algorithm, keeps two states like Floyd's but computes fewer steps
+pub fn brent<S: Clone + PartialEq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // Find the length by teleporting the tortoise to the hare on every power of two
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+    // Then move two states a length apart until they meet at the start of the cycle
+    let mut start = 0;
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, length }
+}
+
+/// The state after `steps` steps, stepping only until a state repeats. The states are compared
+/// through `key`, a snapshot of what matters for the next steps (ex: the positions of the rocks
+/// without the counters of the load), every state is kept until the cycle is found
+pub fn fast_forward<S, K: Eq + Hash>(
+    initial: S,
+    steps: u64,
+    mut step: impl FnMut(&S) -> S,
+    mut key: impl FnMut(&S) -> K,
+) -> S {
+    let mut seen = HashMap::new();
+    let mut states = vec![initial];
+    loop {
+        let current = states.len() as u64 - 1;
+        if current == steps {
+            return states.pop().unwrap();
+        }
+        let last = states.last().unwrap();
+        if let Some(start) = seen.insert(key(last), current) {
+            let cycle = Cycle {
+                start,
+                length: current - start,
+            };
+            log::debug!("Found {:?} after {} steps", cycle, current);
+            return states.swap_remove(cycle.reduce(steps) as usize);
+        }
+        let next = step(last);
+        states.push(next);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// 0, 1, ..., 5 then 6..13 forever
+    fn step(value: &u64) -> u64 {
+        if *value == 12 {
+            6
+        } else {
+            value + 1
+        }
+    }
+
+    #[test]
+    fn test_detection() {
+        crate::init_tests();
+
+        let expected = Cycle {
+            start: 6,
+            length: 7,
+        };
+        assert_eq!(floyd(0, step), expected);
+        assert_eq!(brent(0, step), expected);
+        // Already in the cycle
+        let looping = Cycle {
+            start: 0,
+            length: 7,
+        };
+        assert_eq!(floyd(8, step), looping);
+        assert_eq!(brent(8, step), looping);
+        // A fixed point
+        let fixed = Cycle {
+            start: 3,
+            length: 1,
+        };
+        assert_eq!(floyd(0, |value: &u64| (value + 1).min(3)), fixed);
+        assert_eq!(brent(0, |value: &u64| (value + 1).min(3)), fixed);
+
+        assert_eq!(expected.reduce(4), 4);
+        assert_eq!(expected.reduce(13), 6);
+        assert_eq!(expected.reduce(1_000_000_001), 7);
+    }
+
+    #[test]
+    fn test_fast_forward() {
+        crate::init_tests();
+
+        let naive = |steps: u64| (0..steps).fold(0, |value, _| step(&value));
+        for steps in [0, 3, 6, 12, 13, 100, 1234] {
+            assert_eq!(fast_forward(0, steps, step, |value| *value), naive(steps));
+        }
+        // 1_000_000_000 - 6 is a multiple of 7
+        let billion = naive(6);
+        assert_eq!(
+            fast_forward(0, 1_000_000_000, step, |value| *value),
+            billion
+        );
+
+        // The key ignores a counter which keeps growing
+        let counted = fast_forward(
+            (0, 0),
+            1_000_000_000,
+            |(value, count): &(u64, u64)| (step(value), count + 1),
+            |(value, _)| *value,
+        );
+        assert_eq!(counted.0, billion);
+    }
+}
@@ -0,0 +1,405 @@
+//! Points of the 2D puzzles, signed so moving around never needs casts: the grids answer None
+//! for the points out of them instead. The 3D points are i128 since their cross products multiply
+//! coordinates already in the hundreds of trillions
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+/// A position, or an offset between two positions. y grows downwards like the lines of the input
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point::new(0, 0);
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Point { x, y }
+    }
+
+    pub fn manhattan_distance(self, other: Point) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The points sharing a side with this one, clockwise from the top
+    pub fn neighbors4(self) -> impl Iterator<Item = Point> {
+        Direction::CARDINALS
+            .into_iter()
+            .map(move |direction| self + direction)
+    }
+
+    /// The points sharing a side or a corner with this one, clockwise from the top
+    pub fn neighbors8(self) -> impl Iterator<Item = Point> {
+        Direction::ALL
+            .into_iter()
+            .map(move |direction| self + direction)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+/// Step one cell in a direction
+impl Add<Direction> for Point {
+    type Output = Point;
+
+    fn add(self, direction: Direction) -> Point {
+        self + direction.offset()
+    }
+}
+
+impl AddAssign<Direction> for Point {
+    fn add_assign(&mut self, direction: Direction) {
+        *self = *self + direction;
+    }
+}
+
+impl Mul<i64> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: i64) -> Point {
+        Point::new(self.x * factor, self.y * factor)
+    }
+}
+
+/// The directions on screen, North is up (towards the first line of the input)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    /// The directions sharing a side, clockwise from North
+    pub const CARDINALS: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// Every direction, clockwise from North
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    pub const fn offset(self) -> Point {
+        match self {
+            Direction::North => Point::new(0, -1),
+            Direction::NorthEast => Point::new(1, -1),
+            Direction::East => Point::new(1, 0),
+            Direction::SouthEast => Point::new(1, 1),
+            Direction::South => Point::new(0, 1),
+            Direction::SouthWest => Point::new(-1, 1),
+            Direction::West => Point::new(-1, 0),
+            Direction::NorthWest => Point::new(-1, -1),
+        }
+    }
+
+    /// Rotate by a number of eighths of a turn, clockwise when positive, the variants are
+    /// declared in the order of ALL
+    fn rotate(self, eighths: i32) -> Direction {
+        Direction::ALL[(self as i32 + eighths).rem_euclid(8) as usize]
+    }
+
+    /// A quarter turn counterclockwise
+    pub fn turn_left(self) -> Direction {
+        self.rotate(-2)
+    }
+
+    /// A quarter turn clockwise
+    pub fn turn_right(self) -> Direction {
+        self.rotate(2)
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.rotate(4)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        !Direction::CARDINALS.contains(&self)
+    }
+}
+
+impl Neg for Direction {
+    type Output = Direction;
+
+    fn neg(self) -> Direction {
+        self.opposite()
+    }
+}
+
+/// A position in space, or an offset between two positions (a velocity for example)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Point3 {
+    pub x: i128,
+    pub y: i128,
+    pub z: i128,
+}
+
+/// Same type as the points, for when the name reads better
+pub type Vec3 = Point3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    pub const ALL: [Axis; 3] = [Axis::X, Axis::Y, Axis::Z];
+}
+
+impl Point3 {
+    pub const ORIGIN: Point3 = Point3::new(0, 0, 0);
+
+    pub const fn new(x: i128, y: i128, z: i128) -> Self {
+        Point3 { x, y, z }
+    }
+
+    pub fn dot(self, other: Point3) -> i128 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// The vector perpendicular to both, zero when they are parallel
+    pub fn cross(self, other: Point3) -> Point3 {
+        Point3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn manhattan_distance(self, other: Point3) -> u128 {
+        Axis::ALL
+            .into_iter()
+            .map(|axis| self[axis].abs_diff(other[axis]))
+            .sum()
+    }
+
+    /// The coordinates in the order of the axes
+    pub fn coordinates(self) -> impl Iterator<Item = (Axis, i128)> {
+        Axis::ALL.into_iter().map(move |axis| (axis, self[axis]))
+    }
+}
+
+impl fmt::Display for Point3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+impl Index<Axis> for Point3 {
+    type Output = i128;
+
+    fn index(&self, axis: Axis) -> &i128 {
+        match axis {
+            Axis::X => &self.x,
+            Axis::Y => &self.y,
+            Axis::Z => &self.z,
+        }
+    }
+}
+
+impl IndexMut<Axis> for Point3 {
+    fn index_mut(&mut self, axis: Axis) -> &mut i128 {
+        match axis {
+            Axis::X => &mut self.x,
+            Axis::Y => &mut self.y,
+            Axis::Z => &mut self.z,
+        }
+    }
+}
+
+impl Add for Point3 {
+    type Output = Point3;
+
+    fn add(self, other: Point3) -> Point3 {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl AddAssign for Point3 {
+    fn add_assign(&mut self, other: Point3) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point3 {
+    type Output = Point3;
+
+    fn sub(self, other: Point3) -> Point3 {
+        Point3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl SubAssign for Point3 {
+    fn sub_assign(&mut self, other: Point3) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for Point3 {
+    type Output = Point3;
+
+    fn neg(self) -> Point3 {
+        Point3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl Mul<i128> for Point3 {
+    type Output = Point3;
+
+    fn mul(self, factor: i128) -> Point3 {
+        Point3::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_point() {
+        crate::init_tests();
+
+        let a = Point::new(1, 2);
+        let b = Point::new(-3, 5);
+        assert_eq!(a + b, Point::new(-2, 7));
+        assert_eq!(a - b, Point::new(4, -3));
+        assert_eq!(-a, Point::new(-1, -2));
+        assert_eq!(b * 2, Point::new(-6, 10));
+        assert_eq!(a.manhattan_distance(b), 7);
+        assert_eq!(a.manhattan_distance(a), 0);
+
+        let mut c = Point::ORIGIN;
+        c += a;
+        c -= b;
+        assert_eq!(c, a - b);
+
+        assert_eq!(
+            Point::ORIGIN.neighbors4().collect::<Vec<_>>(),
+            vec![
+                Point::new(0, -1),
+                Point::new(1, 0),
+                Point::new(0, 1),
+                Point::new(-1, 0)
+            ]
+        );
+        assert!(a.neighbors8().all(|other| other.manhattan_distance(a) <= 2));
+    }
+
+    #[test]
+    fn test_direction() {
+        crate::init_tests();
+
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Direction::NorthWest.turn_left(), Direction::SouthWest);
+        assert_eq!(-Direction::SouthEast, Direction::NorthWest);
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(
+                direction.offset() + direction.opposite().offset(),
+                Point::ORIGIN
+            );
+            assert_eq!(
+                direction.offset().manhattan_distance(Point::ORIGIN) == 2,
+                direction.is_diagonal()
+            );
+        }
+
+        let mut point = Point::ORIGIN;
+        point += Direction::South;
+        assert_eq!(point + Direction::East, Point::new(1, 1));
+    }
+
+    #[test]
+    fn test_point3() {
+        crate::init_tests();
+
+        let a = Point3::new(1, 2, 3);
+        let b = Point3::new(-4, 5, 6);
+        assert_eq!(a + b, Point3::new(-3, 7, 9));
+        assert_eq!(a - b, Point3::new(5, -3, -3));
+        assert_eq!(-a * 2, Point3::new(-2, -4, -6));
+        assert_eq!(a.dot(b), 24);
+        assert_eq!(a.cross(b), Point3::new(-3, -18, 13));
+        assert_eq!(a.cross(b).dot(a), 0);
+        assert_eq!(a.cross(a * 3), Vec3::ORIGIN);
+        assert_eq!(a.manhattan_distance(b), 11);
+        assert_eq!(a.to_string(), "(1, 2, 3)");
+
+        let mut c = a;
+        c[Axis::Z] -= 3;
+        c += b;
+        assert_eq!(
+            c.coordinates().collect::<Vec<_>>(),
+            vec![(Axis::X, -3), (Axis::Y, 7), (Axis::Z, 6)]
+        );
+
+        // Hailstone sized coordinates, the products overflow i64
+        let far = Point3::new(
+            400_000_000_000_000,
+            300_000_000_000_000,
+            200_000_000_000_000,
+        );
+        let other = Point3::new(1, 2, 3) * 100_000_000_000_000;
+        assert_eq!(far.cross(other).x, 5 * 10_i128.pow(28));
+    }
+}
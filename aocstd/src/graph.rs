@@ -0,0 +1,481 @@
+//! Graphs stored as adjacency lists, for the puzzles about the structure of the graph rather than
+//! a path through it (components, bridges, cuts). The nodes are numbered in the order they are
+//! added and the algorithms work on these indexes, `node` gives back the value of an index.
+//!
+//! The searches are iterative so the large inputs do not overflow the stack.
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    indexes: HashMap<N, usize>,
+    /// (from, to, weight), added once even in the undirected graphs
+    edges: Vec<(usize, usize, u64)>,
+    /// (neighbor, edge index) of every node
+    adjacency: Vec<Vec<(usize, usize)>>,
+    directed: bool,
+}
+
+impl<N: Clone + Eq + Hash> Graph<N> {
+    pub fn directed() -> Self {
+        Graph::new(true)
+    }
+
+    pub fn undirected() -> Self {
+        Graph::new(false)
+    }
+
+    fn new(directed: bool) -> Self {
+        Graph {
+            nodes: Vec::new(),
+            indexes: HashMap::new(),
+            edges: Vec::new(),
+            adjacency: Vec::new(),
+            directed,
+        }
+    }
+
+    /// The index of the node, added when it is new
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(index) = self.indexes.get(&node) {
+            return *index;
+        }
+        self.nodes.push(node.clone());
+        self.adjacency.push(Vec::new());
+        self.indexes.insert(node, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N) {
+        self.add_weighted_edge(from, to, 1);
+    }
+
+    /// The nodes are added when they are new, parallel edges are kept
+    pub fn add_weighted_edge(&mut self, from: N, to: N, weight: u64) {
+        let (from, to) = (self.add_node(from), self.add_node(to));
+        let edge = self.edges.len();
+        self.edges.push((from, to, weight));
+        self.adjacency[from].push((to, edge));
+        if !self.directed && from != to {
+            self.adjacency[to].push((from, edge));
+        }
+    }
+
+    pub fn index_of(&self, node: &N) -> Option<usize> {
+        self.indexes.get(node).copied()
+    }
+
+    /// The same graph with the corridors contracted: the nodes linked to exactly two others are
+    /// replaced by an edge weighing the whole corridor, unless `keep` wants them. Useful to
+    /// search the paths of a maze between its junctions only. The directions are followed in
+    /// the directed graphs, the corridors leading nowhere or back to where they started are
+    /// dropped
+    pub fn contract(&self, mut keep: impl FnMut(&N) -> bool) -> Graph<N> {
+        let adjacency = self.undirected_adjacency();
+        let is_junction: Vec<bool> = (0..self.nodes.len())
+            .map(|node| {
+                let mut neighbors: Vec<usize> = adjacency[node]
+                    .iter()
+                    .map(|(neighbor, _)| *neighbor)
+                    .filter(|neighbor| *neighbor != node)
+                    .collect();
+                neighbors.sort_unstable();
+                neighbors.dedup();
+                neighbors.len() != 2 || keep(&self.nodes[node])
+            })
+            .collect();
+
+        let mut contracted = Graph::new(self.directed);
+        for junction in (0..self.nodes.len()).filter(|node| is_junction[*node]) {
+            contracted.add_node(self.nodes[junction].clone());
+        }
+        for junction in (0..self.nodes.len()).filter(|node| is_junction[*node]) {
+            for (first, edge) in &self.adjacency[junction] {
+                let (mut previous, mut node, mut weight) = (junction, *first, self.edges[*edge].2);
+                while !is_junction[node] {
+                    let Some((next, edge)) = self.adjacency[node]
+                        .iter()
+                        .find(|(next, _)| *next != previous && *next != node)
+                    else {
+                        break;
+                    };
+                    (previous, node) = (node, *next);
+                    weight += self.edges[*edge].2;
+                }
+                if !is_junction[node] || node == junction {
+                    continue;
+                }
+                // Undirected corridors are found from both ends, keep one
+                if self.directed || junction <= node {
+                    contracted.add_weighted_edge(
+                        self.nodes[junction].clone(),
+                        self.nodes[node].clone(),
+                        weight,
+                    );
+                }
+            }
+        }
+        contracted
+    }
+}
+
+impl<N> Graph<N> {
+    pub fn node(&self, index: usize) -> &N {
+        &self.nodes[index]
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// The nodes reachable through one edge, following the direction of the edges when the graph
+    /// is directed
+    pub fn neighbors(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        self.adjacency[index].iter().map(|(neighbor, _)| *neighbor)
+    }
+
+    /// Same as `neighbors` with the weight of the edge leading to each one
+    pub fn weighted_neighbors(&self, index: usize) -> impl Iterator<Item = (usize, u64)> + '_ {
+        self.adjacency[index]
+            .iter()
+            .map(|(neighbor, edge)| (*neighbor, self.edges[*edge].2))
+    }
+
+    /// Tarjan's strongly connected components, in reverse topological order (a component comes
+    /// before the ones leading to it). The connected components of an undirected graph
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        let n = self.nodes.len();
+        let mut order = vec![None; n];
+        let mut lowlinks = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut counter = 0;
+
+        for root in 0..n {
+            if order[root].is_some() {
+                continue;
+            }
+            // (node, position of the next edge to follow)
+            let mut work = vec![(root, 0)];
+            order[root] = Some(counter);
+            lowlinks[root] = counter;
+            counter += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((node, position)) = work.last_mut() {
+                let node = *node;
+                if let Some((neighbor, _)) = self.adjacency[node].get(*position) {
+                    *position += 1;
+                    match order[*neighbor] {
+                        None => {
+                            order[*neighbor] = Some(counter);
+                            lowlinks[*neighbor] = counter;
+                            counter += 1;
+                            stack.push(*neighbor);
+                            on_stack[*neighbor] = true;
+                            work.push((*neighbor, 0));
+                        }
+                        Some(neighbor_order) if on_stack[*neighbor] => {
+                            lowlinks[node] = lowlinks[node].min(neighbor_order);
+                        }
+                        Some(_) => {}
+                    }
+                    continue;
+                }
+                work.pop();
+                if let Some((parent, _)) = work.last() {
+                    lowlinks[*parent] = lowlinks[*parent].min(lowlinks[node]);
+                }
+                if Some(lowlinks[node]) == order[node] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+
+    /// The edges whose removal disconnects their ends, as (from, to) in the order they were
+    /// added. The directions are ignored, two parallel edges are never bridges
+    pub fn bridges(&self) -> Vec<(usize, usize)> {
+        let adjacency = self.undirected_adjacency();
+        let n = self.nodes.len();
+        let mut order: Vec<Option<usize>> = vec![None; n];
+        let mut lowlinks = vec![0; n];
+        let mut bridges = Vec::new();
+        let mut counter = 0;
+
+        for root in 0..n {
+            if order[root].is_some() {
+                continue;
+            }
+            // (node, edge it was reached by, position of the next edge to follow)
+            let mut work = vec![(root, None, 0)];
+            order[root] = Some(counter);
+            lowlinks[root] = counter;
+            counter += 1;
+
+            while let Some((node, parent_edge, position)) = work.last_mut() {
+                let (node, parent_edge) = (*node, *parent_edge);
+                if let Some((neighbor, edge)) = adjacency[node].get(*position) {
+                    *position += 1;
+                    if Some(*edge) == parent_edge {
+                        continue;
+                    }
+                    match order[*neighbor] {
+                        None => {
+                            order[*neighbor] = Some(counter);
+                            lowlinks[*neighbor] = counter;
+                            counter += 1;
+                            work.push((*neighbor, Some(*edge), 0));
+                        }
+                        Some(neighbor_order) => {
+                            lowlinks[node] = lowlinks[node].min(neighbor_order);
+                        }
+                    }
+                    continue;
+                }
+                work.pop();
+                if let (Some((parent, _, _)), Some(edge)) = (work.last(), parent_edge) {
+                    lowlinks[*parent] = lowlinks[*parent].min(lowlinks[node]);
+                    // Nothing below the node climbs back above its parent without this edge
+                    if Some(lowlinks[node]) > order[*parent] {
+                        bridges.push(edge);
+                    }
+                }
+            }
+        }
+        bridges.sort_unstable();
+        bridges
+            .into_iter()
+            .map(|edge| (self.edges[edge].0, self.edges[edge].1))
+            .collect()
+    }
+
+    /// Stoer-Wagner global minimum cut, the directions are ignored: the total weight of the
+    /// lightest set of edges splitting the graph in two, with the nodes of one side. None with
+    /// less than 2 nodes
+    pub fn min_cut(&self) -> Option<(u64, Vec<usize>)> {
+        let n = self.nodes.len();
+        if n < 2 {
+            return None;
+        }
+        // The nodes are merged phase after phase, the weights between them summed
+        let mut weights: Vec<HashMap<usize, u64>> = vec![HashMap::new(); n];
+        for (from, to, weight) in &self.edges {
+            if from != to {
+                *weights[*from].entry(*to).or_insert(0) += weight;
+                *weights[*to].entry(*from).or_insert(0) += weight;
+            }
+        }
+        let mut members: Vec<Vec<usize>> = (0..n).map(|node| vec![node]).collect();
+        let mut merged = vec![false; n];
+        let mut best: Option<(u64, Vec<usize>)> = None;
+
+        for _ in 1..n {
+            // Maximum adjacency order: always add the node the most connected to the added ones,
+            // the cut of the phase separates the last one from the others
+            let mut connection = vec![0u64; n];
+            let mut added = merged.clone();
+            let mut heap: BinaryHeap<(u64, usize)> = (0..n)
+                .filter(|node| !merged[*node])
+                .map(|node| (0, node))
+                .collect();
+            let (mut previous, mut last, mut cut_of_the_phase) = (None, None, 0);
+            while let Some((weight, node)) = heap.pop() {
+                if added[node] || weight != connection[node] {
+                    continue;
+                }
+                added[node] = true;
+                (previous, last, cut_of_the_phase) = (last, Some(node), weight);
+                for (neighbor, weight) in &weights[node] {
+                    if !added[*neighbor] {
+                        connection[*neighbor] += weight;
+                        heap.push((connection[*neighbor], *neighbor));
+                    }
+                }
+            }
+            let (s, t) = (previous?, last?);
+            if best
+                .as_ref()
+                .is_none_or(|(weight, _)| cut_of_the_phase < *weight)
+            {
+                best = Some((cut_of_the_phase, members[t].clone()));
+            }
+
+            // Merge t into s
+            let t_members = std::mem::take(&mut members[t]);
+            members[s].extend(t_members);
+            merged[t] = true;
+            for (neighbor, weight) in std::mem::take(&mut weights[t]) {
+                weights[neighbor].remove(&t);
+                if neighbor != s {
+                    *weights[s].entry(neighbor).or_insert(0) += weight;
+                    *weights[neighbor].entry(s).or_insert(0) += weight;
+                }
+            }
+        }
+        best.map(|(weight, mut side)| {
+            side.sort_unstable();
+            (weight, side)
+        })
+    }
+
+    /// Every edge in both directions, with its index
+    fn undirected_adjacency(&self) -> Vec<Vec<(usize, usize)>> {
+        if !self.directed {
+            return self.adjacency.clone();
+        }
+        let mut adjacency = vec![Vec::new(); self.nodes.len()];
+        for (edge, (from, to, _)) in self.edges.iter().enumerate() {
+            adjacency[*from].push((*to, edge));
+            if from != to {
+                adjacency[*to].push((*from, edge));
+            }
+        }
+        adjacency
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_components_and_bridges() {
+        crate::init_tests();
+
+        // a -> b -> c -> a, c -> d -> e -> d
+        let mut graph = Graph::directed();
+        for (from, to) in [
+            ('a', 'b'),
+            ('b', 'c'),
+            ('c', 'a'),
+            ('c', 'd'),
+            ('d', 'e'),
+            ('e', 'd'),
+        ] {
+            graph.add_edge(from, to);
+        }
+        let components: Vec<Vec<char>> = graph
+            .strongly_connected_components()
+            .into_iter()
+            .map(|component| {
+                let mut nodes: Vec<char> = component.iter().map(|i| *graph.node(*i)).collect();
+                nodes.sort();
+                nodes
+            })
+            .collect();
+        assert_eq!(components, vec![vec!['d', 'e'], vec!['a', 'b', 'c']]);
+        // Only c - d holds the two cycles together, d - e is doubled
+        let bridges: Vec<(char, char)> = graph
+            .bridges()
+            .into_iter()
+            .map(|(from, to)| (*graph.node(from), *graph.node(to)))
+            .collect();
+        assert_eq!(bridges, vec![('c', 'd')]);
+
+        let mut graph = Graph::undirected();
+        graph.add_edge(1, 2);
+        graph.add_edge(3, 4);
+        graph.add_node(5);
+        assert_eq!(graph.strongly_connected_components().len(), 3);
+        assert_eq!(graph.bridges().len(), 2);
+    }
+
+    #[test]
+    fn test_min_cut() {
+        crate::init_tests();
+
+        let input = "jqt: rhn xhk nvd\nrsh: frs pzl lsr\nxhk: hfx\ncmg: qnr nvd lhk bvb\n\
+                     rhn: xhk bvb hfx\nbvb: xhk hfx\npzl: lsr hfx nvd\nqnr: nvd\n\
+                     ntq: jqt hfx bvb xhk\nnvd: lhk\nlsr: lhk\nrzs: qnr cmg lsr rsh\n\
+                     frs: qnr lhk lsr";
+        let mut graph = Graph::undirected();
+        for line in input.lines() {
+            let (from, tos) = line.split_once(": ").unwrap();
+            for to in tos.split(' ') {
+                graph.add_edge(from, to);
+            }
+        }
+        let (weight, side) = graph.min_cut().unwrap();
+        assert_eq!(weight, 3);
+        assert_eq!(side.len() * (graph.len() - side.len()), 54);
+
+        let mut graph = Graph::undirected();
+        graph.add_weighted_edge("a", "b", 5);
+        graph.add_weighted_edge("b", "c", 2);
+        graph.add_node("d");
+        // d alone, either side can come out
+        let (weight, side) = graph.min_cut().unwrap();
+        assert_eq!(weight, 0);
+        assert!(side == vec![3] || side == vec![0, 1, 2]);
+        graph.add_weighted_edge("c", "d", 4);
+        assert_eq!(graph.min_cut().unwrap().0, 2);
+        assert_eq!(Graph::<u8>::undirected().min_cut(), None);
+    }
+
+    #[test]
+    fn test_contract() {
+        crate::init_tests();
+
+        // s - 1 - 2 - j - 3 - e and j - 4 - 5 - e, e is kept although it only has two neighbors
+        let mut graph = Graph::undirected();
+        for (from, to) in [
+            ("s", "1"),
+            ("1", "2"),
+            ("2", "j"),
+            ("j", "3"),
+            ("3", "e"),
+            ("j", "4"),
+            ("4", "5"),
+            ("5", "e"),
+        ] {
+            graph.add_edge(from, to);
+        }
+        let contracted = graph.contract(|node| *node == "e");
+        assert_eq!(contracted.len(), 3);
+        let j = contracted.index_of(&"j").unwrap();
+        let mut edges: Vec<(&str, u64)> = contracted
+            .weighted_neighbors(j)
+            .map(|(neighbor, weight)| (*contracted.node(neighbor), weight))
+            .collect();
+        edges.sort();
+        assert_eq!(edges, vec![("e", 2), ("e", 3), ("s", 3)]);
+
+        // The way back from e is a dead end
+        let mut graph = Graph::directed();
+        for (from, to) in [("s", "1"), ("1", "2"), ("2", "e"), ("e", "2")] {
+            graph.add_edge(from, to);
+        }
+        let contracted = graph.contract(|_| false);
+        assert_eq!(contracted.edge_count(), 1);
+        let (s, e) = (
+            contracted.index_of(&"s").unwrap(),
+            contracted.index_of(&"e").unwrap(),
+        );
+        assert_eq!(
+            contracted.weighted_neighbors(s).collect::<Vec<_>>(),
+            vec![(e, 3)]
+        );
+    }
+}
@@ -0,0 +1,540 @@
+//! Two dimensional maps of the grid puzzles, stored row after row in a single buffer
+//!
+//! The cells are indexed by `Point`, the points out of the grid (negative ones included) give None
+//! so the solvers do not have to check the bounds themselves.
+use crate::bitset::BitSet;
+use crate::geometry::Point;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::ops::{Index, IndexMut};
+
+/// Which cells touch each other in the flood fills
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Sharing a side
+    Four,
+    /// Sharing a side or a corner
+    Eight,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![fill; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Build the grid from rows of any length, the rows shorter than the longest one are padded
+    pub fn from_rows(rows: Vec<Vec<T>>, padding: T) -> Self
+    where
+        T: Clone,
+    {
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for mut row in rows {
+            row.resize(width, padding.clone());
+            cells.extend(row);
+        }
+        Grid {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// One row per line of the input and one cell per character
+    pub fn from_lines(input: &str, padding: T, mut parse: impl FnMut(char) -> T) -> Self
+    where
+        T: Clone,
+    {
+        let rows = crate::input::lines(input)
+            .map(|line| line.chars().map(&mut parse).collect())
+            .collect();
+        Grid::from_rows(rows, padding)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.index_of(point).is_some()
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        let x = usize::try_from(point.x).ok()?;
+        let y = usize::try_from(point.y).ok()?;
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|index| &mut self.cells[index])
+    }
+
+    /// The neighbors sharing a side with the point, in the grid
+    pub fn neighbors4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point
+            .neighbors4()
+            .filter(move |neighbor| self.contains(*neighbor))
+    }
+
+    /// The neighbors sharing a side or a corner with the point, in the grid
+    pub fn neighbors8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point
+            .neighbors8()
+            .filter(move |neighbor| self.contains(*neighbor))
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks does not accept a width of 0, there are no cells to split then anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> Option<impl Iterator<Item = &T>> {
+        (x < self.width).then(|| self.cells.iter().skip(x).step_by(self.width))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.cells.iter().skip(x).step_by(self.width))
+    }
+
+    /// A copy of a column, `column` iterates over it without allocating
+    pub fn column_to_vec(&self, x: usize) -> Option<Vec<T>>
+    where
+        T: Clone,
+    {
+        Some(self.column(x)?.cloned().collect())
+    }
+
+    /// Whether two rows hold the same cells, false when one of them is out of the grid
+    pub fn rows_equal(&self, a: usize, b: usize) -> bool
+    where
+        T: PartialEq,
+    {
+        matches!((self.row(a), self.row(b)), (Some(a), Some(b)) if a == b)
+    }
+
+    /// Whether two columns hold the same cells, false when one of them is out of the grid
+    pub fn columns_equal(&self, a: usize, b: usize) -> bool
+    where
+        T: PartialEq,
+    {
+        match (self.column(a), self.column(b)) {
+            (Some(a), Some(b)) => a.eq(b),
+            _ => false,
+        }
+    }
+
+    /// Mirror the grid left to right, in place
+    pub fn flip_horizontal(&mut self) {
+        for row in self.cells.chunks_mut(self.width.max(1)) {
+            row.reverse();
+        }
+    }
+
+    /// Mirror the grid top to bottom, in place
+    pub fn flip_vertical(&mut self) {
+        for y in 0..self.height / 2 {
+            let (top, bottom) = self.cells.split_at_mut((self.height - 1 - y) * self.width);
+            top[y * self.width..(y + 1) * self.width].swap_with_slice(&mut bottom[..self.width]);
+        }
+    }
+
+    /// Swap the rows and the columns, in place for a square grid, the others need a new buffer
+    pub fn transpose(&mut self) {
+        if self.width == self.height {
+            let size = self.width;
+            for y in 0..size {
+                for x in y + 1..size {
+                    self.cells.swap(y * size + x, x * size + y);
+                }
+            }
+            return;
+        }
+        // Moving the cells out through Options spares the Clone bound
+        let mut cells: Vec<Option<T>> = std::mem::take(&mut self.cells)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let mut transposed = Vec::with_capacity(cells.len());
+        for x in 0..self.width {
+            for y in 0..self.height {
+                transposed.push(cells[y * self.width + x].take().unwrap());
+            }
+        }
+        self.cells = transposed;
+        std::mem::swap(&mut self.width, &mut self.height);
+    }
+
+    /// A quarter turn clockwise, the first column becomes the first row reversed
+    pub fn rotate_clockwise(&mut self) {
+        self.transpose();
+        self.flip_horizontal();
+    }
+
+    /// A quarter turn counterclockwise, the first row becomes the first column reversed
+    pub fn rotate_counterclockwise(&mut self) {
+        self.transpose();
+        self.flip_vertical();
+    }
+
+    /// Every point of the grid, row after row
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width as i64, self.height as i64);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    /// Every cell with its point, row after row
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    /// The passable cells connected to `start` (included), in the order they are reached. Empty
+    /// when `start` is out of the grid or not passable
+    pub fn flood_fill(
+        &self,
+        start: Point,
+        connectivity: Connectivity,
+        mut passable: impl FnMut(Point, &T) -> bool,
+    ) -> Vec<Point> {
+        let mut visited = BitSet::with_capacity(self.cells.len());
+        self.fill_from(start, connectivity, &mut passable, &mut visited)
+    }
+
+    /// Every region of connected passable cells, in the order of their first cell row after row
+    pub fn regions(
+        &self,
+        connectivity: Connectivity,
+        mut passable: impl FnMut(Point, &T) -> bool,
+    ) -> Vec<Vec<Point>> {
+        let mut visited = BitSet::with_capacity(self.cells.len());
+        let mut regions = Vec::new();
+        for point in self.points() {
+            let region = self.fill_from(point, connectivity, &mut passable, &mut visited);
+            if !region.is_empty() {
+                regions.push(region);
+            }
+        }
+        regions
+    }
+
+    fn fill_from(
+        &self,
+        start: Point,
+        connectivity: Connectivity,
+        passable: &mut impl FnMut(Point, &T) -> bool,
+        visited: &mut BitSet,
+    ) -> Vec<Point> {
+        let mut region = Vec::new();
+        let mut queue = VecDeque::new();
+        let mut visit = |point: Point, queue: &mut VecDeque<Point>| {
+            if let Some(index) = self.index_of(point) {
+                if !visited.contains(index) && passable(point, &self.cells[index]) {
+                    visited.insert(index);
+                    queue.push_back(point);
+                }
+            }
+        };
+        visit(start, &mut queue);
+        while let Some(point) = queue.pop_front() {
+            region.push(point);
+            match connectivity {
+                Connectivity::Four => point.neighbors4().for_each(|n| visit(n, &mut queue)),
+                Connectivity::Eight => point.neighbors8().for_each(|n| visit(n, &mut queue)),
+            }
+        }
+        region
+    }
+
+    /// A deserialized grid is only checked for its size, the cells are trusted
+    pub fn check_size(&self) -> Result<()> {
+        if self.cells.len() != self.width * self.height {
+            return Err(Error::new(format!(
+                "{} cells for a {}x{} grid",
+                self.cells.len(),
+                self.width,
+                self.height
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point).unwrap_or_else(|| {
+            panic!(
+                "{} is out of the {}x{} grid",
+                point, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{} is out of the {}x{} grid", point, width, height))
+    }
+}
+
+/// Only the occupied cells are stored, for the maps far too large and empty to be dense. Unlike
+/// `Grid` there are no bounds, every point can be inserted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        SparseGrid::default()
+    }
+
+    /// Returns the previous value of the cell
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        self.cells.insert(point, value)
+    }
+
+    pub fn remove(&mut self, point: Point) -> Option<T> {
+        self.cells.remove(&point)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.cells.get_mut(&point)
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The occupied neighbors sharing a side with the point
+    pub fn neighbors4(&self, point: Point) -> impl Iterator<Item = (Point, &T)> + '_ {
+        point
+            .neighbors4()
+            .filter_map(move |neighbor| Some((neighbor, self.get(neighbor)?)))
+    }
+
+    /// The occupied neighbors sharing a side or a corner with the point
+    pub fn neighbors8(&self, point: Point) -> impl Iterator<Item = (Point, &T)> + '_ {
+        point
+            .neighbors8()
+            .filter_map(move |neighbor| Some((neighbor, self.get(neighbor)?)))
+    }
+
+    /// The top left and bottom right corners (included) of the occupied cells, None when empty
+    pub fn bounding_box(&self) -> Option<(Point, Point)> {
+        let mut points = self.cells.keys();
+        let first = *points.next()?;
+        Some(points.fold((first, first), |(min, max), point| {
+            (
+                Point::new(min.x.min(point.x), min.y.min(point.y)),
+                Point::new(max.x.max(point.x), max.y.max(point.y)),
+            )
+        }))
+    }
+
+    /// The occupied cells row after row, they are sorted on each call
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        let mut cells: Vec<(Point, &T)> = self
+            .cells
+            .iter()
+            .map(|(point, value)| (*point, value))
+            .collect();
+        cells.sort_unstable_by_key(|(point, _)| (point.y, point.x));
+        cells.into_iter()
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        SparseGrid {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_grid() {
+        crate::init_tests();
+
+        let mut grid = Grid::from_lines("ab\ncde\nf", '.', |c| c);
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid.row(0), Some(&['a', 'b', '.'][..]));
+        assert_eq!(grid[Point::new(2, 1)], 'e');
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.get(Point::new(-1, 0)), None);
+        assert_eq!(
+            grid.column(0).unwrap().collect::<String>(),
+            String::from("acf")
+        );
+        assert_eq!(
+            grid.columns().map(|column| column.count()).sum::<usize>(),
+            9
+        );
+
+        let corner = Point::ORIGIN;
+        assert_eq!(
+            grid.neighbors4(corner).collect::<Vec<_>>(),
+            vec![Point::new(1, 0), Point::new(0, 1)]
+        );
+        assert_eq!(grid.neighbors8(corner).count(), 3);
+        assert_eq!(grid.neighbors8(Point::new(1, 1)).count(), 8);
+
+        grid[corner] = 'z';
+        assert_eq!(grid.iter().next(), Some((corner, &'z')));
+        assert!(grid.check_size().is_ok());
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        crate::init_tests();
+
+        let grid = Grid::from_lines("..#..\n.#...\n#.###\n.#...", '#', |c| c);
+        let open = |_: Point, c: &char| *c == '.';
+        let top_left = grid.flood_fill(Point::ORIGIN, Connectivity::Four, open);
+        assert_eq!(top_left.len(), 3);
+        assert_eq!(top_left[0], Point::ORIGIN);
+        // The middle cell is walled on its sides, only its corners join the rest
+        let middle = Point::new(1, 2);
+        assert_eq!(grid.flood_fill(middle, Connectivity::Four, open).len(), 1);
+        assert_eq!(grid.flood_fill(middle, Connectivity::Eight, open).len(), 13);
+        assert!(grid
+            .flood_fill(Point::new(2, 0), Connectivity::Four, open)
+            .is_empty());
+        assert!(grid
+            .flood_fill(Point::new(-1, 0), Connectivity::Four, open)
+            .is_empty());
+
+        let sizes = |connectivity| -> Vec<usize> {
+            grid.regions(connectivity, open)
+                .iter()
+                .map(Vec::len)
+                .collect()
+        };
+        assert_eq!(sizes(Connectivity::Four), vec![3, 5, 1, 1, 3]);
+        assert_eq!(sizes(Connectivity::Eight), vec![13]);
+    }
+
+    fn to_string(grid: &Grid<char>) -> String {
+        let rows: Vec<String> = grid.rows().map(|row| row.iter().collect()).collect();
+        rows.join("\n")
+    }
+
+    #[test]
+    fn test_transformations() {
+        crate::init_tests();
+
+        let original = Grid::from_lines("abc\ndef", '.', |c| c);
+        let mut grid = original.clone();
+        grid.rotate_clockwise();
+        assert_eq!(to_string(&grid), "da\neb\nfc");
+        grid.rotate_counterclockwise();
+        assert_eq!(grid, original);
+        for _ in 0..4 {
+            grid.rotate_clockwise();
+        }
+        assert_eq!(grid, original);
+
+        grid.transpose();
+        assert_eq!(to_string(&grid), "ad\nbe\ncf");
+        grid.flip_vertical();
+        assert_eq!(to_string(&grid), "cf\nbe\nad");
+        grid.flip_horizontal();
+        assert_eq!(to_string(&grid), "fc\neb\nda");
+
+        let mut square = Grid::from_lines("ab\ncd", '.', |c| c);
+        square.transpose();
+        assert_eq!(to_string(&square), "ac\nbd");
+
+        let mirror = Grid::from_lines("#..#\n.##.\n#..#", '.', |c| c);
+        assert!(mirror.rows_equal(0, 2));
+        assert!(!mirror.rows_equal(0, 1));
+        assert!(!mirror.rows_equal(0, 3));
+        assert!(mirror.columns_equal(1, 2));
+        assert!(!mirror.columns_equal(0, 1));
+        assert_eq!(mirror.column_to_vec(3), Some(vec!['#', '.', '#']));
+    }
+
+    #[test]
+    fn test_sparse_grid() {
+        crate::init_tests();
+
+        let mut grid: SparseGrid<char> = SparseGrid::new();
+        assert_eq!(grid.bounding_box(), None);
+        grid.insert(Point::new(1_000_000, 5), 'a');
+        grid.insert(Point::new(-3, 5), 'b');
+        grid.insert(Point::new(0, -2), 'c');
+        assert_eq!(grid.insert(Point::new(0, -2), 'd'), Some('c'));
+        assert_eq!(grid.len(), 3);
+        assert_eq!(
+            grid.bounding_box(),
+            Some((Point::new(-3, -2), Point::new(1_000_000, 5)))
+        );
+        assert_eq!(
+            grid.iter().map(|(_, c)| *c).collect::<String>(),
+            String::from("dba")
+        );
+
+        grid.insert(Point::new(-2, 4), 'e');
+        assert_eq!(
+            grid.neighbors8(Point::new(-3, 4)).collect::<Vec<_>>(),
+            vec![(Point::new(-2, 4), &'e'), (Point::new(-3, 5), &'b')]
+        );
+        assert_eq!(grid.neighbors4(Point::new(-3, 4)).count(), 2);
+        assert_eq!(grid.remove(Point::new(-2, 4)), Some('e'));
+        assert!(!grid.contains(Point::new(-2, 4)));
+    }
+}
@@ -0,0 +1,121 @@
+//! The Holiday ASCII String Helper of the lens library (HASH, multiply by 17 modulo 256) and the
+//! 256 boxes of labels it spreads the lenses in
+pub const NB_OF_BOXES: usize = 256;
+
+/// HASH of a string: for every character add its ASCII code, multiply by 17 and keep the
+/// remainder of the division by 256. The newlines are ignored like in the puzzle
+pub fn holiday_hash(text: &str) -> u8 {
+    text.bytes()
+        .filter(|byte| *byte != b'\n' && *byte != b'\r')
+        .fold(0u8, |current, byte| {
+            current.wrapping_add(byte).wrapping_mul(17)
+        })
+}
+
+/// Labeled values in the box given by the HASH of their label, each box keeping its labels in
+/// insertion order (a replaced value keeps its slot)
+#[derive(Debug, Clone)]
+pub struct LabelBoxes<V> {
+    boxes: Vec<Vec<(String, V)>>,
+}
+
+impl<V> LabelBoxes<V> {
+    pub fn new() -> Self {
+        LabelBoxes {
+            boxes: (0..NB_OF_BOXES).map(|_| Vec::new()).collect(),
+        }
+    }
+
+    /// Replace the value of the label in place, or add it at the back of its box
+    pub fn insert(&mut self, label: &str, value: V) {
+        let lenses = &mut self.boxes[holiday_hash(label) as usize];
+        match lenses.iter_mut().find(|(current, _)| current == label) {
+            Some((_, current)) => *current = value,
+            None => lenses.push((label.to_string(), value)),
+        }
+    }
+
+    /// Take the label out of its box, the ones behind move forward
+    pub fn remove(&mut self, label: &str) -> Option<V> {
+        let lenses = &mut self.boxes[holiday_hash(label) as usize];
+        let position = lenses.iter().position(|(current, _)| current == label)?;
+        Some(lenses.remove(position).1)
+    }
+
+    pub fn get(&self, label: &str) -> Option<&V> {
+        self.boxes[holiday_hash(label) as usize]
+            .iter()
+            .find(|(current, _)| current == label)
+            .map(|(_, value)| value)
+    }
+
+    /// The content of a box, from the front to the back
+    pub fn contents(&self, box_index: u8) -> &[(String, V)] {
+        &self.boxes[box_index as usize]
+    }
+
+    /// Every value with the index of its box and its slot in the box
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &str, &V)> {
+        self.boxes
+            .iter()
+            .enumerate()
+            .flat_map(|(box_index, lenses)| {
+                lenses
+                    .iter()
+                    .enumerate()
+                    .map(move |(slot, (label, value))| (box_index, slot, label.as_str(), value))
+            })
+    }
+}
+
+impl<V> Default for LabelBoxes<V> {
+    fn default() -> Self {
+        LabelBoxes::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7\n";
+
+    #[test]
+    fn test_holiday_hash() {
+        crate::init_tests();
+
+        assert_eq!(holiday_hash("HASH"), 52);
+        assert_eq!(holiday_hash(""), 0);
+        let sum: u32 = EXAMPLE
+            .split(',')
+            .map(|step| holiday_hash(step) as u32)
+            .sum();
+        assert_eq!(sum, 1320);
+    }
+
+    #[test]
+    fn test_label_boxes() {
+        crate::init_tests();
+
+        let mut boxes = LabelBoxes::new();
+        for step in EXAMPLE.trim().split(',') {
+            match step.split_once('=') {
+                Some((label, focal_length)) => {
+                    boxes.insert(label, focal_length.parse::<usize>().unwrap())
+                }
+                None => {
+                    boxes.remove(step.trim_end_matches('-'));
+                }
+            }
+        }
+        assert_eq!(boxes.get("ot"), Some(&7));
+        assert_eq!(boxes.get("qp"), None);
+        let labels: Vec<&str> = boxes.contents(3).iter().map(|(l, _)| l.as_str()).collect();
+        assert_eq!(labels, vec!["ot", "ab", "pc"]);
+        let focusing_power: usize = boxes
+            .iter()
+            .map(|(box_index, slot, _, focal_length)| (box_index + 1) * (slot + 1) * focal_length)
+            .sum();
+        assert_eq!(focusing_power, 145);
+    }
+}
@@ -0,0 +1,69 @@
+//! The puzzle input is read in one piece and the days parse it from a `&str`, their lines borrow
+//! from it instead of being allocated one by one as with `BufRead::lines`.
+
+/// The lines of the input, without their line ending ("\n" or "\r\n")
+pub fn lines(input: &str) -> std::str::Lines<'_> {
+    input.lines()
+}
+
+/// The blocks of lines separated by blank lines, without the line ending of their last line.
+/// Several blank lines in a row separate two blocks too, there is never an empty block
+pub fn sections(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+    std::iter::from_fn(move || {
+        // Skip the blank lines before the block
+        while let Some(line) = rest.split_inclusive('\n').next() {
+            if !line.trim_end_matches(['\n', '\r']).is_empty() {
+                break;
+            }
+            rest = &rest[line.len()..];
+        }
+        if rest.is_empty() {
+            return None;
+        }
+        let mut end = 0;
+        for line in rest.split_inclusive('\n') {
+            if line.trim_end_matches(['\n', '\r']).is_empty() {
+                break;
+            }
+            end += line.len();
+        }
+        let section = rest[..end].trim_end_matches(['\n', '\r']);
+        rest = &rest[end..];
+        Some(section)
+    })
+}
+
+/// The lines of every block separated by blank lines
+pub fn section_lines(input: &str) -> impl Iterator<Item = std::str::Lines<'_>> {
+    sections(input).map(lines)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sections() {
+        crate::init_tests();
+
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\n\nsoil-to-fertilizer map:\n0 15 37\n";
+        assert_eq!(
+            sections(input).collect::<Vec<_>>(),
+            vec![
+                "seeds: 79 14",
+                "seed-to-soil map:\n50 98 2\n52 50 48",
+                "soil-to-fertilizer map:\n0 15 37"
+            ]
+        );
+        let crlf = "\r\na\r\nb\r\n\r\nc";
+        assert_eq!(
+            section_lines(crlf)
+                .map(|lines| lines.collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            vec![vec!["a", "b"], vec!["c"]]
+        );
+        assert_eq!(sections("\n\n").count(), 0);
+        assert_eq!(sections("").count(), 0);
+    }
+}
@@ -0,0 +1,143 @@
+//! Iterator adapters the puzzles keep asking for (consecutive pairs, sliding windows, blocks of
+//! lines), so the days do not pull itertools for two methods
+use std::collections::VecDeque;
+
+pub trait IteratorExt: Iterator + Sized {
+    /// Every item with the next one: 1, 2, 3 gives (1, 2), (2, 3). For the differences of a
+    /// sequence
+    fn pairwise(self) -> Pairwise<Self>
+    where
+        Self::Item: Clone,
+    {
+        Pairwise {
+            iter: self,
+            previous: None,
+        }
+    }
+
+    /// The overlapping windows of N consecutive items, nothing when there are less than N items.
+    /// Panics when N is 0, like `slice::windows`
+    fn windows<const N: usize>(self) -> Windows<Self, N>
+    where
+        Self::Item: Clone,
+    {
+        assert!(N > 0, "The windows cannot be empty");
+        Windows {
+            iter: self,
+            window: VecDeque::with_capacity(N),
+        }
+    }
+
+    /// The blocks of lines separated by blank lines, like `input::sections` on an iterator of
+    /// lines (ex: the lines of a BufRead)
+    fn sections(self) -> Sections<Self>
+    where
+        Self::Item: AsRef<str>,
+    {
+        Sections { iter: self }
+    }
+}
+
+impl<I: Iterator> IteratorExt for I {}
+
+pub struct Pairwise<I: Iterator> {
+    iter: I,
+    previous: Option<I::Item>,
+}
+
+impl<I: Iterator> Iterator for Pairwise<I>
+where
+    I::Item: Clone,
+{
+    type Item = (I::Item, I::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let previous = match self.previous.take() {
+            Some(previous) => previous,
+            None => self.iter.next()?,
+        };
+        let next = self.iter.next()?;
+        self.previous = Some(next.clone());
+        Some((previous, next))
+    }
+}
+
+pub struct Windows<I: Iterator, const N: usize> {
+    iter: I,
+    window: VecDeque<I::Item>,
+}
+
+impl<I: Iterator, const N: usize> Iterator for Windows<I, N>
+where
+    I::Item: Clone,
+{
+    type Item = [I::Item; N];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.window.len() == N {
+            self.window.pop_front();
+        }
+        while self.window.len() < N {
+            self.window.push_back(self.iter.next()?);
+        }
+        Some(std::array::from_fn(|index| self.window[index].clone()))
+    }
+}
+
+pub struct Sections<I> {
+    iter: I,
+}
+
+impl<I: Iterator> Iterator for Sections<I>
+where
+    I::Item: AsRef<str>,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut section = Vec::new();
+        for line in self.iter.by_ref() {
+            match line.as_ref().trim().is_empty() {
+                // The blank lines before a section (or several in a row) are skipped
+                true if section.is_empty() => {}
+                true => return Some(section),
+                false => section.push(line),
+            }
+        }
+        match section.is_empty() {
+            true => None,
+            false => Some(section),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_adapters() {
+        crate::init_tests();
+
+        let differences: Vec<i32> = [0, 3, 6, 10, 15]
+            .into_iter()
+            .pairwise()
+            .map(|(a, b)| b - a)
+            .collect();
+        assert_eq!(differences, vec![3, 3, 4, 5]);
+        assert_eq!([1].into_iter().pairwise().count(), 0);
+
+        let windows: Vec<[char; 3]> = "abcde".chars().windows().collect();
+        assert_eq!(
+            windows,
+            vec![['a', 'b', 'c'], ['b', 'c', 'd'], ['c', 'd', 'e']]
+        );
+        assert_eq!("ab".chars().windows::<3>().count(), 0);
+        assert_eq!("ab".chars().windows::<1>().count(), 2);
+
+        let lines = ["", "#.#", "..#", "", "", "##.", "  ", "#"];
+        let sections: Vec<Vec<&str>> = lines.into_iter().sections().collect();
+        assert_eq!(sections, vec![vec!["#.#", "..#"], vec!["##."], vec!["#"]]);
+        assert_eq!(std::iter::empty::<String>().sections().count(), 0);
+    }
+}
@@ -1,41 +1,234 @@
-use clap::Parser;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::fmt;
 
-#[derive(clap::ValueEnum, Clone)]
+pub mod bitset;
+#[cfg(feature = "cli")]
+mod cli;
+pub mod collections;
+pub mod combinatorics;
+pub mod cycle;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod hash;
+pub mod input;
+pub mod iter;
+pub mod linalg;
+pub mod logging;
+pub mod math;
+pub mod memo;
+pub mod memory;
+pub mod parallel;
+pub mod parse;
+#[cfg(feature = "parsers")]
+pub mod parsers;
+pub mod pathfinding;
+pub mod profile;
+pub mod queue;
+pub mod ranges;
+pub mod registry;
+pub mod repl;
+pub mod search;
+#[cfg(feature = "smt")]
+pub mod smt;
+pub mod trace;
+pub mod viz;
+
+// The allocator of a library is the one of every binary linking it, so the days and the runner
+// only forward the features
+#[cfg(all(feature = "mimalloc", feature = "jemalloc"))]
+compile_error!("The mimalloc and jemalloc features cannot be enabled together");
+
+#[cfg(feature = "mimalloc")]
+const ALLOCATOR: mimalloc::MiMalloc = mimalloc::MiMalloc;
+#[cfg(feature = "jemalloc")]
+const ALLOCATOR: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+#[cfg(all(feature = "mem", not(any(feature = "mimalloc", feature = "jemalloc"))))]
+const ALLOCATOR: std::alloc::System = std::alloc::System;
+
+#[cfg(all(not(feature = "mem"), any(feature = "mimalloc", feature = "jemalloc")))]
+#[global_allocator]
+static GLOBAL: Allocator = ALLOCATOR;
+
+// The counting wraps whichever allocator was selected, to compare them too
+#[cfg(feature = "mem")]
+#[global_allocator]
+static GLOBAL: memory::CountingAllocator<Allocator> = memory::CountingAllocator::new(ALLOCATOR);
+
+#[cfg(feature = "mimalloc")]
+type Allocator = mimalloc::MiMalloc;
+#[cfg(feature = "jemalloc")]
+type Allocator = tikv_jemallocator::Jemalloc;
+#[cfg(all(feature = "mem", not(any(feature = "mimalloc", feature = "jemalloc"))))]
+type Allocator = std::alloc::System;
+
+// For the macros, so the days do not need their own dependency on log to use them
+#[doc(hidden)]
+pub use log;
+#[cfg(feature = "num-bigint")]
+pub use num_bigint::BigUint;
+
+#[cfg(feature = "cli")]
+pub use cli::{
+    dump_parsed, get_input_grid, get_input_lines, get_input_stream, get_input_string, init_logger,
+    init_profile, init_threads, init_trace, print_visualization, report_answer, start_repl,
+    write_dot, write_html, write_image, write_svg, Cli, InputFormat,
+};
+
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Part {
     Part1,
     Part2,
 }
 
-#[derive(Parser)]
-#[command(author, version, about, long_about = None)]
-pub struct Cli {
-    #[arg(value_enum)]
-    pub part: Part,
-    #[arg(short, long)]
-    pub input_file: Option<String>,
-    #[arg(short, long)]
-    pub verbose: bool,
+/// How the days that answer line by line write each line (ex: day 1 with --per-line)
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LineFormat {
+    /// `line_number,value`
+    Csv,
+    /// `{"line":line_number,"value":value}`
+    Jsonl,
+}
+
+/// How the days that check their input on demand report the suspicious lines (ex: day 1 with
+/// --strict)
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Strictness {
+    /// Log them and solve anyway
+    Warn,
+    /// Log them and fail
+    Error,
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::Part1 => write!(f, "Part 1"),
+            Part::Part2 => write!(f, "Part 2"),
+        }
+    }
 }
 
-pub fn get_input_stream(cli: &Cli) -> Box<dyn BufRead> {
-    match &cli.input_file {
-        Some(file_name) => {
-            let f = File::open(file_name).expect("Could not open input file");
-            Box::new(BufReader::new(f))
+/// The answer of a puzzle part, returned by the solvers so it can be consumed in-process
+/// (by the runner, the tests or an external tool) instead of being scraped from the logs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Unsigned(u64),
+    Signed(i64),
+    Text(String),
+    /// Only built from the values over u64, so an answer has a single representation
+    #[cfg(feature = "num-bigint")]
+    Big(BigUint),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Unsigned(value) => write!(f, "{}", value),
+            Answer::Signed(value) => write!(f, "{}", value),
+            Answer::Text(value) => write!(f, "{}", value),
+            #[cfg(feature = "num-bigint")]
+            Answer::Big(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Self {
+        Answer::Unsigned(value as u64)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        Answer::Unsigned(value)
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(value: i32) -> Self {
+        Answer::Signed(value as i64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::Signed(value)
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+impl From<BigUint> for Answer {
+    fn from(value: BigUint) -> Self {
+        match u64::try_from(&value) {
+            Ok(value) => Answer::Unsigned(value),
+            Err(_) => Answer::Big(value),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+/// Error returned by the solvers when the input cannot be solved
+#[derive(Debug)]
+pub struct Error {
+    message: String,
+}
+
+impl Error {
+    pub fn new(message: impl Into<String>) -> Self {
+        Error {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::new(format!("I/O error: {}", error))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Run a solver, turning its panics (ex: on a malformed input) into errors, for the long-lived
+/// processes that must survive a bad input
+pub fn catch_panic<T>(solver: impl FnOnce() -> Result<T> + std::panic::UnwindSafe) -> Result<T> {
+    match std::panic::catch_unwind(solver) {
+        Ok(result) => result,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| String::from("unknown panic"));
+            Err(Error::new(format!("The solver panicked: {}", message)))
         }
-        None => Box::new(BufReader::new(std::io::stdin())),
     }
 }
 
-pub fn init_logger(cli: &Cli) {
-    let log_level = match cli.verbose {
+/// Same as `init_logger` for the binaries that do not use the day `Cli` (ex: the runner)
+pub fn init_logger_with_verbosity(verbose: bool) {
+    let log_level = match verbose {
         true => log::LevelFilter::max(),
         false => log::LevelFilter::Info,
     };
 
-    let _ = env_logger::builder().filter_level(log_level).init();
+    env_logger::builder().filter_level(log_level).init();
 }
 
 pub fn init_tests() {
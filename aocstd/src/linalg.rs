@@ -0,0 +1,512 @@
+//! Small exact linear algebra: fractions of i128 and matrices, to solve the linear systems some
+//! puzzles reduce to (ex: the rock thrown through every hailstone) without rounding errors
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+/// A fraction always kept reduced with a positive denominator. The operators panic when the
+/// numerator or the denominator overflow i128, the checked methods give None instead
+///
+/// Serialized as a (numerator, denominator) pair, reduced again when read back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "(i128, i128)", into = "(i128, i128)")]
+pub struct Fraction {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Fraction {
+    pub const ZERO: Fraction = Fraction::integer(0);
+    pub const ONE: Fraction = Fraction::integer(1);
+
+    /// Panics when the denominator is 0
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        assert!(denominator != 0, "Fraction with a zero denominator");
+        Fraction::checked_new(numerator, denominator).expect("Fraction overflow")
+    }
+
+    /// None when the denominator is 0 or when i128::MIN cannot change its sign
+    fn checked_new(numerator: i128, denominator: i128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        let divisor = i128::try_from(gcd(numerator, denominator)).ok()?;
+        let sign = denominator.signum();
+        Some(Fraction {
+            numerator: (numerator / divisor).checked_mul(sign)?,
+            denominator: (denominator / divisor).checked_mul(sign)?,
+        })
+    }
+
+    pub fn checked_add(self, other: Fraction) -> Option<Fraction> {
+        // Over the lcm of the denominators to keep the intermediate values small, the gcd of
+        // two positive i128 fits in i128
+        let divisor = gcd(self.denominator, other.denominator) as i128;
+        let denominator = (self.denominator / divisor).checked_mul(other.denominator)?;
+        let numerator = self
+            .numerator
+            .checked_mul(denominator / self.denominator)?
+            .checked_add(
+                other
+                    .numerator
+                    .checked_mul(denominator / other.denominator)?,
+            )?;
+        Fraction::checked_new(numerator, denominator)
+    }
+
+    pub fn checked_neg(self) -> Option<Fraction> {
+        Some(Fraction {
+            numerator: self.numerator.checked_neg()?,
+            denominator: self.denominator,
+        })
+    }
+
+    pub fn checked_sub(self, other: Fraction) -> Option<Fraction> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    pub fn checked_mul(self, other: Fraction) -> Option<Fraction> {
+        // Cross reduce first to keep the intermediate values small
+        let first = gcd(self.numerator, other.denominator) as i128;
+        let second = gcd(other.numerator, self.denominator) as i128;
+        Fraction::checked_new(
+            (self.numerator / first).checked_mul(other.numerator / second)?,
+            (self.denominator / second).checked_mul(other.denominator / first)?,
+        )
+    }
+
+    /// None when dividing by zero too
+    pub fn checked_div(self, other: Fraction) -> Option<Fraction> {
+        self.checked_mul(Fraction::checked_new(other.denominator, other.numerator)?)
+    }
+
+    pub const fn integer(value: i128) -> Self {
+        Fraction {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    pub fn numerator(self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.numerator == 0
+    }
+
+    /// The value when the fraction is a whole number
+    pub fn to_integer(self) -> Option<i128> {
+        (self.denominator == 1).then_some(self.numerator)
+    }
+}
+
+/// The gcd of the magnitudes, math::gcd only takes u64. Only the gcd of two i128::MIN (or of
+/// i128::MIN and 0) does not fit back in i128
+fn gcd(a: i128, b: i128) -> u128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl From<i128> for Fraction {
+    fn from(value: i128) -> Self {
+        Fraction::integer(value)
+    }
+}
+
+impl TryFrom<(i128, i128)> for Fraction {
+    type Error = Error;
+
+    fn try_from((numerator, denominator): (i128, i128)) -> Result<Self> {
+        if denominator == 0 {
+            return Err(Error::new("Fraction with a zero denominator"));
+        }
+        Ok(Fraction::new(numerator, denominator))
+    }
+}
+
+impl From<Fraction> for (i128, i128) {
+    fn from(fraction: Fraction) -> Self {
+        (fraction.numerator, fraction.denominator)
+    }
+}
+
+impl fmt::Display for Fraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+impl Add for Fraction {
+    type Output = Fraction;
+
+    fn add(self, other: Fraction) -> Fraction {
+        self.checked_add(other).expect("Fraction overflow")
+    }
+}
+
+impl AddAssign for Fraction {
+    fn add_assign(&mut self, other: Fraction) {
+        *self = *self + other;
+    }
+}
+
+impl Neg for Fraction {
+    type Output = Fraction;
+
+    fn neg(self) -> Fraction {
+        self.checked_neg().expect("Fraction overflow")
+    }
+}
+
+impl Sub for Fraction {
+    type Output = Fraction;
+
+    fn sub(self, other: Fraction) -> Fraction {
+        self + -other
+    }
+}
+
+impl SubAssign for Fraction {
+    fn sub_assign(&mut self, other: Fraction) {
+        *self = *self - other;
+    }
+}
+
+impl Mul for Fraction {
+    type Output = Fraction;
+
+    fn mul(self, other: Fraction) -> Fraction {
+        self.checked_mul(other).expect("Fraction overflow")
+    }
+}
+
+impl Div for Fraction {
+    type Output = Fraction;
+
+    /// Panics when dividing by zero
+    fn div(self, other: Fraction) -> Fraction {
+        assert!(!other.is_zero(), "Division of a fraction by zero");
+        self.checked_div(other).expect("Fraction overflow")
+    }
+}
+
+/// A matrix stored row by row
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Matrix<T> {
+    rows: usize,
+    columns: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Copy> Matrix<T> {
+    /// Every cell set to `value`
+    pub fn filled(rows: usize, columns: usize, value: T) -> Self {
+        Matrix {
+            rows,
+            columns,
+            cells: vec![value; rows * columns],
+        }
+    }
+
+    /// Panics when the rows do not have the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let columns = rows.first().map_or(0, Vec::len);
+        assert!(
+            rows.iter().all(|row| row.len() == columns),
+            "The rows of a matrix must have the same length"
+        );
+        Matrix {
+            rows: rows.len(),
+            columns,
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.columns..(row + 1) * self.columns]
+    }
+
+    pub fn transpose(&self) -> Matrix<T> {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for column in 0..self.columns {
+            for row in 0..self.rows {
+                cells.push(self[(row, column)]);
+            }
+        }
+        Matrix {
+            rows: self.columns,
+            columns: self.rows,
+            cells,
+        }
+    }
+
+    fn swap_rows(&mut self, first: usize, second: usize) {
+        for column in 0..self.columns {
+            self.cells.swap(
+                first * self.columns + column,
+                second * self.columns + column,
+            );
+        }
+    }
+}
+
+/// Fixed size matrices, ex: `Matrix::from([[1, 2], [3, 4]])`
+impl<T: Copy, const R: usize, const C: usize> From<[[T; C]; R]> for Matrix<T> {
+    fn from(rows: [[T; C]; R]) -> Self {
+        Matrix {
+            rows: R,
+            columns: C,
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+}
+
+/// Indexed by (row, column)
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, column): (usize, usize)) -> &T {
+        assert!(row < self.rows && column < self.columns);
+        &self.cells[row * self.columns + column]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut T {
+        assert!(row < self.rows && column < self.columns);
+        &mut self.cells[row * self.columns + column]
+    }
+}
+
+impl Matrix<Fraction> {
+    pub fn identity(size: usize) -> Self {
+        let mut identity = Matrix::filled(size, size, Fraction::ZERO);
+        for index in 0..size {
+            identity[(index, index)] = Fraction::ONE;
+        }
+        identity
+    }
+
+    /// A matrix of fractions from integers
+    pub fn from_integers(rows: Vec<Vec<i128>>) -> Self {
+        Matrix::from_rows(
+            rows.into_iter()
+                .map(|row| row.into_iter().map(Fraction::from).collect())
+                .collect(),
+        )
+    }
+
+    /// Gaussian elimination to the reduced row echelon form, in place. Returns the rank and the
+    /// determinant of the left square part (0 when the rank is lower than the number of rows),
+    /// fails when a fraction overflows i128 on the way
+    pub fn eliminate(&mut self) -> Result<(usize, Fraction)> {
+        let overflow = || Error::new("The fractions of the elimination overflow i128");
+        let mut rank = 0;
+        let mut determinant = Fraction::ONE;
+        for column in 0..self.columns {
+            if rank == self.rows {
+                break;
+            }
+            let Some(pivot) = (rank..self.rows).find(|row| !self[(*row, column)].is_zero()) else {
+                determinant = Fraction::ZERO;
+                continue;
+            };
+            if pivot != rank {
+                self.swap_rows(pivot, rank);
+                determinant = determinant.checked_neg().ok_or_else(overflow)?;
+            }
+            let pivot_value = self[(rank, column)];
+            determinant = determinant.checked_mul(pivot_value).ok_or_else(overflow)?;
+            for other in column..self.columns {
+                self[(rank, other)] = self[(rank, other)]
+                    .checked_div(pivot_value)
+                    .ok_or_else(overflow)?;
+            }
+            for row in 0..self.rows {
+                let factor = self[(row, column)];
+                if row == rank || factor.is_zero() {
+                    continue;
+                }
+                for other in column..self.columns {
+                    let value = self[(rank, other)];
+                    self[(row, other)] = factor
+                        .checked_mul(value)
+                        .and_then(|product| self[(row, other)].checked_sub(product))
+                        .ok_or_else(overflow)?;
+                }
+            }
+            rank += 1;
+        }
+        if rank < self.rows {
+            determinant = Fraction::ZERO;
+        }
+        Ok((rank, determinant))
+    }
+
+    /// Fails when a fraction overflows i128 during the elimination
+    pub fn determinant(&self) -> Result<Fraction> {
+        assert_eq!(
+            self.rows, self.columns,
+            "Only square matrices have a determinant"
+        );
+        Ok(self.clone().eliminate()?.1)
+    }
+
+    /// The x such that self * x = b, None when the matrix is not square or is singular. Fails
+    /// when a fraction overflows i128 during the elimination
+    pub fn solve(&self, b: &[Fraction]) -> Result<Option<Vec<Fraction>>> {
+        if self.rows != self.columns || b.len() != self.rows {
+            return Ok(None);
+        }
+        let mut augmented = Matrix::filled(self.rows, self.columns + 1, Fraction::ZERO);
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                augmented[(row, column)] = self[(row, column)];
+            }
+            augmented[(row, self.columns)] = b[row];
+        }
+        let (rank, _) = augmented.eliminate()?;
+        // The last column holds the solution once the left part is the identity
+        let solvable =
+            rank == self.rows && (0..self.rows).all(|row| augmented[(row, row)] == Fraction::ONE);
+        Ok(solvable.then(|| {
+            (0..self.rows)
+                .map(|row| augmented[(row, self.columns)])
+                .collect()
+        }))
+    }
+}
+
+impl Mul for &Matrix<Fraction> {
+    type Output = Matrix<Fraction>;
+
+    /// Panics when the sizes do not match
+    fn mul(self, other: &Matrix<Fraction>) -> Matrix<Fraction> {
+        assert_eq!(self.columns, other.rows, "Mismatched matrix sizes");
+        let mut product = Matrix::filled(self.rows, other.columns, Fraction::ZERO);
+        for row in 0..self.rows {
+            for column in 0..other.columns {
+                for index in 0..self.columns {
+                    product[(row, column)] += self[(row, index)] * other[(index, column)];
+                }
+            }
+        }
+        product
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fraction() {
+        crate::init_tests();
+
+        let half = Fraction::new(2, 4);
+        assert_eq!(half, Fraction::new(-1, -2));
+        assert_eq!((half.numerator(), half.denominator()), (1, 2));
+        let third = Fraction::new(1, 3);
+        assert_eq!(half + third, Fraction::new(5, 6));
+        assert_eq!(half - third, Fraction::new(1, 6));
+        assert_eq!(half * third, Fraction::new(1, 6));
+        assert_eq!(half / third, Fraction::new(3, 2));
+        assert_eq!(-half, Fraction::new(1, -2));
+        assert_eq!((half + half).to_integer(), Some(1));
+        assert_eq!(third.to_integer(), None);
+        assert_eq!(Fraction::new(-6, 4).to_string(), "-3/2");
+        assert_eq!(Fraction::from(7).to_string(), "7");
+        // Values past u64
+        let big = Fraction::new(10_i128.pow(30), 3 * 10_i128.pow(20));
+        assert_eq!(big, Fraction::new(10_i128.pow(10), 3));
+        let read: Fraction = serde_json::from_str("[4, -6]").unwrap();
+        assert_eq!(read, Fraction::new(-2, 3));
+        assert_eq!(serde_json::to_string(&read).unwrap(), "[-2,3]");
+        assert!(serde_json::from_str::<Fraction>("[1, 0]").is_err());
+
+        // The checked operations stop at i128
+        let max = Fraction::from(i128::MAX);
+        assert_eq!(max.checked_add(Fraction::ONE), None);
+        assert_eq!(
+            max.checked_add(-half),
+            Some(Fraction::new(i128::MAX * 2 - 1, 2))
+        );
+        assert_eq!(Fraction::from(i128::MIN).checked_neg(), None);
+        assert_eq!(max.checked_mul(Fraction::from(2)), None);
+        assert_eq!(max.checked_mul(half), Some(Fraction::new(i128::MAX, 2)));
+        assert_eq!(half.checked_div(Fraction::ZERO), None);
+        assert_eq!(
+            Fraction::ONE.checked_div(max),
+            Some(Fraction::new(1, i128::MAX))
+        );
+        assert_eq!(Fraction::checked_new(1, i128::MIN), None);
+    }
+
+    #[test]
+    fn test_matrix() {
+        crate::init_tests();
+
+        let matrix = Matrix::from([[1, 2, 3], [4, 5, 6]]);
+        assert_eq!((matrix.rows(), matrix.columns()), (2, 3));
+        assert_eq!(matrix[(1, 0)], 4);
+        assert_eq!(matrix.row(1), &[4, 5, 6]);
+        assert_eq!(matrix.transpose(), Matrix::from([[1, 4], [2, 5], [3, 6]]));
+
+        // 2x + y - z = 8, -3x - y + 2z = -11, -2x + y + 2z = -3
+        let system = Matrix::from_integers(vec![vec![2, 1, -1], vec![-3, -1, 2], vec![-2, 1, 2]]);
+        let b: Vec<Fraction> = [8, -11, -3].into_iter().map(Fraction::from).collect();
+        let solution = system.solve(&b).unwrap().unwrap();
+        assert_eq!(
+            solution.iter().map(|x| x.to_integer()).collect::<Vec<_>>(),
+            vec![Some(2), Some(3), Some(-1)]
+        );
+        assert_eq!(system.determinant().unwrap(), Fraction::from(-1));
+        let column = Matrix::from_rows(solution.iter().map(|x| vec![*x]).collect());
+        assert_eq!((&system * &column).transpose().row(0), &b[..]);
+        assert_eq!(&system * &Matrix::identity(3), system);
+
+        // A fractional solution: x + 2y = 1, 3x + 4y = 1
+        let system = Matrix::from_integers(vec![vec![1, 2], vec![3, 4]]);
+        let solution = system
+            .solve(&[Fraction::ONE, Fraction::ONE])
+            .unwrap()
+            .unwrap();
+        assert_eq!(solution, vec![Fraction::from(-1), Fraction::ONE]);
+
+        // Singular
+        let singular = Matrix::from_integers(vec![vec![1, 2], vec![2, 4]]);
+        assert_eq!(
+            singular.solve(&[Fraction::ONE, Fraction::ONE]).unwrap(),
+            None
+        );
+        assert_eq!(singular.determinant().unwrap(), Fraction::ZERO);
+        let mut reduced = singular.clone();
+        assert_eq!(reduced.eliminate().unwrap().0, 1);
+
+        // The determinant overflows on the way
+        let large = Matrix::from_integers(vec![vec![i128::MAX, 0], vec![0, 2]]);
+        assert!(large.determinant().is_err());
+        assert!(large.solve(&[Fraction::ONE, Fraction::ONE]).is_err());
+    }
+}
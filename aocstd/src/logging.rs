@@ -0,0 +1,48 @@
+//! Logging helpers for the hot loops of the solvers.
+//!
+//! The log macros only format their message when the level is enabled, but with --verbose a
+//! log in a loop over millions of values still floods the output and slows the solver down:
+//! `debug_sampled!` keeps the first calls of a loop and then only one out of N.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Whether a call of a sampled log is kept: the first `every` calls are, then one out of
+/// `every`
+pub fn sample(calls: &AtomicU64, every: u64) -> bool {
+    let every = every.max(1);
+    let call = calls.fetch_add(1, Ordering::Relaxed);
+    call < every || call.is_multiple_of(every)
+}
+
+/// Same as `log::debug!` but only for a sample of the calls made from this place (see
+/// `logging::sample`), nothing is counted when the debug level is disabled:
+///   aocstd::debug_sampled!(1000, "hold {} ms: {:?}", hold_button_time, race);
+#[macro_export]
+macro_rules! debug_sampled {
+    ($every:expr, $($arg:tt)+) => {{
+        if $crate::log::log_enabled!($crate::log::Level::Debug) {
+            static CALLS: ::std::sync::atomic::AtomicU64 = ::std::sync::atomic::AtomicU64::new(0);
+            if $crate::logging::sample(&CALLS, $every) {
+                $crate::log::debug!($($arg)+);
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sample() {
+        crate::init_tests();
+
+        let calls = AtomicU64::new(0);
+        let kept: Vec<u64> = (0..40).filter(|_| sample(&calls, 10)).collect();
+        assert_eq!(kept, vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 20, 30]);
+
+        for hold_button_time in 0..3 {
+            crate::debug_sampled!(2, "hold {} ms", hold_button_time);
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Number theory of the cycle puzzles: gcd and lcm of cycle lengths, and the chinese remainder
+//! theorem when the cycles have offsets. The signed helpers work on i128 so the products of two
+//! u64 moduli do not overflow.
+//!
+//! Also the areas of the lattice polygons (the loops drawn on a grid), where the vertices are the
+//! corners of the loop in order, clockwise or not
+use crate::geometry::Point;
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Panics when the result does not fit in u64
+pub fn lcm(a: u64, b: u64) -> u64 {
+    checked_lcm(a, b).unwrap_or_else(|| panic!("The lcm of {} and {} overflows u64", a, b))
+}
+
+/// None when the lcm does not fit in u64
+pub fn checked_lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// The lcm of every value, 1 when there are none. Panics when it does not fit in u64
+pub fn lcm_all(values: impl IntoIterator<Item = u64>) -> u64 {
+    values.into_iter().fold(1, lcm)
+}
+
+/// The lcm of every value, 1 when there are none and None when it does not fit in u64
+pub fn checked_lcm_all(values: impl IntoIterator<Item = u64>) -> Option<u64> {
+    values.into_iter().try_fold(1, checked_lcm)
+}
+
+/// (g, x, y) such that a * x + b * y = g, the gcd of a and b (positive unless both are 0)
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The x in 0..modulus such that a * x = 1 (mod modulus), None when a and modulus are not coprime
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// Chinese remainder theorem: the smallest x >= 0 such that x = residue (mod modulus) for every
+/// (residue, modulus), along with the lcm of the moduli (every solution is x plus a multiple of
+/// it). The moduli do not have to be coprime, None when the congruences contradict each other
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    let mut solution: i128 = 0;
+    let mut step: i128 = 1;
+    for &(residue, modulus) in congruences {
+        // solution + step * k = residue (mod modulus)
+        let (g, inverse, _) = extended_gcd(step, modulus);
+        let difference = residue - solution;
+        if difference.rem_euclid(g) != 0 {
+            return None;
+        }
+        let reduced_modulus = modulus / g;
+        let k = (difference / g % reduced_modulus * inverse).rem_euclid(reduced_modulus);
+        solution += step * k;
+        step *= reduced_modulus;
+        solution = solution.rem_euclid(step);
+    }
+    Some((solution, step))
+}
+
+/// Twice the area of the polygon with the shoelace formula, doubled so it stays exact
+pub fn double_area(vertices: &[Point]) -> u64 {
+    let Some(last) = vertices.last() else {
+        return 0;
+    };
+    let mut previous = *last;
+    let mut sum: i64 = 0;
+    for vertex in vertices {
+        sum += previous.x * vertex.y - vertex.x * previous.y;
+        previous = *vertex;
+    }
+    sum.unsigned_abs()
+}
+
+/// The number of lattice points on the edges of the polygon
+pub fn boundary_points(vertices: &[Point]) -> u64 {
+    let Some(last) = vertices.last() else {
+        return 0;
+    };
+    let mut previous = *last;
+    let mut count = 0;
+    for vertex in vertices {
+        let offset = *vertex - previous;
+        count += gcd(offset.x.unsigned_abs(), offset.y.unsigned_abs());
+        previous = *vertex;
+    }
+    count
+}
+
+/// Pick's theorem: the number of lattice points strictly inside a polygon from its doubled area
+/// and its number of boundary points (area = interior + boundary / 2 - 1). None when the
+/// polygon folds back on its edges, then the boundary is too long for the area
+pub fn pick_interior_points(double_area: u64, boundary_points: u64) -> Option<u64> {
+    Some((double_area.checked_add(2)?.checked_sub(boundary_points)?) / 2)
+}
+
+/// The number of lattice points strictly inside the polygon, ex: the tiles enclosed by a pipe loop
+pub fn interior_points(vertices: &[Point]) -> Option<u64> {
+    pick_interior_points(double_area(vertices), boundary_points(vertices))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        crate::init_tests();
+
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+        assert_eq!(lcm_all([]), 1);
+        // The cycle lengths of the haunted wasteland example are 2 and 3
+        assert_eq!(lcm_all([2, 3]), 6);
+        assert_eq!(lcm_all([4, 6, 10, 15]), 60);
+        // Large primes, the product fits in u64 but not in u32
+        assert_eq!(
+            lcm_all([4_294_967_291, 4_294_967_279]),
+            18446743979220271189
+        );
+
+        assert_eq!(checked_lcm(4, 6), Some(12));
+        assert_eq!(checked_lcm(0, u64::MAX), Some(0));
+        assert_eq!(checked_lcm(u64::MAX, u64::MAX), Some(u64::MAX));
+        assert_eq!(checked_lcm(u64::MAX, 2), None);
+        assert_eq!(checked_lcm_all([]), Some(1));
+        assert_eq!(checked_lcm_all([4, 6, 10, 15]), Some(60));
+        assert_eq!(checked_lcm_all([4_294_967_291, 4_294_967_279, 3]), None);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        crate::init_tests();
+
+        for (a, b) in [(240, 46), (-35, 15), (17, 0), (0, -4)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(a * x + b * y, g, "{} {}", a, b);
+            assert_eq!(
+                g,
+                gcd(a.unsigned_abs() as u64, b.unsigned_abs() as u64) as i128
+            );
+        }
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        crate::init_tests();
+
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Moduli sharing a factor
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+        // Large coprime moduli, the intermediate products do not fit in u64
+        let (a, b) = (4_294_967_291, 4_294_967_279);
+        let (x, step) = crt(&[(5, a), (7, b)]).unwrap();
+        assert_eq!(step, a * b);
+        assert_eq!((x % a, x % b), (5, 7));
+    }
+
+    #[test]
+    fn test_polygon() {
+        crate::init_tests();
+
+        let points = |coordinates: &[(i64, i64)]| -> Vec<Point> {
+            coordinates
+                .iter()
+                .map(|(x, y)| Point::new(*x, *y))
+                .collect()
+        };
+        assert_eq!(double_area(&[]), 0);
+        assert_eq!(boundary_points(&[]), 0);
+
+        // A 4x3 rectangle, in both orientations
+        let rectangle = points(&[(0, 0), (4, 0), (4, 3), (0, 3)]);
+        assert_eq!(double_area(&rectangle), 24);
+        let mut reversed = rectangle.clone();
+        reversed.reverse();
+        assert_eq!(double_area(&reversed), 24);
+        assert_eq!(boundary_points(&rectangle), 14);
+        assert_eq!(interior_points(&rectangle), Some(3 * 2));
+
+        // A triangle with diagonal edges, the first one goes through (2, 1)
+        let triangle = points(&[(0, 0), (4, 2), (0, 3)]);
+        assert_eq!(double_area(&triangle), 12);
+        assert_eq!(boundary_points(&triangle), 2 + 1 + 3);
+        assert_eq!(interior_points(&triangle), Some(4));
+
+        // The lagoon of the example: the trench is the boundary and the lagoon holds the interior
+        // as well
+        let lagoon = points(&[
+            (0, 0),
+            (6, 0),
+            (6, 5),
+            (4, 5),
+            (4, 7),
+            (6, 7),
+            (6, 9),
+            (1, 9),
+            (1, 7),
+            (0, 7),
+            (0, 5),
+            (2, 5),
+            (2, 2),
+            (0, 2),
+        ]);
+        let boundary = boundary_points(&lagoon);
+        assert_eq!(boundary, 38);
+        assert_eq!(interior_points(&lagoon).unwrap() + boundary, 62);
+
+        // A segment dug there and back has no area for its boundary
+        let segment = points(&[(2, 0), (0, 0)]);
+        assert_eq!(double_area(&segment), 0);
+        assert_eq!(interior_points(&segment), None);
+        assert_eq!(pick_interior_points(0, 2), Some(0));
+    }
+}
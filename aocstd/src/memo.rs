@@ -0,0 +1,183 @@
+//! Memoization of the recursive solvers: a cache owned by the caller and passed down the recursion,
+//! or the `memoize!` macro caching a function of owned arguments for the whole thread
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The results already computed, by key. A recursive function takes the memo and asks it for the
+/// results of the sub problems:
+///
+/// ```
+/// use aocstd::memo::Memo;
+///
+/// fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+///     memo.get(n, |memo, n| match n {
+///         0 | 1 => n,
+///         _ => fibonacci(memo, n - 1) + fibonacci(memo, n - 2),
+///     })
+/// }
+///
+/// assert_eq!(fibonacci(&mut Memo::new(), 90), 2880067194370816120);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    hits: u64,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Memo {
+            cache: HashMap::new(),
+            hits: 0,
+        }
+    }
+
+    /// The cached result of the key, or the result of `compute` which is cached for the next time.
+    /// `compute` gets the memo back for the recursive calls
+    pub fn get(&mut self, key: K, compute: impl FnOnce(&mut Self, K) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+        let value = compute(self, key.clone());
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    /// Number of results cached
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Number of calls answered from the cache
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.hits = 0;
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Memo::new()
+    }
+}
+
+/// Define a function caching its results by arguments in a thread local cache, which is kept
+/// between calls: only for pure functions. The arguments must be owned (`Vec<u8>`, not `&[u8]`)
+/// and implement Clone, Eq and Hash, the result must implement Clone.
+///
+/// ```
+/// aocstd::memoize! {
+///     /// Number of ways to climb the stairs by steps of 1 or 2
+///     fn ways(stairs: u64) -> u64 {
+///         if stairs <= 1 {
+///             1
+///         } else {
+///             ways(stairs - 1) + ways(stairs - 2)
+///         }
+///     }
+/// }
+///
+/// assert_eq!(ways(80), 37889062373143906);
+/// ```
+#[macro_export]
+macro_rules! memoize {
+    ($(#[$attribute:meta])* $visibility:vis fn $name:ident($($argument:ident: $type:ty),* $(,)?) -> $result:ty $body:block) => {
+        $(#[$attribute])*
+        $visibility fn $name($($argument: $type),*) -> $result {
+            fn compute($($argument: $type),*) -> $result $body
+
+            ::std::thread_local! {
+                static CACHE: ::std::cell::RefCell<::std::collections::HashMap<($($type,)*), $result>> =
+                    ::std::cell::RefCell::new(::std::collections::HashMap::new());
+            }
+            let key = ($($argument.clone(),)*);
+            if let Some(value) = CACHE.with(|cache| cache.borrow().get(&key).cloned()) {
+                return value;
+            }
+            // The borrow is released during the computation, the recursive calls fill the cache too
+            let value = compute($($argument),*);
+            CACHE.with(|cache| cache.borrow_mut().insert(key, value.clone()));
+            value
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Number of ways to place the groups of broken springs in the pattern
+    fn arrangements(
+        memo: &mut Memo<(Vec<u8>, Vec<usize>), u64>,
+        pattern: &[u8],
+        groups: &[usize],
+    ) -> u64 {
+        memo.get(
+            (pattern.to_vec(), groups.to_vec()),
+            |memo, (pattern, groups)| {
+                let Some((&group, rest)) = groups.split_first() else {
+                    return !pattern.contains(&b'#') as u64;
+                };
+                let mut total = 0;
+                for start in 0..pattern.len() {
+                    let end = start + group;
+                    if end > pattern.len() || pattern[..start].contains(&b'#') {
+                        break;
+                    }
+                    if pattern[start..end].contains(&b'.') || pattern.get(end) == Some(&b'#') {
+                        continue;
+                    }
+                    let next = (end + 1).min(pattern.len());
+                    total += arrangements(memo, &pattern[next..], rest);
+                }
+                total
+            },
+        )
+    }
+
+    #[test]
+    fn test_memo() {
+        crate::init_tests();
+
+        let mut memo = Memo::new();
+        assert_eq!(arrangements(&mut memo, b"???.###", &[1, 1, 3]), 1);
+        assert_eq!(arrangements(&mut memo, b"?###????????", &[3, 2, 1]), 10);
+        assert!(!memo.is_empty());
+        memo.clear();
+        assert_eq!((memo.len(), memo.hits()), (0, 0));
+
+        // Unfolded five times, hopeless without the cache
+        let pattern = [&b"?###????????"[..]; 5].join(&b'?');
+        let groups = [3, 2, 1].repeat(5);
+        assert_eq!(arrangements(&mut memo, &pattern, &groups), 506250);
+        assert!(memo.hits() > 0);
+    }
+
+    crate::memoize! {
+        fn paths(width: u64, height: u64) -> u64 {
+            if width == 0 || height == 0 {
+                1
+            } else {
+                paths(width - 1, height) + paths(width, height - 1)
+            }
+        }
+    }
+
+    #[test]
+    fn test_macro() {
+        crate::init_tests();
+
+        // The central binomial coefficient, as many calls without the cache
+        assert_eq!(paths(30, 30), 118264581564861424);
+        assert_eq!(paths(2, 1), 3);
+    }
+}
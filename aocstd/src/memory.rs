@@ -0,0 +1,236 @@
+//! Memory usage of the solvers, for the `--mem` flag of the runner
+//!
+//! The heap counters need the `mem` feature, which wraps the global allocator (the system one, or
+//! mimalloc/jemalloc when enabled) in a [`CountingAllocator`]. The peak RSS comes from the OS and
+//! is only available on Linux.
+use std::alloc::{GlobalAlloc, Layout};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// Allocator counting the allocations and the bytes in use of the allocator it wraps
+pub struct CountingAllocator<A> {
+    inner: A,
+    allocations: AtomicU64,
+    current: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl<A> CountingAllocator<A> {
+    pub const fn new(inner: A) -> Self {
+        CountingAllocator {
+            inner,
+            allocations: AtomicU64::new(0),
+            current: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        }
+    }
+
+    /// Start a new measure: zero the allocation count and bring the peak down to the current use
+    pub fn reset(&self) {
+        self.allocations.store(0, Ordering::Relaxed);
+        self.peak
+            .store(self.current.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> HeapStats {
+        HeapStats {
+            allocations: self.allocations.load(Ordering::Relaxed),
+            peak_bytes: self.peak.load(Ordering::Relaxed),
+        }
+    }
+
+    fn grow(&self, size: usize) {
+        let current = self.current.fetch_add(size, Ordering::Relaxed) + size;
+        self.peak.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn shrink(&self, size: usize) {
+        self.current.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc(layout);
+        if !ptr.is_null() {
+            self.allocations.fetch_add(1, Ordering::Relaxed);
+            self.grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            self.allocations.fetch_add(1, Ordering::Relaxed);
+            self.grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.inner.dealloc(ptr, layout);
+        self.shrink(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = self.inner.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            // A realloc is counted as an allocation, it is what the solvers should avoid too
+            self.allocations.fetch_add(1, Ordering::Relaxed);
+            self.shrink(layout.size());
+            self.grow(new_size);
+        }
+        new_ptr
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HeapStats {
+    pub allocations: u64,
+    pub peak_bytes: usize,
+}
+
+/// Heap and OS view of the memory used since the last [`reset`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// None without the `mem` feature
+    pub heap: Option<HeapStats>,
+    /// None outside of Linux
+    pub peak_rss_bytes: Option<u64>,
+}
+
+impl fmt::Display for MemoryStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.heap {
+            Some(heap) => write!(
+                f,
+                "{} allocations, {} peak heap",
+                heap.allocations,
+                Bytes(heap.peak_bytes as u64)
+            )?,
+            None => write!(f, "heap not measured (mem feature disabled)")?,
+        }
+        match self.peak_rss_bytes {
+            Some(rss) => write!(f, ", {} peak RSS", Bytes(rss)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Human readable size, in binary units
+pub struct Bytes(pub u64);
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+        if self.0 < 1024 {
+            return write!(f, "{} B", self.0);
+        }
+        let mut size = self.0 as f64 / 1024.0;
+        let mut unit = 0;
+        while size >= 1024.0 && unit < UNITS.len() - 1 {
+            size /= 1024.0;
+            unit += 1;
+        }
+        write!(f, "{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Start a new measure, so the stats only cover what runs after this call
+pub fn reset() {
+    #[cfg(feature = "mem")]
+    crate::GLOBAL.reset();
+    #[cfg(target_os = "linux")]
+    {
+        // Writing 5 to clear_refs resets the peak RSS of the process (Linux 4.0+), if it fails
+        // the peak includes what ran before
+        if let Err(error) = std::fs::write("/proc/self/clear_refs", "5") {
+            log::debug!("Could not reset the peak RSS: {}", error);
+        }
+    }
+}
+
+pub fn stats() -> MemoryStats {
+    #[cfg(feature = "mem")]
+    let heap = Some(crate::GLOBAL.stats());
+    #[cfg(not(feature = "mem"))]
+    let heap = None;
+    MemoryStats {
+        heap,
+        peak_rss_bytes: peak_rss(),
+    }
+}
+
+/// VmHWM of /proc/self/status
+#[cfg(target_os = "linux")]
+fn peak_rss() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    parse_vm_hwm(&status)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn peak_rss() -> Option<u64> {
+    None
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_vm_hwm(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kilobytes = line
+        .trim_start_matches("VmHWM:")
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kilobytes * 1024)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::alloc::System;
+
+    #[test]
+    fn test_counting_allocator() {
+        crate::init_tests();
+        let allocator = CountingAllocator::new(System);
+        let small = Layout::from_size_align(16, 8).unwrap();
+        let big = Layout::from_size_align(1024, 8).unwrap();
+        unsafe {
+            let a = allocator.alloc(small);
+            let b = allocator.alloc(big);
+            allocator.dealloc(b, big);
+            let a = allocator.realloc(a, small, 64);
+            assert_eq!(
+                allocator.stats(),
+                HeapStats {
+                    allocations: 3,
+                    peak_bytes: 1040
+                }
+            );
+            allocator.reset();
+            assert_eq!(
+                allocator.stats(),
+                HeapStats {
+                    allocations: 0,
+                    peak_bytes: 64
+                }
+            );
+            allocator.dealloc(a, Layout::from_size_align(64, 8).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        crate::init_tests();
+        assert_eq!(Bytes(512).to_string(), "512 B");
+        assert_eq!(Bytes(1536).to_string(), "1.5 KiB");
+        assert_eq!(Bytes(3 * 1024 * 1024).to_string(), "3.0 MiB");
+        assert_eq!(
+            parse_vm_hwm("Name:\taoc\nVmHWM:\t    4096 kB\n"),
+            Some(4096 * 1024)
+        );
+    }
+}
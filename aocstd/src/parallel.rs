@@ -0,0 +1,81 @@
+//! Data parallelism for the solvers that go through a lot of values (ex: the seed ranges of
+//! day 5), built on rayon.
+//!
+//! The work runs on the global rayon pool, its number of threads can be set once with
+//! `set_threads` (ex: from --threads), otherwise rayon uses every core (or RAYON_NUM_THREADS).
+use crate::{Error, Result};
+use rayon::prelude::*;
+use std::ops::Range;
+
+/// Set the number of threads of the pool, before any parallel work
+pub fn set_threads(threads: usize) -> Result<()> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build_global()
+        .map_err(|e| Error::new(format!("Could not set the number of threads: {}", e)))
+}
+
+/// Number of threads the parallel work runs on
+pub fn threads() -> usize {
+    rayon::current_num_threads()
+}
+
+/// Split ranges into chunks of at most `chunk_size` values
+pub fn chunks(ranges: impl IntoIterator<Item = Range<u64>>, chunk_size: u64) -> Vec<Range<u64>> {
+    let chunk_size = chunk_size.max(1);
+    let mut chunks = Vec::new();
+    for range in ranges {
+        let mut start = range.start;
+        while start < range.end {
+            let end = range.end.min(start.saturating_add(chunk_size));
+            chunks.push(start..end);
+            start = end;
+        }
+    }
+    chunks
+}
+
+/// Map every chunk of the ranges in parallel and combine the results with `reduce`
+/// (ex: `u64::min` for the lowest value), None when the ranges are empty
+pub fn reduce_chunks<T, M, R>(
+    ranges: impl IntoIterator<Item = Range<u64>>,
+    chunk_size: u64,
+    map: M,
+    reduce: R,
+) -> Option<T>
+where
+    T: Send,
+    M: Fn(Range<u64>) -> T + Sync + Send,
+    R: Fn(T, T) -> T + Sync + Send,
+{
+    chunks(ranges, chunk_size)
+        .into_par_iter()
+        .map(map)
+        .reduce_with(reduce)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_reduce_chunks() {
+        crate::init_tests();
+
+        assert_eq!(
+            chunks([0..5, 10..12, 20..20], 2),
+            vec![0..2, 2..4, 4..5, 10..12]
+        );
+        let lowest_square = reduce_chunks(
+            [3..1000, 7..50],
+            16,
+            |chunk| chunk.map(|x| x * x).min().unwrap(),
+            u64::min,
+        );
+        assert_eq!(lowest_square, Some(9));
+        assert_eq!(
+            reduce_chunks(std::iter::once(5..5), 16, |chunk| chunk.start, u64::min),
+            None
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Helpers for the lines which are numbers lost in some text, ex: "Card  12: 41 48 | 83 86"
+use crate::{Error, Result};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A line made of a labeled header and a payload cut in segments, "Card  12: 41 48 | 83 86" has
+/// the label "Card", the id 12 and the segments "41 48" and "83 86"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderLine<'a, T> {
+    pub label: &'a str,
+    pub id: T,
+    /// Trimmed, there is always at least one (maybe empty)
+    pub segments: Vec<&'a str>,
+}
+
+/// Split a "Label id: payload" line, the payload being cut on every `separator`. Any number of
+/// spaces can surround the id and the segments
+pub fn header_line<T: FromStr>(line: &str, separator: char) -> Result<HeaderLine<'_, T>> {
+    let (header, payload) = line
+        .split_once(':')
+        .ok_or_else(|| Error::new(format!("No ':' after the header of \"{}\"", line)))?;
+    let mut words = header.split_whitespace();
+    let (Some(label), Some(id), None) = (words.next(), words.next(), words.next()) else {
+        return Err(Error::new(format!(
+            "The header of \"{}\" is not a label followed by an id",
+            line
+        )));
+    };
+    let id = id
+        .parse()
+        .map_err(|_| Error::new(format!("Invalid id \"{}\" in \"{}\"", id, line)))?;
+    Ok(HeaderLine {
+        label,
+        id,
+        segments: payload.split(separator).map(str::trim).collect(),
+    })
+}
+
+/// Every integer of the text in order, whatever separates them. A '-' right before digits is a
+/// sign unless it follows a digit too, so "x=-3..5" gives -3 and 5 but "1-3" gives 1 and 3.
+/// Fails when a number does not fit in T (or is negative for an unsigned T)
+pub fn ints<T>(text: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let bytes = text.as_bytes();
+    let mut numbers = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        let is_sign = bytes[index] == b'-'
+            && bytes.get(index + 1).is_some_and(u8::is_ascii_digit)
+            && (index == 0 || !bytes[index - 1].is_ascii_digit());
+        if !is_sign && !bytes[index].is_ascii_digit() {
+            index += 1;
+            continue;
+        }
+        let start = index;
+        index += 1;
+        while index < bytes.len() && bytes[index].is_ascii_digit() {
+            index += 1;
+        }
+        let number = &text[start..index];
+        numbers.push(
+            number
+                .parse()
+                .map_err(|e| Error::new(format!("Cannot parse {} as a number: {}", number, e)))?,
+        );
+    }
+    Ok(numbers)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ints() {
+        crate::init_tests();
+
+        assert_eq!(
+            ints::<u32>("Card  12: 41 48 | 83 86").unwrap(),
+            vec![12, 41, 48, 83, 86]
+        );
+        assert_eq!(ints::<i64>("x=-3..5, y=-10").unwrap(), vec![-3, 5, -10]);
+        assert_eq!(ints::<i64>("1-3 a: -").unwrap(), vec![1, 3]);
+        assert_eq!(ints::<u64>("seed-to-soil map:").unwrap(), Vec::<u64>::new());
+        assert_eq!(
+            ints::<u64>("Time:      7  15   30").unwrap(),
+            vec![7, 15, 30]
+        );
+        assert_eq!(ints::<u8>("Game 255").unwrap(), vec![255]);
+
+        for (text, number) in [("Game 256", "256"), ("x=-3", "-3")] {
+            let message = ints::<u8>(text).unwrap_err().to_string();
+            assert!(message.contains(number), "{}", message);
+        }
+    }
+
+    #[test]
+    fn test_header_line() {
+        crate::init_tests();
+
+        assert_eq!(
+            header_line::<u32>("Card  12: 41 48 | 83 86", '|').unwrap(),
+            HeaderLine {
+                label: "Card",
+                id: 12,
+                segments: vec!["41 48", "83 86"]
+            }
+        );
+        let game = header_line::<i32>("Game 3: 8 green; 5 blue", ';').unwrap();
+        assert_eq!((game.label, game.id), ("Game", 3));
+        assert_eq!(game.segments, vec!["8 green", "5 blue"]);
+        assert_eq!(
+            header_line::<u32>("Card 1:", '|').unwrap().segments,
+            vec![""]
+        );
+
+        for (line, error) in [
+            ("Card 1 41 48", "No ':'"),
+            ("Card: 41 48", "not a label followed by an id"),
+            ("Card x: 41", "Invalid id \"x\""),
+        ] {
+            let message = header_line::<u32>(line, '|').unwrap_err().to_string();
+            assert!(message.contains(error), "{}", message);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Parser combinators for the usual shapes of the inputs, built on nom (re-exported so the days do
+//! not need the dependency). `parse_all` runs a parser on the whole input and turns the failures
+//! into errors telling where the input stopped making sense, instead of a panic in some slicing
+pub use nom;
+
+use crate::{Error, Result};
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, digit1, line_ending, multispace0, none_of, space0, space1};
+use nom::combinator::{all_consuming, map_opt, map_res, opt, recognize};
+use nom::multi::{many1, separated_list0, separated_list1};
+use nom::sequence::{delimited, pair, preceded, separated_pair, terminated, tuple};
+use nom::Parser;
+use std::str::FromStr;
+
+pub type IResult<'a, T> = nom::IResult<&'a str, T>;
+
+/// A decimal integer with an optional '-'
+pub fn integer<T: FromStr>(input: &str) -> IResult<'_, T> {
+    map_res(recognize(pair(opt(char('-')), digit1)), str::parse)(input)
+}
+
+/// Integers separated by spaces: "41 48  83"
+pub fn integers<T: FromStr>(input: &str) -> IResult<'_, Vec<T>> {
+    separated_list0(space1, integer)(input)
+}
+
+/// A header with an id like "Card  12:" (the spaces after the colon included), gives the id
+pub fn header<'a, T: FromStr>(label: &'static str) -> impl FnMut(&'a str) -> IResult<'a, T> {
+    delimited(pair(tag(label), space1), integer, pair(char(':'), space0))
+}
+
+/// A labeled list of integers like "Time:      7  15   30"
+pub fn labeled_integers<'a, T: FromStr>(
+    label: &'static str,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<T>> {
+    preceded(tuple((tag(label), char(':'), space0)), integers)
+}
+
+/// A key and a value on both sides of a separator, with optional spaces around it: "x = 12"
+pub fn key_value<'a, K, V>(
+    key: impl Parser<&'a str, K, nom::error::Error<&'a str>>,
+    separator: &'static str,
+    value: impl Parser<&'a str, V, nom::error::Error<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<'a, (K, V)> {
+    separated_pair(key, tuple((space0, tag(separator), space0)), value)
+}
+
+/// One item per line, the trailing line ending and blank lines included
+pub fn lines<'a, T>(
+    item: impl Parser<&'a str, T, nom::error::Error<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<T>> {
+    terminated(separated_list1(line_ending, item), multispace0)
+}
+
+/// Rows of cells, each character being turned into a cell by `cell` (None fails the parsing)
+pub fn grid<'a, T>(
+    cell: impl Fn(char) -> Option<T>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<Vec<T>>> {
+    lines(many1(map_opt(none_of("\r\n"), cell)))
+}
+
+/// A run of letters, ex: "red" or "blue" in "3 blue, 4 red"
+pub fn word(input: &str) -> IResult<'_, &str> {
+    nom::character::complete::alpha1(input)
+}
+
+/// Run the parser on the whole input, the errors give the line and column where the parsing
+/// failed along with the rest of that line
+pub fn parse_all<'a, T>(
+    parser: impl Parser<&'a str, T, nom::error::Error<&'a str>>,
+    input: &'a str,
+) -> Result<T> {
+    match all_consuming(parser)(input) {
+        Ok((_, value)) => Ok(value),
+        Err(nom::Err::Error(error) | nom::Err::Failure(error)) => {
+            let offset = input.len() - error.input.len();
+            let before = &input[..offset];
+            let line = before.matches('\n').count() + 1;
+            let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+            let rest = error.input.lines().next().unwrap_or("");
+            Err(Error::new(format!(
+                "Parse error at line {}, column {} ({}): \"{}\"",
+                line,
+                column,
+                error.code.description(),
+                rest
+            )))
+        }
+        Err(nom::Err::Incomplete(_)) => Err(Error::new("Parse error: unexpected end of input")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nom::branch::alt;
+
+    #[test]
+    fn test_parsers() {
+        crate::init_tests();
+
+        assert_eq!(parse_all(integer::<i64>, "-42").unwrap(), -42);
+        assert_eq!(
+            parse_all(
+                pair(header::<u32>("Card"), integers::<u32>),
+                "Card  12: 41 48  83"
+            )
+            .unwrap(),
+            (12, vec![41, 48, 83])
+        );
+        assert_eq!(
+            parse_all(labeled_integers::<u64>("Time"), "Time:      7  15   30").unwrap(),
+            vec![7, 15, 30]
+        );
+        assert_eq!(
+            parse_all(key_value(word, "=", integer::<i32>), "x = 12").unwrap(),
+            ("x", 12)
+        );
+        assert_eq!(
+            parse_all(lines(alt((word, digit1))), "ab\n12\r\ncd\n").unwrap(),
+            vec!["ab", "12", "cd"]
+        );
+        let cells = |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        };
+        assert_eq!(
+            parse_all(grid(cells), "#.\n.#\n").unwrap(),
+            vec![vec![true, false], vec![false, true]]
+        );
+
+        // The errors tell where the parsing stopped
+        let error = parse_all(grid(cells), "#.\n.x\n").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("Parse error at line 2, column 2"),
+            "{}",
+            error
+        );
+        let error = parse_all(header::<u32>("Game"), "Game x:").unwrap_err();
+        assert!(error.to_string().contains("\"x:\""), "{}", error);
+    }
+}
@@ -0,0 +1,327 @@
+//! Graph searches over nodes of any type, the graph is given by closures returning the neighbors
+//! of a node so the node can carry whatever state the puzzle needs (ex: a position along with a
+//! direction and a number of steps).
+//!
+//! Every search stops as soon as a node matching `is_goal` is reached, pass `|_| false` to explore
+//! everything reachable.
+use crate::queue::PriorityQueue;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Whether the searches remember where each node was reached from, to rebuild the paths
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parents {
+    Track,
+    Skip,
+}
+
+/// What a search found: the distance of every settled node from the closest start
+#[derive(Debug, Clone)]
+pub struct Search<N> {
+    pub distances: HashMap<N, u64>,
+    /// Only with `Parents::Track`, the starts have no parent
+    pub parents: Option<HashMap<N, N>>,
+    /// The goal the search stopped at, if any
+    pub goal: Option<N>,
+}
+
+impl<N: Clone + Eq + Hash> Search<N> {
+    fn new(parents: Parents) -> Self {
+        Search {
+            distances: HashMap::new(),
+            parents: (parents == Parents::Track).then(HashMap::new),
+            goal: None,
+        }
+    }
+
+    fn settle(&mut self, node: N, distance: u64, parent: Option<N>) {
+        if let (Some(parents), Some(parent)) = (&mut self.parents, parent) {
+            parents.insert(node.clone(), parent);
+        }
+        self.distances.insert(node, distance);
+    }
+
+    pub fn distance(&self, node: &N) -> Option<u64> {
+        self.distances.get(node).copied()
+    }
+
+    /// Distance of the goal the search stopped at
+    pub fn goal_distance(&self) -> Option<u64> {
+        self.distance(self.goal.as_ref()?)
+    }
+
+    /// The nodes from a start to `node` included, None when the parents were not tracked or the
+    /// node was not reached
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        let parents = self.parents.as_ref()?;
+        self.distances.get(node)?;
+        let mut path = vec![node.clone()];
+        while let Some(parent) = parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth first search, every edge costs 1
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+    parents: Parents,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new(parents);
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if !search.distances.contains_key(&start) {
+            search.settle(start.clone(), 0, None);
+            queue.push_back(start);
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+        let distance = search.distances[&node];
+        for neighbor in neighbors(&node) {
+            if !search.distances.contains_key(&neighbor) {
+                search.settle(neighbor.clone(), distance + 1, Some(node.clone()));
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    search
+}
+
+/// Depth first search, the distances are the depths in the search tree (not the shortest ones),
+/// the first neighbors are explored first
+pub fn dfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+    parents: Parents,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new(parents);
+    let mut stack: Vec<(N, u64, Option<N>)> =
+        starts.into_iter().map(|start| (start, 0, None)).collect();
+    stack.reverse();
+    while let Some((node, depth, parent)) = stack.pop() {
+        if search.distances.contains_key(&node) {
+            continue;
+        }
+        search.settle(node.clone(), depth, parent);
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+        let next: Vec<N> = neighbors(&node)
+            .into_iter()
+            .filter(|neighbor| !search.distances.contains_key(neighbor))
+            .collect();
+        for neighbor in next.into_iter().rev() {
+            stack.push((neighbor, depth + 1, Some(node.clone())));
+        }
+    }
+    search
+}
+
+/// Shortest paths with weighted edges, `edges` returns the neighbors along with the cost to reach
+/// them
+pub fn dijkstra<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    edges: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+    parents: Parents,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    astar(starts, edges, |_| 0, is_goal, parents)
+}
+
+/// Dijkstra guided by a heuristic, which must never overestimate the remaining cost to a goal
+/// (ex: the manhattan distance on a grid where every step costs at least 1)
+pub fn astar<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut edges: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> u64,
+    mut is_goal: impl FnMut(&N) -> bool,
+    parents: Parents,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    let mut search = Search::new(parents);
+    // Ordered by the estimated total cost, then by the cost so far. For a given node the
+    // heuristic is always the same so a lower priority is a lower cost
+    let mut queue = PriorityQueue::new();
+    // Where the queued nodes were reached from with their best known cost
+    let mut queued_parents: HashMap<N, Option<N>> = HashMap::new();
+    for start in starts {
+        if queue.push(start.clone(), (heuristic(&start), 0)) {
+            queued_parents.insert(start, None);
+        }
+    }
+    while let Some((node, (_, cost))) = queue.pop() {
+        let parent = queued_parents.remove(&node).flatten();
+        search.settle(node.clone(), cost, parent);
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+        for (neighbor, edge_cost) in edges(&node) {
+            if search.distances.contains_key(&neighbor) {
+                continue;
+            }
+            let neighbor_cost = cost + edge_cost;
+            let priority = (neighbor_cost + heuristic(&neighbor), neighbor_cost);
+            if queue.push(neighbor.clone(), priority) {
+                queued_parents.insert(neighbor, Some(node.clone()));
+            }
+        }
+    }
+    search
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::{Direction, Point};
+    use crate::grid::Grid;
+
+    const MAZE: &str = "S.#.....\n\
+                        .##.###.\n\
+                        ....#..E\n\
+                        .##...#.";
+
+    fn maze() -> (Grid<char>, Point, Point) {
+        let grid = Grid::from_lines(MAZE, '#', |c| c);
+        let find = |target| grid.iter().find(|(_, c)| **c == target).unwrap().0;
+        let (start, end) = (find('S'), find('E'));
+        (grid, start, end)
+    }
+
+    fn open_neighbors(grid: &Grid<char>, point: Point) -> Vec<Point> {
+        grid.neighbors4(point)
+            .filter(|neighbor| grid[*neighbor] != '#')
+            .collect()
+    }
+
+    #[test]
+    fn test_bfs_and_dfs() {
+        crate::init_tests();
+
+        let (grid, start, end) = maze();
+        let search = bfs(
+            [start],
+            |point| open_neighbors(&grid, *point),
+            |point| *point == end,
+            Parents::Track,
+        );
+        assert_eq!(search.goal, Some(end));
+        assert_eq!(search.goal_distance(), Some(11));
+        let path = search.path_to(&end).unwrap();
+        assert_eq!(path.len(), 12);
+        assert_eq!(path[0], start);
+        assert!(path
+            .windows(2)
+            .all(|step| step[0].manhattan_distance(step[1]) == 1));
+
+        let everything = bfs(
+            [start],
+            |point| open_neighbors(&grid, *point),
+            |_| false,
+            Parents::Skip,
+        );
+        assert_eq!(
+            everything.distances.len(),
+            grid.iter().filter(|(_, c)| **c != '#').count()
+        );
+        assert_eq!(everything.path_to(&end), None);
+
+        let depth_first = dfs(
+            [start],
+            |point| open_neighbors(&grid, *point),
+            |point| *point == end,
+            Parents::Track,
+        );
+        let path = depth_first.path_to(&end).unwrap();
+        assert!(path.len() >= 12);
+        assert_eq!(depth_first.goal_distance(), Some(path.len() as u64 - 1));
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        crate::init_tests();
+
+        // Entering a cell costs its digit
+        let grid = Grid::from_lines("1163\n1381\n2136\n3694", 0, |c| {
+            c.to_digit(10).unwrap() as u64
+        });
+        let end = Point::new(3, 3);
+        let edges = |point: &Point| -> Vec<(Point, u64)> {
+            grid.neighbors4(*point)
+                .map(|neighbor| (neighbor, grid[neighbor]))
+                .collect()
+        };
+        let search = dijkstra(
+            [Point::ORIGIN],
+            edges,
+            |point| *point == end,
+            Parents::Track,
+        );
+        // Down twice then right to the last column and down: 1 + 2 + 1 + 3 + 6 + 4
+        let best = search.goal_distance().unwrap();
+        let path = search.path_to(&end).unwrap();
+        assert_eq!(
+            path.iter().skip(1).map(|point| grid[*point]).sum::<u64>(),
+            best
+        );
+        assert_eq!(best, 17);
+
+        let guided = astar(
+            [Point::ORIGIN],
+            edges,
+            |point| point.manhattan_distance(end),
+            |point| *point == end,
+            Parents::Skip,
+        );
+        assert_eq!(guided.goal_distance(), Some(best));
+
+        // A state with a direction, turning costs 10 and going straight 1
+        let (maze, start, end) = maze();
+        let turns = dijkstra(
+            [(start, Direction::East)],
+            |(point, direction): &(Point, Direction)| {
+                let mut next = vec![
+                    (*point, direction.turn_left()),
+                    (*point, direction.turn_right()),
+                ]
+                .into_iter()
+                .map(|state| (state, 10))
+                .collect::<Vec<_>>();
+                let ahead = *point + *direction;
+                if maze.get(ahead).is_some_and(|c| *c != '#') {
+                    next.push(((ahead, *direction), 1));
+                }
+                next
+            },
+            |(point, _)| *point == end,
+            Parents::Skip,
+        );
+        // South, east on the third line, north, east on the first line and south to the end
+        assert_eq!(turns.goal_distance(), Some(13 + 10 * 5));
+    }
+}
@@ -0,0 +1,78 @@
+//! Opt-in CPU profiling of the solvers, written as a flamegraph SVG so nobody has to set up perf
+//! for each binary.
+//!
+//! The sampling needs the `profile` feature and a unix system, without them `start` fails.
+use crate::{Error, Result};
+use std::path::{Path, PathBuf};
+
+/// Samples per second, high enough for the solvers that run in a few milliseconds
+#[cfg_attr(not(all(feature = "profile", unix)), allow(dead_code))]
+const FREQUENCY: i32 = 1000;
+
+#[cfg(all(feature = "profile", unix))]
+mod sampler {
+    use super::*;
+    use std::cell::RefCell;
+
+    thread_local! {
+        // The guard is not Send, it is started and finished by the main thread anyway
+        static PROFILE: RefCell<Option<(pprof::ProfilerGuard<'static>, PathBuf)>> =
+            const { RefCell::new(None) };
+    }
+
+    pub fn start(path: &Path) -> Result<()> {
+        let guard = pprof::ProfilerGuardBuilder::default()
+            .frequency(FREQUENCY)
+            .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+            .build()
+            .map_err(|error| Error::new(format!("Could not start the profiler: {}", error)))?;
+        PROFILE.with(|profile| *profile.borrow_mut() = Some((guard, path.to_path_buf())));
+        Ok(())
+    }
+
+    pub fn finish() -> Result<Option<PathBuf>> {
+        let Some((guard, path)) = PROFILE.with(|profile| profile.borrow_mut().take()) else {
+            return Ok(None);
+        };
+        let report = guard
+            .report()
+            .build()
+            .map_err(|error| Error::new(format!("Could not build the profile: {}", error)))?;
+        if report.data.is_empty() {
+            // inferno writes an empty file instead of failing
+            return Err(Error::new(
+                "No sample in the profile, the solve was shorter than the sampling period",
+            ));
+        }
+        let file = std::fs::File::create(&path)?;
+        report
+            .flamegraph(std::io::BufWriter::new(file))
+            .map_err(|error| Error::new(format!("Could not write the flamegraph: {}", error)))?;
+        Ok(Some(path))
+    }
+}
+
+#[cfg(not(all(feature = "profile", unix)))]
+mod sampler {
+    use super::*;
+
+    pub fn start(_path: &Path) -> Result<()> {
+        Err(Error::new(
+            "Profiling needs the profile feature on a unix system (ex: cargo run --features profile)",
+        ))
+    }
+
+    pub fn finish() -> Result<Option<PathBuf>> {
+        Ok(None)
+    }
+}
+
+/// Start sampling the CPU, the flamegraph is written to `path` by `finish`
+pub fn start(path: &Path) -> Result<()> {
+    sampler::start(path)
+}
+
+/// Stop sampling and write the flamegraph, returns where it was written if a profile was running
+pub fn finish() -> Result<Option<PathBuf>> {
+    sampler::finish()
+}
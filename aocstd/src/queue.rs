@@ -0,0 +1,131 @@
+//! A min priority queue of distinct items whose priority can be lowered, for the Dijkstra like
+//! searches. The heap cannot update its entries so lowering a priority pushes a new entry, the old
+//! one is skipped when it comes out (lazy deletion)
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+#[derive(Debug, Clone)]
+pub struct PriorityQueue<T, P> {
+    heap: BinaryHeap<Entry<T, P>>,
+    /// The current priority of every item in the queue
+    priorities: HashMap<T, P>,
+}
+
+impl<T: Clone + Eq + Hash, P: Copy + Ord> PriorityQueue<T, P> {
+    pub fn new() -> Self {
+        PriorityQueue {
+            heap: BinaryHeap::new(),
+            priorities: HashMap::new(),
+        }
+    }
+
+    /// Add the item, or lower its priority when it is already queued with a higher one. Returns
+    /// false when the item was already queued with a lower or equal priority
+    pub fn push(&mut self, item: T, priority: P) -> bool {
+        if self
+            .priorities
+            .get(&item)
+            .is_some_and(|current| *current <= priority)
+        {
+            return false;
+        }
+        self.priorities.insert(item.clone(), priority);
+        self.heap.push(Entry { priority, item });
+        true
+    }
+
+    /// The item with the lowest priority, the ties come out in any order
+    pub fn pop(&mut self) -> Option<(T, P)> {
+        while let Some(Entry { priority, item }) = self.heap.pop() {
+            // Outdated entries of the items whose priority was lowered (or already popped)
+            if self.priorities.get(&item) == Some(&priority) {
+                self.priorities.remove(&item);
+                return Some((item, priority));
+            }
+        }
+        None
+    }
+
+    pub fn priority(&self, item: &T) -> Option<P> {
+        self.priorities.get(item).copied()
+    }
+
+    pub fn contains(&self, item: &T) -> bool {
+        self.priorities.contains_key(item)
+    }
+
+    /// Number of items queued, the outdated entries are not counted
+    pub fn len(&self) -> usize {
+        self.priorities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.priorities.is_empty()
+    }
+}
+
+impl<T: Clone + Eq + Hash, P: Copy + Ord> Default for PriorityQueue<T, P> {
+    fn default() -> Self {
+        PriorityQueue::new()
+    }
+}
+
+/// An entry of the heap, ordered by priority only so the items need no ordering
+#[derive(Debug, Clone)]
+struct Entry<T, P> {
+    priority: P,
+    item: T,
+}
+
+impl<T, P: Ord> PartialEq for Entry<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<T, P: Ord> Eq for Entry<T, P> {}
+
+impl<T, P: Ord> PartialOrd for Entry<T, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T, P: Ord> Ord for Entry<T, P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // BinaryHeap is a max-heap, the lowest priority must come first
+        other.priority.cmp(&self.priority)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_priority_queue() {
+        crate::init_tests();
+
+        let mut queue = PriorityQueue::new();
+        assert!(queue.push("a", 5));
+        assert!(queue.push("b", 3));
+        assert!(queue.push("c", 8));
+        // Lowered, then not raised back
+        assert!(queue.push("c", 1));
+        assert!(!queue.push("c", 4));
+        assert!(!queue.push("b", 3));
+        assert_eq!(queue.priority(&"c"), Some(1));
+        assert_eq!(queue.len(), 3);
+
+        assert_eq!(queue.pop(), Some(("c", 1)));
+        assert!(!queue.contains(&"c"));
+        assert_eq!(queue.pop(), Some(("b", 3)));
+        // Popped items can be queued again
+        assert!(queue.push("c", 2));
+        assert_eq!(queue.pop(), Some(("c", 2)));
+        assert_eq!(queue.pop(), Some(("a", 5)));
+        assert_eq!(queue.pop(), None);
+        assert!(queue.is_empty());
+    }
+}
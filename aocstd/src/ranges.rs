@@ -0,0 +1,494 @@
+//! Sets of values stored as their ranges, for the puzzles where the values are far too many to
+//! be stored one by one (ex: the seed ranges of day 5), and maps from ranges of values to what
+//! happens to them
+use crate::{Error, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::ops::{Range, Sub};
+
+/// The values both ranges hold, None when they do not overlap
+pub fn intersection<T: Ord + Copy>(a: &Range<T>, b: &Range<T>) -> Option<Range<T>> {
+    let range = a.start.max(b.start)..a.end.min(b.end);
+    (range.start < range.end).then_some(range)
+}
+
+pub fn overlaps<T: Ord + Copy>(a: &Range<T>, b: &Range<T>) -> bool {
+    intersection(a, b).is_some()
+}
+
+/// The range squeezed in the bounds, empty (at one of the bounds) when they do not overlap
+pub fn clamp<T: Ord + Copy>(range: &Range<T>, bounds: &Range<T>) -> Range<T> {
+    let clamp = |value: T| value.max(bounds.start).min(bounds.end);
+    clamp(range.start)..clamp(range.end).max(clamp(range.start))
+}
+
+/// The values before `at` and the others, one of them is empty when `at` is out of the range
+pub fn split_at<T: Ord + Copy>(range: &Range<T>, at: T) -> (Range<T>, Range<T>) {
+    let at = at.max(range.start).min(range.end.max(range.start));
+    (range.start..at, at..range.end.max(at))
+}
+
+/// The range moved by a signed distance, None when one of its ends leaves the type
+pub fn offset<T: Shift>(range: &Range<T>, delta: T::Delta) -> Option<Range<T>> {
+    Some(range.start.checked_shift(delta)?..range.end.checked_shift(delta)?)
+}
+
+/// The integers a range can be moved along, by a distance of the signed type of the same size
+pub trait Shift: Copy {
+    type Delta: Copy;
+    fn checked_shift(self, delta: Self::Delta) -> Option<Self>;
+}
+
+macro_rules! impl_shift {
+    ($($type:ty => $delta:ty, $method:ident);*) => {
+        $(
+            impl Shift for $type {
+                type Delta = $delta;
+
+                fn checked_shift(self, delta: $delta) -> Option<Self> {
+                    self.$method(delta)
+                }
+            }
+        )*
+    };
+}
+
+impl_shift!(
+    u8 => i8, checked_add_signed; u16 => i16, checked_add_signed;
+    u32 => i32, checked_add_signed; u64 => i64, checked_add_signed;
+    u128 => i128, checked_add_signed; usize => isize, checked_add_signed;
+    i8 => i8, checked_add; i16 => i16, checked_add; i32 => i32, checked_add;
+    i64 => i64, checked_add; i128 => i128, checked_add; isize => isize, checked_add
+);
+
+/// Half-open ranges kept sorted, disjoint and merged: two ranges that overlap or touch become
+/// one, and the empty ones are dropped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet { ranges: Vec::new() }
+    }
+}
+
+impl<T: Ord + Copy> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    /// Add a range, merging it with the ranges it overlaps or touches
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.start >= range.end {
+            return;
+        }
+        // The ranges from first to last (excluded) overlap or touch the new one
+        let first = self.ranges.partition_point(|other| other.end < range.start);
+        let last = self
+            .ranges
+            .partition_point(|other| other.start <= range.end);
+        let merged = if first < last {
+            range.start.min(self.ranges[first].start)..range.end.max(self.ranges[last - 1].end)
+        } else {
+            range
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.range_containing(value).is_some()
+    }
+
+    /// The range of the set a value is in
+    pub fn range_containing(&self, value: T) -> Option<&Range<T>> {
+        let index = self.ranges.partition_point(|range| range.end <= value);
+        self.ranges.get(index).filter(|range| range.start <= value)
+    }
+
+    /// The ranges in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = &Range<T>> {
+        self.ranges.iter()
+    }
+
+    /// The number of ranges, not of values
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The smallest range holding every value of the set
+    pub fn bounds(&self) -> Option<Range<T>> {
+        Some(self.ranges.first()?.start..self.ranges.last()?.end)
+    }
+
+    /// The number of values in the set
+    pub fn covered(&self) -> T
+    where
+        T: Sub<Output = T> + std::iter::Sum<T>,
+    {
+        self.ranges
+            .iter()
+            .map(|range| range.end - range.start)
+            .sum()
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut union = self.clone();
+        for range in &other.ranges {
+            union.insert(range.clone());
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut ranges = Vec::new();
+        let (mut a, mut b) = (0, 0);
+        while a < self.ranges.len() && b < other.ranges.len() {
+            let (left, right) = (&self.ranges[a], &other.ranges[b]);
+            ranges.extend(intersection(left, right));
+            // The range ending first cannot overlap the next ones of the other set
+            if left.end < right.end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    /// The values of the bounds that are not in the set
+    pub fn complement(&self, bounds: Range<T>) -> IntervalSet<T> {
+        let mut ranges = Vec::new();
+        let mut cursor = bounds.start;
+        for range in &self.ranges {
+            if range.end <= cursor {
+                continue;
+            }
+            if range.start >= bounds.end {
+                break;
+            }
+            if range.start > cursor {
+                ranges.push(cursor..range.start);
+            }
+            cursor = range.end;
+        }
+        if cursor < bounds.end {
+            ranges.push(cursor..bounds.end);
+        }
+        IntervalSet { ranges }
+    }
+
+    /// The values of this set that are not in the other one
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        match self.bounds() {
+            Some(bounds) => self.intersection(&other.complement(bounds)),
+            None => IntervalSet::new(),
+        }
+    }
+}
+
+impl<T: Ord + Copy> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+/// Serialized as the list of its ranges
+impl<T: Serialize> Serialize for IntervalSet<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.ranges.serialize(serializer)
+    }
+}
+
+/// The ranges read back are merged again, they may come from anywhere
+impl<'de, T: Deserialize<'de> + Ord + Copy> Deserialize<'de> for IntervalSet<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Ok(Vec::<Range<T>>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
+/// Disjoint ranges each holding a value, sorted by range. The values outside of every range have
+/// no entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalMap<T, V> {
+    entries: Vec<(Range<T>, V)>,
+}
+
+impl<T, V> Default for IntervalMap<T, V> {
+    fn default() -> Self {
+        IntervalMap {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<T: Ord + Copy, V> IntervalMap<T, V> {
+    pub fn new() -> Self {
+        IntervalMap::default()
+    }
+
+    /// Build the map from entries in any order, failing on the first overlap like `insert`
+    pub fn from_entries(entries: impl IntoIterator<Item = (Range<T>, V)>) -> Result<Self>
+    where
+        T: fmt::Debug,
+    {
+        let mut map = IntervalMap::new();
+        for (range, value) in entries {
+            map.insert(range, value)?;
+        }
+        Ok(map)
+    }
+
+    /// Add an entry, this fails when its range overlaps another one since a value would have two
+    /// entries. The empty ranges are ignored
+    pub fn insert(&mut self, range: Range<T>, value: V) -> Result<()>
+    where
+        T: fmt::Debug,
+    {
+        if range.start >= range.end {
+            return Ok(());
+        }
+        let index = self
+            .entries
+            .partition_point(|(other, _)| other.end <= range.start);
+        if let Some((other, _)) = self.entries.get(index) {
+            if other.start < range.end {
+                return Err(Error::new(format!(
+                    "The range {:?} overlaps {:?}",
+                    range, other
+                )));
+            }
+        }
+        self.entries.insert(index, (range, value));
+        Ok(())
+    }
+
+    /// The entry a value is in
+    pub fn get(&self, value: T) -> Option<(&Range<T>, &V)> {
+        let index = self
+            .entries
+            .partition_point(|(range, _)| range.end <= value);
+        self.entries
+            .get(index)
+            .filter(|(range, _)| range.start <= value)
+            .map(|(range, entry)| (range, entry))
+    }
+
+    /// The entries in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = (&Range<T>, &V)> {
+        self.entries.iter().map(|(range, value)| (range, value))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(_, value)| value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Split a range on the boundaries of the entries, each piece along with the value of its
+    /// entry, or None for the gaps between them
+    pub fn split(&self, range: Range<T>) -> Vec<(Range<T>, Option<&V>)> {
+        let mut pieces = Vec::new();
+        let mut cursor = range.start;
+        let first = self
+            .entries
+            .partition_point(|(entry, _)| entry.end <= range.start);
+        for (entry, value) in &self.entries[first..] {
+            if entry.start >= range.end {
+                break;
+            }
+            if entry.start > cursor {
+                pieces.push((cursor..entry.start, None));
+                cursor = entry.start;
+            }
+            let piece = clamp(entry, &(cursor..range.end));
+            cursor = piece.end;
+            pieces.push((piece, Some(value)));
+        }
+        if cursor < range.end {
+            pieces.push((cursor..range.end, None));
+        }
+        pieces
+    }
+}
+
+/// The values of an `IntervalMap` that shift the values of their range, for `map_range`
+pub trait Offset {
+    fn offset(&self) -> i64;
+}
+
+impl Offset for i64 {
+    fn offset(&self) -> i64 {
+        *self
+    }
+}
+
+impl<V: Offset> IntervalMap<u64, V> {
+    /// Shift a value by the offset of its entry, the values without entry are kept as they are.
+    /// None when the value is shifted out of u64
+    pub fn map_value(&self, value: u64) -> Option<u64> {
+        match self.get(value) {
+            Some((_, entry)) => value.checked_add_signed(entry.offset()),
+            None => Some(value),
+        }
+    }
+
+    /// Split a range on the boundaries of the entries and shift each piece, returning the pieces
+    /// along with where they are sent (the gaps are kept as they are). None when a piece is
+    /// shifted out of u64
+    pub fn map_range(&self, range: Range<u64>) -> Option<Vec<(Range<u64>, Range<u64>)>> {
+        self.split(range)
+            .into_iter()
+            .map(|(piece, entry)| {
+                let destination = offset(&piece, entry.map_or(0, Offset::offset))?;
+                Some((piece, destination))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Written as (start, end) pairs, clippy takes an array of one range for a mistake
+    fn set(ranges: &[(u64, u64)]) -> IntervalSet<u64> {
+        ranges.iter().map(|(start, end)| *start..*end).collect()
+    }
+
+    #[test]
+    fn test_range_helpers() {
+        crate::init_tests();
+
+        assert_eq!(intersection(&(0u64..10), &(5..20)), Some(5..10));
+        assert_eq!(intersection(&(0u64..10), &(10..20)), None);
+        assert!(overlaps(&(-5i32..0), &(-1..3)));
+        assert!(!overlaps(&(0u8..0), &(0..3)));
+
+        assert_eq!(clamp(&(0u64..10), &(5..20)), 5..10);
+        assert_eq!(clamp(&(30u64..40), &(5..20)), 20..20);
+        assert_eq!(clamp(&(-10i64..-8), &(-5..5)), -5..-5);
+
+        assert_eq!(split_at(&(0u32..10), 4), (0..4, 4..10));
+        assert_eq!(split_at(&(0u32..10), 15), (0..10, 10..10));
+        assert_eq!(split_at(&(5i32..10), -3), (5..5, 5..10));
+
+        assert_eq!(offset(&(10u64..20), -10), Some(0..10));
+        assert_eq!(offset(&(10u64..20), -11), None);
+        assert_eq!(offset(&(250u8..254), 1), Some(251..255));
+        assert_eq!(offset(&(250u8..255), 1), None);
+        assert_eq!(offset(&(i64::MAX - 1..i64::MAX), 1), None);
+    }
+
+    #[test]
+    fn test_insert() {
+        crate::init_tests();
+
+        let mut ranges = set(&[(10, 20), (30, 40), (50, 60)]);
+        assert_eq!(ranges.len(), 3);
+        ranges.insert(5..5);
+        assert_eq!(ranges.len(), 3);
+        // Touching the first range and overlapping the second one
+        ranges.insert(20..35);
+        assert_eq!(ranges, set(&[(10, 40), (50, 60)]));
+        ranges.insert(0..100);
+        assert_eq!(ranges, set(&[(0, 100)]));
+        assert_eq!(ranges.covered(), 100);
+
+        let ranges = set(&[(79, 93), (55, 68)]);
+        assert_eq!(ranges.iter().next(), Some(&(55..68)));
+        assert!(ranges.contains(79));
+        assert!(!ranges.contains(93));
+        assert_eq!(ranges.range_containing(80), Some(&(79..93)));
+        assert_eq!(ranges.bounds(), Some(55..93));
+    }
+
+    #[test]
+    fn test_operations() {
+        crate::init_tests();
+
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25)]);
+        assert_eq!(a.union(&b), set(&[(0, 30)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25)]));
+        assert_eq!(a.difference(&b), set(&[(0, 5), (25, 30)]));
+        assert_eq!(b.difference(&a), set(&[(10, 20)]));
+        assert_eq!(a.complement(0..40), set(&[(10, 20), (30, 40)]));
+        assert_eq!(a.complement(5..25), set(&[(10, 20)]));
+        assert_eq!(IntervalSet::new().complement(1..3), set(&[(1, 3)]));
+        assert!(a.difference(&a).is_empty());
+
+        let json = serde_json::to_string(&a).unwrap();
+        assert_eq!(json, r#"[{"start":0,"end":10},{"start":20,"end":30}]"#);
+        let read_back: IntervalSet<u64> =
+            serde_json::from_str(r#"[{"start":20,"end":30},{"start":0,"end":21}]"#).unwrap();
+        assert_eq!(read_back, set(&[(0, 30)]));
+    }
+
+    #[test]
+    fn test_interval_map() {
+        crate::init_tests();
+
+        // The seed-to-soil map of day 5
+        let map = IntervalMap::from_entries([(98..100, -48i64), (50..98, 2)]).unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(97), Some((&(50..98), &2)));
+        assert_eq!(map.get(100), None);
+        assert_eq!(map.map_value(79), Some(81));
+        assert_eq!(map.map_value(99), Some(51));
+        assert_eq!(map.map_value(10), Some(10));
+
+        assert_eq!(
+            map.split(40..120),
+            vec![
+                (40..50, None),
+                (50..98, Some(&2)),
+                (98..100, Some(&-48)),
+                (100..120, None)
+            ]
+        );
+        assert_eq!(
+            map.map_range(45..55),
+            Some(vec![(45..50, 45..50), (50..55, 52..57)])
+        );
+        assert_eq!(map.map_range(60..61), Some(vec![(60..61, 62..63)]));
+        assert_eq!(map.map_range(7..7), Some(vec![]));
+
+        let mut overlapping = map.clone();
+        assert!(overlapping.insert(90..95, 0).is_err());
+        assert!(overlapping.insert(0..50, 7).is_ok());
+        assert_eq!(overlapping.values().next(), Some(&7));
+
+        // Shifted out of u64 on either side
+        let edges =
+            IntervalMap::from_entries([(0..10, -1i64), (u64::MAX - 10..u64::MAX, 20)]).unwrap();
+        assert_eq!(edges.map_value(0), None);
+        assert_eq!(edges.map_value(1), Some(0));
+        assert_eq!(edges.map_value(u64::MAX - 5), None);
+        assert_eq!(edges.map_range(0..20), None);
+        assert_eq!(
+            edges.map_range(u64::MAX - 30..u64::MAX - 10),
+            Some(vec![(
+                u64::MAX - 30..u64::MAX - 10,
+                u64::MAX - 30..u64::MAX - 10
+            )])
+        );
+    }
+}
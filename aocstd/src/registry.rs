@@ -0,0 +1,173 @@
+use crate::{Answer, Error, Part, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+
+/// A solver function takes the whole puzzle input and returns the answer of one part
+pub type SolverFn = fn(&str) -> Result<Answer>;
+
+/// A parser function only parses the input, returning a short description of what was parsed
+pub type ParseFn = fn(&str) -> Result<String>;
+
+/// Identify a puzzle by its year and its day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PuzzleId {
+    pub year: u16,
+    pub day: u8,
+}
+
+impl PuzzleId {
+    pub fn new(year: u16, day: u8) -> Self {
+        PuzzleId { year, day }
+    }
+
+    /// Default location of the puzzle input, relative to the root of the workspace.
+    /// The 2023 days live at the root of the workspace (ex: day05/input-day05.txt) while the
+    /// other years are grouped in a directory named after the year (ex: 2022/day05/input-day05.txt)
+    pub fn input_path(&self) -> PathBuf {
+        let day_path = PathBuf::from(format!("day{:02}/input-day{:02}.txt", self.day, self.day));
+        if self.year == 2023 {
+            day_path
+        } else {
+            PathBuf::from(self.year.to_string()).join(day_path)
+        }
+    }
+}
+
+impl fmt::Display for PuzzleId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} day {:02}", self.year, self.day)
+    }
+}
+
+/// The two parts of a puzzle, and optionally its parser alone
+#[derive(Clone, Copy)]
+pub struct Solver {
+    pub part1: SolverFn,
+    pub part2: SolverFn,
+    pub parse_only: Option<ParseFn>,
+}
+
+impl Solver {
+    pub fn with_parser(&mut self, parse_only: ParseFn) -> &mut Self {
+        self.parse_only = Some(parse_only);
+        self
+    }
+
+    pub fn part(&self, part: Part) -> SolverFn {
+        match part {
+            Part::Part1 => self.part1,
+            Part::Part2 => self.part2,
+        }
+    }
+}
+
+/// Registry of the available solvers, keyed by (year, day) and iterated in that order
+#[derive(Default)]
+pub struct Registry {
+    solvers: BTreeMap<PuzzleId, Solver>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    /// Register the solver of a puzzle, the returned solver can be completed (ex: its parser)
+    pub fn register(&mut self, id: PuzzleId, part1: SolverFn, part2: SolverFn) -> &mut Solver {
+        let solver = Solver {
+            part1,
+            part2,
+            parse_only: None,
+        };
+        if self.solvers.insert(id, solver).is_some() {
+            log::warn!("Solver for {} registered twice, keeping the last one", id);
+        }
+        self.solvers
+            .get_mut(&id)
+            .expect("The solver was just inserted")
+    }
+
+    pub fn get(&self, id: PuzzleId) -> Option<&Solver> {
+        self.solvers.get(&id)
+    }
+
+    pub fn solve(&self, id: PuzzleId, part: Part, input: &str) -> Result<Answer> {
+        let solver = self
+            .get(id)
+            .ok_or_else(|| Error::new(format!("No solver registered for {}", id)))?;
+        solver.part(part)(input)
+    }
+
+    pub fn parse_only(&self, id: PuzzleId, input: &str) -> Result<String> {
+        let solver = self
+            .get(id)
+            .ok_or_else(|| Error::new(format!("No solver registered for {}", id)))?;
+        let parse_only = solver
+            .parse_only
+            .ok_or_else(|| Error::new(format!("No parser registered for {}", id)))?;
+        parse_only(input)
+    }
+
+    /// All the registered puzzles, ordered by year then day
+    pub fn puzzles(&self) -> impl Iterator<Item = PuzzleId> + '_ {
+        self.solvers.keys().copied()
+    }
+
+    /// The registered puzzles of one year
+    pub fn days_of(&self, year: u16) -> impl Iterator<Item = PuzzleId> + '_ {
+        self.puzzles().filter(move |id| id.year == year)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn answer_one(_input: &str) -> Result<Answer> {
+        Ok(Answer::Unsigned(1))
+    }
+
+    fn answer_two(_input: &str) -> Result<Answer> {
+        Ok(Answer::Unsigned(2))
+    }
+
+    #[test]
+    fn test_registry_keys() {
+        let mut registry = Registry::new();
+        registry.register(PuzzleId::new(2023, 2), answer_one, answer_two);
+        registry.register(PuzzleId::new(2022, 25), answer_two, answer_one);
+        registry.register(PuzzleId::new(2023, 1), answer_one, answer_one);
+
+        assert_eq!(
+            registry.puzzles().collect::<Vec<_>>(),
+            vec![
+                PuzzleId::new(2022, 25),
+                PuzzleId::new(2023, 1),
+                PuzzleId::new(2023, 2)
+            ]
+        );
+        assert_eq!(registry.days_of(2022).count(), 1);
+        assert_eq!(
+            registry
+                .solve(PuzzleId::new(2022, 25), Part::Part1, "")
+                .unwrap(),
+            Answer::Unsigned(2)
+        );
+        assert!(registry
+            .solve(PuzzleId::new(2022, 1), Part::Part1, "")
+            .is_err());
+    }
+
+    #[test]
+    fn test_input_path() {
+        assert_eq!(
+            PuzzleId::new(2023, 5).input_path(),
+            PathBuf::from("day05/input-day05.txt")
+        );
+        assert_eq!(
+            PuzzleId::new(2022, 5).input_path(),
+            PathBuf::from("2022/day05/input-day05.txt")
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! Small query loop over a parsed input, used by the `--repl` mode of the days to inspect
+//! how the input was understood by the parser
+use crate::{Error, Result};
+use std::io::{BufRead, Write};
+
+/// A parsed input answering the queries typed in the REPL
+pub trait Queryable {
+    /// One line per supported query, ex: "card <id>: matching numbers and copies of a card"
+    fn help(&self) -> Vec<String>;
+
+    /// Answer a query, the first word typed is the command and the others its arguments
+    fn query(&self, command: &str, args: &[&str]) -> Result<String>;
+}
+
+/// Parse the n-th argument of a query
+pub fn arg<T: std::str::FromStr>(args: &[&str], index: usize, name: &str) -> Result<T> {
+    let value = args
+        .get(index)
+        .ok_or_else(|| Error::new(format!("Missing argument <{}>", name)))?;
+    value
+        .parse::<T>()
+        .map_err(|_| Error::new(format!("Invalid <{}>: {}", name, value)))
+}
+
+/// Read the queries line by line until the end of the input or `quit`
+pub fn run(queryable: &dyn Queryable, input: impl BufRead, mut output: impl Write) -> Result<()> {
+    writeln!(
+        output,
+        "Type `help` for the list of queries, `quit` to exit"
+    )?;
+    write!(output, "> ")?;
+    output.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [] => {}
+            ["quit"] | ["exit"] => break,
+            ["help"] => {
+                for help in queryable.help() {
+                    writeln!(output, "  {}", help)?;
+                }
+            }
+            [command, args @ ..] => match queryable.query(command, args) {
+                Ok(answer) => writeln!(output, "{}", answer)?,
+                Err(error) => writeln!(output, "error: {}", error)?,
+            },
+        }
+        write!(output, "> ")?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+/// Error returned by the queryables for the commands they do not know
+pub fn unknown_command(command: &str) -> Error {
+    Error::new(format!("Unknown query `{}`, type `help`", command))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Echo;
+
+    impl Queryable for Echo {
+        fn help(&self) -> Vec<String> {
+            vec![String::from("double <n>: twice n")]
+        }
+
+        fn query(&self, command: &str, args: &[&str]) -> Result<String> {
+            match command {
+                "double" => Ok((arg::<u32>(args, 0, "n")? * 2).to_string()),
+                _ => Err(unknown_command(command)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let mut output = Vec::new();
+        run(
+            &Echo,
+            "double 21\ndouble x\nnope\nquit\ndouble 1\n".as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("> 42\n"));
+        assert!(output.contains("error: Invalid <n>: x"));
+        assert!(output.contains("error: Unknown query `nope`"));
+        assert!(!output.contains("> 2\n"));
+    }
+}
@@ -0,0 +1,80 @@
+//! Binary search over integers instead of slices, for the "lowest value satisfying X" puzzles
+//! where the values are too many to try one by one
+
+/// The integer types the searches work on
+pub trait Integer: Copy + Ord {
+    /// The middle of lo..hi rounded down, without overflowing even across the whole type
+    fn midpoint(lo: Self, hi: Self) -> Self;
+    fn successor(self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($type:ty),*) => {
+        $(
+            impl Integer for $type {
+                fn midpoint(lo: Self, hi: Self) -> Self {
+                    // Half the distance always fits in the type, unlike hi - lo for the signed
+                    // types
+                    lo.wrapping_add((hi.abs_diff(lo) / 2) as $type)
+                }
+
+                fn successor(self) -> Self {
+                    self + 1
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// The first value of lo..hi for which `pred` is false, hi when there is none. `pred` must be
+/// true for a prefix of the range and false after (like `slice::partition_point`)
+pub fn partition_point<T: Integer>(lo: T, hi: T, mut pred: impl FnMut(T) -> bool) -> T {
+    let (mut lo, mut hi) = (lo, hi);
+    while lo < hi {
+        let middle = T::midpoint(lo, hi);
+        if pred(middle) {
+            lo = middle.successor();
+        } else {
+            hi = middle;
+        }
+    }
+    lo
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_partition_point() {
+        crate::init_tests();
+
+        assert_eq!(partition_point(0, 100, |x: u32| x * x < 50), 8);
+        assert_eq!(partition_point(0, 100, |_: u32| true), 100);
+        assert_eq!(partition_point(0, 100, |_: u32| false), 0);
+        assert_eq!(partition_point(5, 5, |_: u32| true), 5);
+        assert_eq!(partition_point(-50, 50, |x: i32| x < -7), -7);
+
+        // The extremes of the types, the midpoints must not overflow
+        assert_eq!(partition_point(0, u64::MAX, |_| true), u64::MAX);
+        assert_eq!(
+            partition_point(0, u64::MAX, |x| x < u64::MAX - 1),
+            u64::MAX - 1
+        );
+        assert_eq!(
+            partition_point(i64::MIN, i64::MAX, |x| x < i64::MIN + 3),
+            i64::MIN + 3
+        );
+        assert_eq!(partition_point(i64::MIN, i64::MAX, |x| x < 0), 0);
+        assert_eq!(partition_point(i8::MIN, i8::MAX, |_| true), i8::MAX);
+        assert_eq!(partition_point(i128::MIN, i128::MAX, |_| false), i128::MIN);
+        let mut calls = 0;
+        partition_point(u128::MIN, u128::MAX, |x| {
+            calls += 1;
+            x < 1 << 100
+        });
+        assert!(calls <= 128);
+    }
+}
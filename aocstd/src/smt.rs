@@ -0,0 +1,394 @@
+//! Integer constraints handed to Z3 (the `smt` feature, it links the system libz3), as a second
+//! backend for the algebra puzzles when the hand written elimination gives up or has to be
+//! double-checked. The terms can multiply variables together, Z3 also takes the few products of
+//! unknowns of the hailstones
+use crate::{Error, Result};
+use std::ffi::{CStr, CString};
+use std::fmt;
+
+/// Index of a variable in its system
+pub type Variable = usize;
+
+/// A coefficient times the product of some variables, a constant when there are none
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    pub coefficient: i128,
+    pub variables: Vec<Variable>,
+}
+
+impl Term {
+    pub fn new(coefficient: i128, variables: &[Variable]) -> Self {
+        Term {
+            coefficient,
+            variables: variables.to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Equal,
+    LessOrEqual,
+    GreaterOrEqual,
+}
+
+/// The sum of the terms compared to a value
+#[derive(Debug, Clone)]
+struct Constraint {
+    terms: Vec<Term>,
+    relation: Relation,
+    value: i128,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct System {
+    names: Vec<String>,
+    constraints: Vec<Constraint>,
+}
+
+impl System {
+    pub fn new() -> Self {
+        System::default()
+    }
+
+    /// Declare a new integer unknown, the name is only used in the query text
+    pub fn variable(&mut self, name: impl Into<String>) -> Variable {
+        self.names.push(name.into());
+        self.names.len() - 1
+    }
+
+    /// Add `sum(terms) relation value`
+    pub fn constrain(&mut self, terms: Vec<Term>, relation: Relation, value: i128) {
+        self.constraints.push(Constraint {
+            terms,
+            relation,
+            value,
+        });
+    }
+
+    /// The value of every variable in a model of the system, None when it has no solution. Fails
+    /// when Z3 gives up or when a value does not fit in i128
+    pub fn solve(&self) -> Result<Option<Vec<i128>>> {
+        let z3 = Z3::new();
+        let variables: Vec<ffi::Ast> = self
+            .names
+            .iter()
+            .map(|name| {
+                let name = CString::new(name.as_str()).unwrap_or_default();
+                // SAFETY: the context is alive and the name is a valid C string
+                unsafe {
+                    let symbol = ffi::Z3_mk_string_symbol(z3.context, name.as_ptr());
+                    ffi::Z3_mk_const(z3.context, symbol, z3.int_sort)
+                }
+            })
+            .collect();
+
+        for constraint in &self.constraints {
+            let terms: Vec<ffi::Ast> = constraint
+                .terms
+                .iter()
+                .map(|term| {
+                    let mut factors = vec![z3.numeral(term.coefficient)];
+                    factors.extend(term.variables.iter().map(|variable| variables[*variable]));
+                    z3.apply(ffi::Z3_mk_mul, &factors)
+                })
+                .collect();
+            let sum = match terms.is_empty() {
+                true => z3.numeral(0),
+                false => z3.apply(ffi::Z3_mk_add, &terms),
+            };
+            let value = z3.numeral(constraint.value);
+            let make = match constraint.relation {
+                Relation::Equal => ffi::Z3_mk_eq,
+                Relation::LessOrEqual => ffi::Z3_mk_le,
+                Relation::GreaterOrEqual => ffi::Z3_mk_ge,
+            };
+            // SAFETY: the ASTs all come from this context
+            unsafe { ffi::Z3_solver_assert(z3.context, z3.solver, make(z3.context, sum, value)) };
+        }
+
+        // SAFETY: the solver belongs to the context
+        match unsafe { ffi::Z3_solver_check(z3.context, z3.solver) } {
+            ffi::Z3_L_FALSE => return Ok(None),
+            ffi::Z3_L_TRUE => {}
+            _ => return Err(Error::new("Z3 could not decide the system")),
+        }
+        let model = Model::new(&z3);
+        variables
+            .iter()
+            .zip(&self.names)
+            .map(|(variable, name)| {
+                let value = model.eval(*variable, name)?;
+                value
+                    .parse()
+                    .map_err(|_| Error::new(format!("Cannot fit {} = {} in i128", name, value)))
+            })
+            .collect::<Result<Vec<i128>>>()
+            .map(Some)
+    }
+}
+
+/// The query in SMT-LIB, to replay it with the z3 binary
+impl fmt::Display for System {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let number = |value: i128| match value < 0 {
+            true => format!("(- {})", value.unsigned_abs()),
+            false => value.to_string(),
+        };
+        for name in &self.names {
+            writeln!(f, "(declare-const |{}| Int)", name)?;
+        }
+        for constraint in &self.constraints {
+            let terms: Vec<String> = constraint
+                .terms
+                .iter()
+                .map(|term| {
+                    let mut factors = vec![number(term.coefficient)];
+                    factors.extend(
+                        term.variables
+                            .iter()
+                            .map(|variable| format!("|{}|", self.names[*variable])),
+                    );
+                    format!("(* {})", factors.join(" "))
+                })
+                .collect();
+            let relation = match constraint.relation {
+                Relation::Equal => "=",
+                Relation::LessOrEqual => "<=",
+                Relation::GreaterOrEqual => ">=",
+            };
+            writeln!(
+                f,
+                "(assert ({} (+ 0 {}) {}))",
+                relation,
+                terms.join(" "),
+                number(constraint.value)
+            )?;
+        }
+        writeln!(f, "(check-sat)")?;
+        write!(f, "(get-model)")
+    }
+}
+
+/// A context and its solver, released together
+struct Z3 {
+    context: ffi::Context,
+    solver: ffi::Solver,
+    int_sort: ffi::Sort,
+}
+
+impl Z3 {
+    fn new() -> Self {
+        // SAFETY: the configuration is only needed to create the context, the solver is
+        // reference counted and released in drop
+        unsafe {
+            let config = ffi::Z3_mk_config();
+            let context = ffi::Z3_mk_context(config);
+            ffi::Z3_del_config(config);
+            let solver = ffi::Z3_mk_solver(context);
+            ffi::Z3_solver_inc_ref(context, solver);
+            let int_sort = ffi::Z3_mk_int_sort(context);
+            Z3 {
+                context,
+                solver,
+                int_sort,
+            }
+        }
+    }
+
+    /// Z3 takes the numerals as text, so the whole i128 range goes through
+    fn numeral(&self, value: i128) -> ffi::Ast {
+        let text = CString::new(value.to_string()).unwrap();
+        // SAFETY: the context is alive and the text is a valid integer numeral
+        unsafe { ffi::Z3_mk_numeral(self.context, text.as_ptr(), self.int_sort) }
+    }
+
+    /// An n-ary operation, or the argument itself when it is alone
+    fn apply(
+        &self,
+        operation: unsafe extern "C" fn(ffi::Context, u32, *const ffi::Ast) -> ffi::Ast,
+        arguments: &[ffi::Ast],
+    ) -> ffi::Ast {
+        match arguments {
+            [argument] => *argument,
+            // SAFETY: the arguments come from this context and the count matches the slice
+            _ => unsafe { operation(self.context, arguments.len() as u32, arguments.as_ptr()) },
+        }
+    }
+}
+
+impl Drop for Z3 {
+    fn drop(&mut self) {
+        // SAFETY: nothing uses the solver or the context after this
+        unsafe {
+            ffi::Z3_solver_dec_ref(self.context, self.solver);
+            ffi::Z3_del_context(self.context);
+        }
+    }
+}
+
+/// The model of a satisfied solver
+struct Model<'a> {
+    z3: &'a Z3,
+    model: ffi::Model,
+}
+
+impl<'a> Model<'a> {
+    fn new(z3: &'a Z3) -> Self {
+        // SAFETY: only called once the solver answered sat
+        unsafe {
+            let model = ffi::Z3_solver_get_model(z3.context, z3.solver);
+            ffi::Z3_model_inc_ref(z3.context, model);
+            Model { z3, model }
+        }
+    }
+
+    /// The value of a constant as a decimal string, completed with 0 when the model leaves it
+    /// free. An error when Z3 cannot evaluate it to a number
+    fn eval(&self, constant: ffi::Ast, name: &str) -> Result<String> {
+        let cannot_eval = || Error::new(format!("Z3 could not evaluate {}", name));
+        let mut value = std::ptr::null_mut();
+        // SAFETY: the constant comes from this context, `value` is only read when Z3 set it
+        let evaluated =
+            unsafe { ffi::Z3_model_eval(self.z3.context, self.model, constant, true, &mut value) };
+        if !evaluated || value.is_null() {
+            return Err(cannot_eval());
+        }
+        // SAFETY: the numeral string is owned by the context and copied right away
+        unsafe {
+            let numeral = ffi::Z3_get_numeral_string(self.z3.context, value);
+            if numeral.is_null() {
+                return Err(cannot_eval());
+            }
+            Ok(CStr::from_ptr(numeral).to_string_lossy().into_owned())
+        }
+    }
+}
+
+impl Drop for Model<'_> {
+    fn drop(&mut self) {
+        // SAFETY: the context outlives the model
+        unsafe { ffi::Z3_model_dec_ref(self.z3.context, self.model) };
+    }
+}
+
+/// The few functions of the Z3 C API used here
+#[allow(non_snake_case)]
+mod ffi {
+    use std::ffi::{c_char, c_int, c_void};
+
+    pub type Config = *mut c_void;
+    pub type Context = *mut c_void;
+    pub type Sort = *mut c_void;
+    pub type Symbol = *mut c_void;
+    pub type Ast = *mut c_void;
+    pub type Solver = *mut c_void;
+    pub type Model = *mut c_void;
+
+    pub const Z3_L_FALSE: c_int = -1;
+    pub const Z3_L_TRUE: c_int = 1;
+
+    #[link(name = "z3")]
+    extern "C" {
+        pub fn Z3_mk_config() -> Config;
+        pub fn Z3_del_config(config: Config);
+        pub fn Z3_mk_context(config: Config) -> Context;
+        pub fn Z3_del_context(context: Context);
+        pub fn Z3_mk_int_sort(context: Context) -> Sort;
+        pub fn Z3_mk_string_symbol(context: Context, name: *const c_char) -> Symbol;
+        pub fn Z3_mk_const(context: Context, symbol: Symbol, sort: Sort) -> Ast;
+        pub fn Z3_mk_numeral(context: Context, numeral: *const c_char, sort: Sort) -> Ast;
+        pub fn Z3_mk_add(context: Context, count: u32, arguments: *const Ast) -> Ast;
+        pub fn Z3_mk_mul(context: Context, count: u32, arguments: *const Ast) -> Ast;
+        pub fn Z3_mk_eq(context: Context, left: Ast, right: Ast) -> Ast;
+        pub fn Z3_mk_le(context: Context, left: Ast, right: Ast) -> Ast;
+        pub fn Z3_mk_ge(context: Context, left: Ast, right: Ast) -> Ast;
+        pub fn Z3_mk_solver(context: Context) -> Solver;
+        pub fn Z3_solver_inc_ref(context: Context, solver: Solver);
+        pub fn Z3_solver_dec_ref(context: Context, solver: Solver);
+        pub fn Z3_solver_assert(context: Context, solver: Solver, assertion: Ast);
+        pub fn Z3_solver_check(context: Context, solver: Solver) -> c_int;
+        pub fn Z3_solver_get_model(context: Context, solver: Solver) -> Model;
+        pub fn Z3_model_inc_ref(context: Context, model: Model);
+        pub fn Z3_model_dec_ref(context: Context, model: Model);
+        pub fn Z3_model_eval(
+            context: Context,
+            model: Model,
+            ast: Ast,
+            completion: bool,
+            value: *mut Ast,
+        ) -> bool;
+        pub fn Z3_get_numeral_string(context: Context, ast: Ast) -> *const c_char;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_linear() {
+        crate::init_tests();
+
+        let mut system = System::new();
+        let (x, y) = (system.variable("x"), system.variable("y"));
+        system.constrain(
+            vec![Term::new(1, &[x]), Term::new(1, &[y])],
+            Relation::Equal,
+            10,
+        );
+        system.constrain(
+            vec![Term::new(1, &[x]), Term::new(-1, &[y])],
+            Relation::Equal,
+            4,
+        );
+        assert_eq!(system.solve().unwrap(), Some(vec![7, 3]));
+        assert!(system
+            .to_string()
+            .contains("(assert (= (+ 0 (* 1 |x|) (* (- 1) |y|)) 4))"));
+
+        system.constrain(vec![Term::new(1, &[x])], Relation::LessOrEqual, 6);
+        assert_eq!(system.solve().unwrap(), None);
+
+        let mut system = System::new();
+        let big = system.variable("big");
+        system.constrain(
+            vec![Term::new(1, &[big])],
+            Relation::GreaterOrEqual,
+            i128::MAX,
+        );
+        assert_eq!(system.solve().unwrap(), Some(vec![i128::MAX]));
+    }
+
+    #[test]
+    fn test_hailstones() {
+        crate::init_tests();
+
+        // The rock thrown through the three first hailstones of the example, one unknown time of
+        // collision per hailstone: position + time * velocity = hailstone + time * its velocity
+        let hailstones = [
+            ([19, 13, 30], [-2, 1, -2]),
+            ([18, 19, 22], [-1, -1, -2]),
+            ([20, 25, 34], [-2, -2, -4]),
+        ];
+        let mut system = System::new();
+        let position = ["x", "y", "z"].map(|name| system.variable(name));
+        let velocity = ["vx", "vy", "vz"].map(|name| system.variable(name));
+        for (index, (hailstone, hailstone_velocity)) in hailstones.iter().enumerate() {
+            let time = system.variable(format!("t{}", index));
+            for axis in 0..3 {
+                system.constrain(
+                    vec![
+                        Term::new(1, &[position[axis]]),
+                        Term::new(1, &[time, velocity[axis]]),
+                        Term::new(-hailstone_velocity[axis], &[time]),
+                    ],
+                    Relation::Equal,
+                    hailstone[axis],
+                );
+            }
+        }
+        let values = system.solve().unwrap().unwrap();
+        assert_eq!(values[..6], [24, 13, 10, -3, 1, 2]);
+    }
+}
@@ -0,0 +1,103 @@
+//! Opt-in trace of the intermediate steps of the solvers, written as JSON Lines for the external
+//! tools (ex: a step by step visualizer) that would otherwise have to scrape the debug logs.
+//!
+//! Each event is one JSON object per line, with its name in the `event` field:
+//!   {"event":"card","id":1,"matches":4,"copies":1}
+//!
+//! The events emitted from parallel work (see `parallel`) are not in a particular order.
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static SINK: Mutex<Option<Box<dyn Write + Send>>> = Mutex::new(None);
+
+/// Send the events to a writer, the events emitted before are lost
+pub fn set_sink(writer: impl Write + Send + 'static) {
+    *SINK.lock().unwrap() = Some(Box::new(writer));
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Stop tracing and flush what was written
+pub fn close() {
+    ENABLED.store(false, Ordering::Relaxed);
+    if let Some(mut writer) = SINK.lock().unwrap().take() {
+        if let Err(error) = writer.flush() {
+            log::warn!("Could not flush the trace: {}", error);
+        }
+    }
+}
+
+/// Whether a sink is set, for the solvers that need extra work to build their events
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Emit an event, the fields are only built when tracing is enabled
+pub fn emit<T: Serialize>(event: &str, fields: impl FnOnce() -> T) {
+    if !enabled() {
+        return;
+    }
+    let mut line = match serde_json::to_value(fields()) {
+        Ok(Value::Object(fields)) => fields,
+        Ok(value) => serde_json::Map::from_iter([(String::from("value"), value)]),
+        Err(error) => {
+            log::warn!("Could not serialize the {} trace event: {}", event, error);
+            return;
+        }
+    };
+    line.insert(String::from("event"), Value::from(event));
+
+    if let Some(writer) = SINK.lock().unwrap().as_mut() {
+        if let Err(error) = writeln!(writer, "{}", Value::Object(line)) {
+            log::warn!("Could not write the trace: {}", error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_emit() {
+        emit("ignored", || -> u32 { panic!("not built when disabled") });
+
+        let buffer = SharedBuffer::default();
+        set_sink(buffer.clone());
+        emit("card", || json!({ "id": 1, "copies": 2 }));
+        emit("seed", || 79);
+        close();
+        emit("ignored", || 0);
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                json!({ "event": "card", "id": 1, "copies": 2 }),
+                json!({ "event": "seed", "value": 79 }),
+            ]
+        );
+    }
+}
@@ -0,0 +1,418 @@
+//! Small visualization toolkit shared by the days: a day draws its state once into a `Frame`
+//! (a grid of colored characters) and submits it to a `Renderer`, which takes care of the output
+//! target (terminal, SVG, ...). Submitting several frames makes an animation.
+use crate::Result;
+#[cfg(feature = "image")]
+pub use image::ImageRenderer;
+use std::io::Write;
+use std::time::Duration;
+
+#[cfg(feature = "image")]
+mod image;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    pub const GRAY: Color = Color::rgb(128, 128, 128);
+    pub const RED: Color = Color::rgb(220, 50, 47);
+    pub const GREEN: Color = Color::rgb(80, 200, 80);
+    pub const BLUE: Color = Color::rgb(38, 139, 210);
+    pub const YELLOW: Color = Color::rgb(230, 200, 0);
+    pub const MAGENTA: Color = Color::rgb(211, 54, 130);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+}
+
+impl Style {
+    pub fn fg(color: Color) -> Self {
+        Style {
+            fg: Some(color),
+            ..Style::default()
+        }
+    }
+
+    pub fn bold(self) -> Self {
+        Style { bold: true, ..self }
+    }
+
+    pub fn on(self, color: Color) -> Self {
+        Style {
+            bg: Some(color),
+            ..self
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub character: char,
+    pub style: Style,
+}
+
+/// A grid of styled characters, the unit of drawing of the renderers
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+}
+
+impl Frame {
+    /// A frame filled with spaces
+    pub fn new(width: usize, height: usize) -> Self {
+        let blank = Cell {
+            character: ' ',
+            style: Style::default(),
+        };
+        Frame {
+            width,
+            height,
+            cells: vec![blank; width * height],
+        }
+    }
+
+    /// A frame drawing the lines of a text, without style
+    pub fn from_text(text: &str) -> Self {
+        let lines: Vec<&str> = text.lines().collect();
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut frame = Frame::new(width, lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            frame.print(0, y, line, Style::default());
+        }
+        frame
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&Cell> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    /// Draw a character, the positions out of the frame are ignored
+    pub fn set(&mut self, x: usize, y: usize, character: char, style: Style) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = Cell { character, style };
+        }
+    }
+
+    /// Change the style of a cell without changing its character
+    pub fn style(&mut self, x: usize, y: usize, style: Style) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x].style = style;
+        }
+    }
+
+    /// Draw a text from left to right starting at (x, y)
+    pub fn print(&mut self, x: usize, y: usize, text: &str, style: Style) {
+        for (offset, character) in text.chars().enumerate() {
+            self.set(x + offset, y, character, style);
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+
+    /// The characters of the frame without their style, one string per row
+    pub fn lines(&self) -> Vec<String> {
+        self.rows()
+            .map(|row| row.iter().map(|cell| cell.character).collect())
+            .collect()
+    }
+}
+
+/// An output target of the frames
+pub trait Renderer {
+    /// Render a frame, after the previous ones for the animated targets
+    fn submit(&mut self, frame: &Frame) -> Result<()>;
+
+    /// Complete the output once every frame has been submitted
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Render the frames with ANSI colors, each frame replacing the previous one on screen when a
+/// frame delay is set
+pub struct TerminalRenderer<W: Write> {
+    writer: W,
+    frame_delay: Option<Duration>,
+    nb_of_frames: usize,
+}
+
+impl<W: Write> TerminalRenderer<W> {
+    pub fn new(writer: W) -> Self {
+        TerminalRenderer {
+            writer,
+            frame_delay: None,
+            nb_of_frames: 0,
+        }
+    }
+
+    /// Animate the frames in place, waiting this long after each one
+    pub fn with_frame_delay(mut self, frame_delay: Duration) -> Self {
+        self.frame_delay = Some(frame_delay);
+        self
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_style(&mut self, style: &Style) -> Result<()> {
+        if style.bold {
+            write!(self.writer, "\x1b[1m")?;
+        }
+        if let Some(Color { r, g, b }) = style.fg {
+            write!(self.writer, "\x1b[38;2;{};{};{}m", r, g, b)?;
+        }
+        if let Some(Color { r, g, b }) = style.bg {
+            write!(self.writer, "\x1b[48;2;{};{};{}m", r, g, b)?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Renderer for TerminalRenderer<W> {
+    fn submit(&mut self, frame: &Frame) -> Result<()> {
+        if self.frame_delay.is_some() {
+            // Clear the screen and go back to its top left corner
+            write!(self.writer, "\x1b[2J\x1b[H")?;
+        } else if self.nb_of_frames > 0 {
+            writeln!(self.writer)?;
+        }
+        for row in frame.rows() {
+            let mut current_style = Style::default();
+            for cell in row {
+                if cell.style != current_style {
+                    write!(self.writer, "\x1b[0m")?;
+                    self.write_style(&cell.style)?;
+                    current_style = cell.style;
+                }
+                write!(self.writer, "{}", cell.character)?;
+            }
+            if current_style != Style::default() {
+                write!(self.writer, "\x1b[0m")?;
+            }
+            writeln!(self.writer)?;
+        }
+        self.writer.flush()?;
+        self.nb_of_frames += 1;
+        if let Some(frame_delay) = self.frame_delay {
+            std::thread::sleep(frame_delay);
+        }
+        Ok(())
+    }
+}
+
+/// Render the frames as an SVG document, written on `finish`, the frames being played in a
+/// loop when there are several of them
+pub struct SvgRenderer<W: Write> {
+    writer: W,
+    frame_duration: Duration,
+    frames: Vec<String>,
+    size: (usize, usize),
+}
+
+/// Size of a cell in the SVG, in pixels
+const CELL_WIDTH: usize = 10;
+const CELL_HEIGHT: usize = 16;
+
+impl<W: Write> SvgRenderer<W> {
+    pub fn new(writer: W) -> Self {
+        SvgRenderer {
+            writer,
+            frame_duration: Duration::from_millis(200),
+            frames: Vec::new(),
+            size: (0, 0),
+        }
+    }
+
+    pub fn with_frame_duration(mut self, frame_duration: Duration) -> Self {
+        self.frame_duration = frame_duration;
+        self
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+fn svg_color(color: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+fn svg_escape(character: char) -> String {
+    match character {
+        '<' => String::from("&lt;"),
+        '>' => String::from("&gt;"),
+        '&' => String::from("&amp;"),
+        character => character.to_string(),
+    }
+}
+
+impl<W: Write> Renderer for SvgRenderer<W> {
+    fn submit(&mut self, frame: &Frame) -> Result<()> {
+        let mut group = String::new();
+        for (y, row) in frame.rows().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                let (px, py) = (x * CELL_WIDTH, y * CELL_HEIGHT);
+                if let Some(bg) = cell.style.bg {
+                    group.push_str(&format!(
+                        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                        px,
+                        py,
+                        CELL_WIDTH,
+                        CELL_HEIGHT,
+                        svg_color(bg)
+                    ));
+                }
+                if !cell.character.is_whitespace() {
+                    group.push_str(&format!(
+                        "<text x=\"{}\" y=\"{}\" fill=\"{}\"{}>{}</text>",
+                        px,
+                        py + CELL_HEIGHT - 4,
+                        svg_color(cell.style.fg.unwrap_or(Color::WHITE)),
+                        if cell.style.bold {
+                            " font-weight=\"bold\""
+                        } else {
+                            ""
+                        },
+                        svg_escape(cell.character)
+                    ));
+                }
+            }
+            group.push('\n');
+        }
+        self.size = (
+            self.size.0.max(frame.width()),
+            self.size.1.max(frame.height()),
+        );
+        self.frames.push(group);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let (width, height) = (self.size.0 * CELL_WIDTH, self.size.1 * CELL_HEIGHT);
+        writeln!(
+            self.writer,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"monospace\" font-size=\"14\">",
+            width, height
+        )?;
+        writeln!(
+            self.writer,
+            "<rect width=\"{}\" height=\"{}\" fill=\"#1e1e1e\"/>",
+            width, height
+        )?;
+        let nb_of_frames = self.frames.len();
+        let duration = self.frame_duration.as_secs_f64();
+        for (index, frame) in self.frames.iter().enumerate() {
+            if nb_of_frames == 1 {
+                writeln!(self.writer, "<g>\n{}</g>", frame)?;
+            } else {
+                // Each frame is only displayed during its slot of the loop
+                writeln!(
+                    self.writer,
+                    "<g visibility=\"hidden\"><set attributeName=\"visibility\" to=\"visible\" begin=\"{:.3}s;loop.end+{:.3}s\" dur=\"{:.3}s\"/>\n{}</g>",
+                    index as f64 * duration,
+                    index as f64 * duration,
+                    duration,
+                    frame
+                )?;
+            }
+        }
+        if nb_of_frames > 1 {
+            // An invisible clock restarting the loop of frames
+            writeln!(
+                self.writer,
+                "<rect width=\"0\" height=\"0\"><animate id=\"loop\" attributeName=\"x\" from=\"0\" to=\"0\" begin=\"0s;loop.end\" dur=\"{:.3}s\"/></rect>",
+                nb_of_frames as f64 * duration
+            )?;
+        }
+        writeln!(self.writer, "</svg>")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+fn render_all(renderer: &mut dyn Renderer, frames: &[Frame]) -> Result<()> {
+    for frame in frames {
+        renderer.submit(frame)?;
+    }
+    renderer.finish()
+}
+
+/// The frames rendered for a terminal, one after the other, for the days returning their
+/// visualization to the CLI
+pub fn terminal_string(frames: &[Frame]) -> Result<String> {
+    let mut renderer = TerminalRenderer::new(Vec::new());
+    render_all(&mut renderer, frames)?;
+    Ok(String::from_utf8_lossy(&renderer.into_inner()).into_owned())
+}
+
+/// The frames rendered as an SVG document
+pub fn svg_string(frames: &[Frame]) -> Result<String> {
+    let mut renderer = SvgRenderer::new(Vec::new());
+    render_all(&mut renderer, frames)?;
+    Ok(String::from_utf8_lossy(&renderer.into_inner()).into_owned())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_frame() {
+        let mut frame = Frame::from_text("ab\nc");
+        assert_eq!(frame.lines(), vec!["ab", "c "]);
+        frame.set(1, 1, 'd', Style::fg(Color::RED));
+        frame.set(5, 5, 'x', Style::default());
+        assert_eq!(frame.lines(), vec!["ab", "cd"]);
+        assert_eq!(frame.get(1, 1).unwrap().style.fg, Some(Color::RED));
+        assert!(frame.get(2, 0).is_none());
+    }
+
+    #[test]
+    fn test_renderers() {
+        let mut frame = Frame::from_text("a<");
+        frame.style(0, 0, Style::fg(Color::GREEN).bold());
+
+        let mut terminal = TerminalRenderer::new(Vec::new());
+        terminal.submit(&frame).unwrap();
+        let output = String::from_utf8(terminal.into_inner()).unwrap();
+        assert_eq!(output, "\x1b[0m\x1b[1m\x1b[38;2;80;200;80ma\x1b[0m<\n");
+
+        let mut svg = SvgRenderer::new(Vec::new());
+        svg.submit(&frame).unwrap();
+        svg.finish().unwrap();
+        let output = String::from_utf8(svg.into_inner()).unwrap();
+        assert!(output.starts_with("<svg"));
+        assert!(output.contains(">&lt;</text>"));
+        assert!(output.contains("font-weight=\"bold\">a</text>"));
+    }
+}
@@ -0,0 +1,262 @@
+//! Image backend of the visualizations, the frames are written as an animated GIF or as a
+//! sequence of PNG files.
+//!
+//! There is no font here: every cell is a square of its background color, the block characters
+//! (ex: the bars of a chart) fill the part of the square they cover and the other visible
+//! characters are drawn as a smaller square of their foreground color.
+use super::{Cell, Color, Frame, Renderer};
+use crate::{Error, Result};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const BACKGROUND: Color = Color::rgb(30, 30, 30);
+const DEFAULT_FOREGROUND: Color = Color::rgb(170, 170, 170);
+
+/// Accumulate the frames and write them on `finish`, as a GIF when the path ends with .gif,
+/// otherwise as numbered PNG files next to the path (ex: out.png -> out-0000.png, out-0001.png)
+pub struct ImageRenderer {
+    path: PathBuf,
+    cell_size: usize,
+    frame_delay: Duration,
+    frames: Vec<Frame>,
+}
+
+impl ImageRenderer {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        ImageRenderer {
+            path: path.into(),
+            cell_size: 8,
+            frame_delay: Duration::from_millis(100),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Size of a cell of the frames in pixels
+    pub fn with_cell_size(mut self, cell_size: usize) -> Self {
+        self.cell_size = cell_size.max(1);
+        self
+    }
+
+    pub fn with_frame_delay(mut self, frame_delay: Duration) -> Self {
+        self.frame_delay = frame_delay;
+        self
+    }
+
+    fn is_gif(&self) -> bool {
+        self.path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("gif"))
+    }
+
+    /// The size of the images, every frame is drawn on an image large enough for the biggest
+    fn image_size(&self) -> (usize, usize) {
+        let width = self.frames.iter().map(Frame::width).max().unwrap_or(0);
+        let height = self.frames.iter().map(Frame::height).max().unwrap_or(0);
+        (
+            (width * self.cell_size).max(1),
+            (height * self.cell_size).max(1),
+        )
+    }
+
+    fn write_gif(&self) -> Result<()> {
+        let (width, height) = self.image_size();
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(Error::new(format!(
+                "The frames are too large for a GIF ({}x{} pixels)",
+                width, height
+            )));
+        }
+        let file = BufWriter::new(File::create(&self.path)?);
+        let mut encoder = gif::Encoder::new(file, width as u16, height as u16, &[])
+            .map_err(|e| Error::new(format!("Could not write the GIF: {}", e)))?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(|e| Error::new(format!("Could not write the GIF: {}", e)))?;
+        let delay = (self.frame_delay.as_millis() / 10).min(u16::MAX as u128) as u16;
+        let palette = palette(&self.frames);
+        let colors = palette.as_ref().map(|palette| {
+            let mut colors = vec![0; palette.len() * 3];
+            for (color, index) in palette {
+                let index = *index as usize * 3;
+                colors[index..index + 3].copy_from_slice(&[color.r, color.g, color.b]);
+            }
+            colors
+        });
+        for frame in &self.frames {
+            let mut gif_frame = match (&palette, &colors) {
+                (Some(palette), Some(colors)) => {
+                    let pixels = rasterize(frame, self.cell_size, (width, height), |color| {
+                        palette[&color]
+                    });
+                    gif::Frame::from_palette_pixels(
+                        width as u16,
+                        height as u16,
+                        pixels,
+                        colors.as_slice(),
+                        None,
+                    )
+                }
+                _ => {
+                    let pixels = rgb_pixels(frame, self.cell_size, (width, height));
+                    gif::Frame::from_rgb_speed(width as u16, height as u16, &pixels, 10)
+                }
+            };
+            gif_frame.delay = delay;
+            encoder
+                .write_frame(&gif_frame)
+                .map_err(|e| Error::new(format!("Could not write the GIF: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    fn write_pngs(&self) -> Result<()> {
+        let (width, height) = self.image_size();
+        for (index, frame) in self.frames.iter().enumerate() {
+            let path = numbered_path(&self.path, index);
+            let file = BufWriter::new(File::create(&path)?);
+            let mut encoder = png::Encoder::new(file, width as u32, height as u32);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            let pixels = rgb_pixels(frame, self.cell_size, (width, height));
+            encoder
+                .write_header()
+                .and_then(|mut writer| writer.write_image_data(&pixels))
+                .map_err(|e| Error::new(format!("Could not write {}: {}", path.display(), e)))?;
+        }
+        Ok(())
+    }
+}
+
+impl Renderer for ImageRenderer {
+    fn submit(&mut self, frame: &Frame) -> Result<()> {
+        self.frames.push(frame.clone());
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if self.frames.is_empty() {
+            return Err(Error::new("No frame to write"));
+        }
+        match self.is_gif() {
+            true => self.write_gif(),
+            false => self.write_pngs(),
+        }
+    }
+}
+
+fn numbered_path(path: &Path, index: usize) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| String::from("frame"));
+    path.with_file_name(format!("{}-{:04}.png", stem, index))
+}
+
+/// How much of the width of a cell a character covers, from the left
+fn coverage(character: char) -> Option<f64> {
+    match character {
+        '█' => Some(1.0),
+        '▉' => Some(7.0 / 8.0),
+        '▊' => Some(6.0 / 8.0),
+        '▋' => Some(5.0 / 8.0),
+        '▌' => Some(4.0 / 8.0),
+        '▍' => Some(3.0 / 8.0),
+        '▎' => Some(2.0 / 8.0),
+        '▏' => Some(1.0 / 8.0),
+        _ => None,
+    }
+}
+
+/// Whether the pixel (px, py) of a cell of the given size is drawn with the foreground color
+fn is_foreground(cell: &Cell, px: usize, py: usize, cell_size: usize) -> bool {
+    if cell.character.is_whitespace() {
+        return false;
+    }
+    match coverage(cell.character) {
+        Some(coverage) => (px as f64) < coverage * cell_size as f64,
+        None => {
+            // A centered square of a half cell
+            let margin = cell_size / 4;
+            (margin..cell_size - margin).contains(&px) && (margin..cell_size - margin).contains(&py)
+        }
+    }
+}
+
+/// The pixels of a frame drawn at the top left of an image of the given size, `pixel` gives
+/// the value of a pixel of each color (ex: its RGB components or its index in a palette)
+fn rasterize<T: Copy>(
+    frame: &Frame,
+    cell_size: usize,
+    (width, height): (usize, usize),
+    pixel: impl Fn(Color) -> T,
+) -> Vec<T> {
+    let background = pixel(BACKGROUND);
+    let mut pixels = vec![background; width * height];
+    for (cell_y, row) in frame.rows().enumerate() {
+        for (cell_x, cell) in row.iter().enumerate() {
+            // The colors are resolved once per cell, not once per pixel
+            let fg = pixel(cell.style.fg.unwrap_or(DEFAULT_FOREGROUND));
+            let bg = pixel(cell.style.bg.unwrap_or(BACKGROUND));
+            for py in 0..cell_size {
+                let line = (cell_y * cell_size + py) * width + cell_x * cell_size;
+                for px in 0..cell_size {
+                    pixels[line + px] = match is_foreground(cell, px, py, cell_size) {
+                        true => fg,
+                        false => bg,
+                    };
+                }
+            }
+        }
+    }
+    pixels
+}
+
+fn rgb_pixels(frame: &Frame, cell_size: usize, size: (usize, usize)) -> Vec<u8> {
+    rasterize(frame, cell_size, size, |color| [color.r, color.g, color.b]).concat()
+}
+
+/// The visualizations only use a few colors, so the frames can usually share an exact palette
+/// instead of being quantized
+fn palette(frames: &[Frame]) -> Option<HashMap<Color, u8>> {
+    let mut palette = HashMap::new();
+    let colors = frames
+        .iter()
+        .flat_map(|frame| frame.rows().flatten())
+        .flat_map(|cell| [cell.style.fg, cell.style.bg])
+        .flatten()
+        .chain([BACKGROUND, DEFAULT_FOREGROUND]);
+    for color in colors {
+        if !palette.contains_key(&color) {
+            let index = u8::try_from(palette.len()).ok()?;
+            palette.insert(color, index);
+        }
+    }
+    Some(palette)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::viz::Style;
+
+    #[test]
+    fn test_rasterize() {
+        let mut frame = Frame::new(2, 1);
+        frame.set(0, 0, '▌', Style::fg(Color::RED));
+        frame.set(1, 0, 'x', Style::fg(Color::GREEN).on(Color::BLUE));
+        let pixels = rgb_pixels(&frame, 4, (8, 4));
+        let pixel = |x: usize, y: usize| &pixels[(y * 8 + x) * 3..(y * 8 + x) * 3 + 3];
+
+        assert_eq!(pixel(1, 3), &[220, 50, 47]);
+        assert_eq!(pixel(2, 0), &[30, 30, 30]);
+        assert_eq!(pixel(4, 0), &[38, 139, 210]);
+        assert_eq!(pixel(5, 1), &[80, 200, 80]);
+        assert_eq!(
+            numbered_path(Path::new("out/cascade.png"), 3),
+            PathBuf::from("out/cascade-0003.png")
+        );
+    }
+}
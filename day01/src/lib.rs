@@ -0,0 +1,753 @@
+pub mod trebuchet {
+
+    use aho_corasick::{AhoCorasick, MatchKind};
+    use aocstd::repl::{self, Queryable};
+    use aocstd::{trace, Answer, Error, LineFormat, Part, Result, Strictness};
+    use phf::phf_map;
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+    use std::io::{BufRead, Write};
+    use std::ops::Range;
+    use std::path::Path;
+    use std::sync::OnceLock;
+
+    type CalibrationValue = u8;
+
+    /// What counts as a digit in a line
+    #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+    pub enum IdentificationMode {
+        /// Only 0 to 9, as in part 1
+        Digit,
+        /// The digits and their English names, as in part 2
+        DigitAndName,
+    }
+
+    /// Digits and their associated values
+    /// We are using phf crate to create a static Map, the default dictionary of part 2
+    static DIGITS: phf::Map<&'static str, u8> = phf_map! {
+        "zero" => 0,
+        "one" => 1,
+        "two" => 2,
+        "three" => 3,
+        "four" => 4,
+        "five" => 5,
+        "six" => 6,
+        "seven" => 7,
+        "eight" => 8,
+        "nine" => 9,
+    };
+
+    /// The words spelling the digits in part 2, English by default. Another dictionary can be
+    /// read from a TOML file of `word = value` lines (ex: `"zéro" = 0`), several words can have
+    /// the same value
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct DigitNames {
+        names: Vec<(String, u8)>,
+    }
+
+    impl DigitNames {
+        pub fn english() -> Self {
+            let mut names: Vec<(String, u8)> = DIGITS
+                .entries()
+                .map(|(name, value)| (name.to_string(), *value))
+                .collect();
+            names.sort_by_key(|(_, value)| *value);
+            DigitNames { names }
+        }
+
+        pub fn from_toml(text: &str) -> Result<Self> {
+            let table: BTreeMap<String, i64> = toml::from_str(text)
+                .map_err(|e| Error::new(format!("Invalid digit map: {}", e)))?;
+            let mut names = Vec::with_capacity(table.len());
+            for (name, value) in table {
+                if name.is_empty() {
+                    return Err(Error::new("Empty word in the digit map"));
+                }
+                let value = u8::try_from(value)
+                    .ok()
+                    .filter(|value| *value <= 9)
+                    .ok_or_else(|| Error::new(format!("{} is not a digit: {}", name, value)))?;
+                names.push((name, value));
+            }
+            Ok(DigitNames { names })
+        }
+
+        pub fn from_file(path: &Path) -> Result<Self> {
+            let text = std::fs::read_to_string(path).map_err(|e| {
+                Error::new(format!(
+                    "Cannot read the digit map {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            Self::from_toml(&text)
+        }
+    }
+
+    /// A digit found in a line: its value and its byte range
+    type Digit = (u8, Range<usize>);
+
+    /// The automatons finding the first and the last digit of a mode in a line, with the value
+    /// of each pattern
+    struct DigitMatcher {
+        identification_mode: IdentificationMode,
+        /// Its leftmost match is the first digit
+        forward: AhoCorasick,
+        /// The reversed patterns, run on the reversed line. The digits can overlap (ex: "eightwo"
+        /// has an 8 and a 2) so the last digit is not the last leftmost match of `forward`, but
+        /// it is the match ending first in the reversed line
+        backward: AhoCorasick,
+        values: Vec<u8>,
+    }
+
+    impl DigitMatcher {
+        fn new(identification_mode: IdentificationMode, digit_names: &DigitNames) -> Self {
+            let mut patterns: Vec<(String, u8)> =
+                (0..10).map(|digit| (digit.to_string(), digit)).collect();
+            if identification_mode == IdentificationMode::DigitAndName {
+                patterns.extend(digit_names.names.iter().cloned());
+            }
+            let forward = AhoCorasick::builder()
+                .match_kind(MatchKind::LeftmostFirst)
+                .build(patterns.iter().map(|(pattern, _)| pattern))
+                .expect("Cannot build the digits automaton");
+            let backward = AhoCorasick::new(
+                patterns
+                    .iter()
+                    .map(|(pattern, _)| pattern.bytes().rev().collect::<Vec<u8>>()),
+            )
+            .expect("Cannot build the reversed digits automaton");
+            let values = patterns.iter().map(|(_, value)| *value).collect();
+            DigitMatcher {
+                identification_mode,
+                forward,
+                backward,
+                values,
+            }
+        }
+
+        /// The matcher of a mode with the English names, built once
+        fn get(identification_mode: IdentificationMode) -> &'static DigitMatcher {
+            static DIGIT: OnceLock<DigitMatcher> = OnceLock::new();
+            static DIGIT_AND_NAME: OnceLock<DigitMatcher> = OnceLock::new();
+            let new = || Self::new(identification_mode, &DigitNames::english());
+            match identification_mode {
+                IdentificationMode::Digit => DIGIT.get_or_init(new),
+                IdentificationMode::DigitAndName => DIGIT_AND_NAME.get_or_init(new),
+            }
+        }
+
+        /// The first and the last digit of the line, the same one when there is only one
+        fn first_and_last(&self, line: &str) -> Option<(Digit, Digit)> {
+            thread_local! {
+                /// Reused from one line to the next so the lines do not allocate
+                static REVERSED: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+            }
+            let first = self.forward.find(line)?;
+            // The last digit cannot start before the first one
+            let rest = &line[first.start()..];
+            let last = REVERSED.with(|reversed| {
+                let mut reversed = reversed.borrow_mut();
+                reversed.clear();
+                reversed.extend(rest.bytes().rev());
+                self.backward.find(reversed.as_slice())
+            })?;
+            let last_range = line.len() - last.end()..line.len() - last.start();
+            Some((
+                (self.values[first.pattern().as_usize()], first.range()),
+                (self.values[last.pattern().as_usize()], last_range),
+            ))
+        }
+    }
+
+    /// The digit starting at a character of the line, if any. `index` is the byte offset of
+    /// the character, the noise around the digits can be any UTF-8 (ex: "é1è")
+    fn digit_starting_at(
+        line: &str,
+        index: usize,
+        identification_mode: IdentificationMode,
+        digit_names: &DigitNames,
+    ) -> Option<u8> {
+        let rest = &line[index..];
+        if let Some(digit) = rest.chars().next().and_then(|c| c.to_digit(10)) {
+            return Some(digit as u8);
+        }
+        if identification_mode == IdentificationMode::Digit {
+            return None;
+        }
+        digit_names
+            .names
+            .iter()
+            .find(|(digit_name, _)| rest.starts_with(digit_name.as_str()))
+            .map(|(_, digit_value)| *digit_value)
+    }
+
+    /// Only look for the first digit from the start of the line and for the last one from its
+    /// end, stopping at the first found in each direction. There is no log nor trace here
+    fn scan_calibration_value(
+        line: &str,
+        identification_mode: IdentificationMode,
+        digit_names: &DigitNames,
+    ) -> u8 {
+        let digit_at =
+            |(index, _)| digit_starting_at(line, index, identification_mode, digit_names);
+        let first = line.char_indices().find_map(digit_at);
+        let last = line.char_indices().rev().find_map(digit_at);
+        match (first, last) {
+            (Some(f), Some(l)) => f * 10 + l,
+            _ => 0,
+        }
+    }
+
+    fn identify_calibration_value_single_line(
+        line: &str,
+        digit_matcher: &DigitMatcher,
+    ) -> CalibrationValue {
+        // find the first and the last Digit of the line
+        // Create the line number by associating the two Digits
+        let calibration_value = match digit_matcher.first_and_last(line) {
+            Some(((f, _), (l, _))) => f * 10 + l,
+            None => 0,
+        };
+
+        aocstd::debug_sampled!(
+            1000,
+            "line=[{}] calibration_value=[{}]",
+            line,
+            calibration_value
+        );
+        trace::emit("calibration", || {
+            serde_json::json!({
+                "line": line,
+                "mode": format!("{:?}", digit_matcher.identification_mode),
+                "value": calibration_value,
+            })
+        });
+        calibration_value
+    }
+
+    fn identify_calibration_values(
+        input: &str,
+        digit_matcher: &DigitMatcher,
+    ) -> Vec<CalibrationValue> {
+        let mut calibration_values = Vec::new();
+
+        for line in aocstd::input::lines(input) {
+            let calibration_value = identify_calibration_value_single_line(line, digit_matcher);
+            calibration_values.push(calibration_value);
+        }
+
+        calibration_values
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        // There is no model for this day, the lines are scanned while solving
+        Ok(format!("{} lines", aocstd::input::lines(input).count()))
+    }
+
+    const ANSI_RESET: &str = "\x1b[0m";
+    const ANSI_FIRST: &str = "\x1b[1;32m";
+    const ANSI_LAST: &str = "\x1b[1;34m";
+    /// When the first and last digits share letters (ex: "eightwo")
+    const ANSI_OVERLAP: &str = "\x1b[1;35m";
+
+    /// Echo every line with its first digit in green and its last one in blue (magenta where
+    /// they overlap), followed by its calibration value
+    pub fn explain(input: &str, part: Part, digit_names: &DigitNames) -> Result<String> {
+        let identification_mode = match part {
+            Part::Part1 => IdentificationMode::Digit,
+            Part::Part2 => IdentificationMode::DigitAndName,
+        };
+        let digit_matcher = DigitMatcher::new(identification_mode, digit_names);
+        let mut lines = Vec::new();
+        let mut sum: u32 = 0;
+        for line in aocstd::input::lines(input) {
+            let (first, last) = match digit_matcher.first_and_last(line) {
+                Some(((_, first), (_, last))) => (Some(first), Some(last)),
+                None => (None, None),
+            };
+            let in_digit = |range: &Option<Range<usize>>, index: usize| {
+                range.as_ref().is_some_and(|range| range.contains(&index))
+            };
+
+            let mut explained = String::new();
+            for (index, character) in line.char_indices() {
+                let color = match (in_digit(&first, index), in_digit(&last, index)) {
+                    (true, true) if first != last => Some(ANSI_OVERLAP),
+                    (true, _) => Some(ANSI_FIRST),
+                    (false, true) => Some(ANSI_LAST),
+                    (false, false) => None,
+                };
+                match color {
+                    Some(color) => {
+                        explained.push_str(color);
+                        explained.push(character);
+                        explained.push_str(ANSI_RESET);
+                    }
+                    None => explained.push(character),
+                }
+            }
+            let calibration_value = identify_calibration_value_single_line(line, &digit_matcher);
+            sum += calibration_value as u32;
+            lines.push(format!("{} -> {}", explained, calibration_value));
+        }
+        lines.push(format!("Sum of the calibration values: {}", sum));
+        Ok(lines.join("\n"))
+    }
+
+    struct CalibrationRepl {
+        lines: Vec<String>,
+        digit: DigitMatcher,
+        digit_and_name: DigitMatcher,
+    }
+
+    impl Queryable for CalibrationRepl {
+        fn help(&self) -> Vec<String> {
+            vec![String::from(
+                "line <n>: calibration values of the n-th line (starting at 1)",
+            )]
+        }
+
+        fn query(&self, command: &str, args: &[&str]) -> Result<String> {
+            match command {
+                "line" => {
+                    let line_number: usize = repl::arg(args, 0, "n")?;
+                    let line = line_number
+                        .checked_sub(1)
+                        .and_then(|index| self.lines.get(index))
+                        .ok_or_else(|| Error::new(format!("No line {}", line_number)))?;
+                    Ok(format!(
+                        "[{}] digits: {}, digits and names: {}",
+                        line,
+                        identify_calibration_value_single_line(line, &self.digit),
+                        identify_calibration_value_single_line(line, &self.digit_and_name)
+                    ))
+                }
+                _ => Err(repl::unknown_command(command)),
+            }
+        }
+    }
+
+    /// Queries over the input lines, both identification modes are shown whatever the part
+    pub fn repl(input: &str, _part: Part, digit_names: &DigitNames) -> Result<Box<dyn Queryable>> {
+        let lines = aocstd::input::lines(input).map(String::from).collect();
+        Ok(Box::new(CalibrationRepl {
+            lines,
+            digit: DigitMatcher::new(IdentificationMode::Digit, digit_names),
+            digit_and_name: DigitMatcher::new(IdentificationMode::DigitAndName, digit_names),
+        }))
+    }
+
+    /// The structured form of the input for --dump-parsed, there is nothing more than lines here
+    pub fn parsed(input: &str, _part: Part) -> Result<impl serde::Serialize> {
+        Ok(aocstd::input::lines(input)
+            .map(String::from)
+            .collect::<Vec<String>>())
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part, digit_names: &DigitNames) -> Result<Answer> {
+        let lines: Vec<String> = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        solve_with_digit_names(&lines.join("\n"), part, "automaton", digit_names)
+    }
+
+    /// The algorithms of --algorithm: `automaton` finds every digit of the lines (the default),
+    /// `scan` stops at the first digit from each end
+    pub const ALGORITHMS: [&str; 2] = ["automaton", "scan"];
+
+    pub fn solve_with_algorithm(input: &str, part: Part, algorithm: &str) -> Result<Answer> {
+        solve_with_digit_names(input, part, algorithm, &DigitNames::english())
+    }
+
+    /// Solve a part with the digits spelled with other words than the English ones, for
+    /// --digit-map. Part 1 only looks at the digits so it is the same whatever the names
+    pub fn solve_with_digit_names(
+        input: &str,
+        part: Part,
+        algorithm: &str,
+        digit_names: &DigitNames,
+    ) -> Result<Answer> {
+        let identification_mode = match part {
+            Part::Part1 => IdentificationMode::Digit,
+            Part::Part2 => IdentificationMode::DigitAndName,
+        };
+        let sum: u32 = match algorithm {
+            "automaton" => {
+                let digit_matcher = DigitMatcher::new(identification_mode, digit_names);
+                identify_calibration_values(input, &digit_matcher)
+                    .iter()
+                    .map(|x| *x as u32)
+                    .sum()
+            }
+            "scan" => aocstd::input::lines(input)
+                .map(|line| scan_calibration_value(line, identification_mode, digit_names) as u32)
+                .sum(),
+            _ => {
+                return Err(Error::new(format!(
+                    "Unknown algorithm {}, expected one of: {}",
+                    algorithm,
+                    ALGORITHMS.join(", ")
+                )))
+            }
+        };
+        Ok(Answer::from(sum))
+    }
+
+    /// The lines without any digit, they count for 0 in the sum which could hide a corrupted
+    /// input. They are logged as they are found and counted at the end
+    struct DigitFreeLines {
+        strictness: Strictness,
+        line_numbers: Vec<usize>,
+    }
+
+    impl DigitFreeLines {
+        fn new(strictness: Strictness) -> Self {
+            DigitFreeLines {
+                strictness,
+                line_numbers: Vec::new(),
+            }
+        }
+
+        fn check(&mut self, line_number: usize, line: &str, digit_matcher: &DigitMatcher) {
+            if digit_matcher.forward.is_match(line) {
+                return;
+            }
+            match self.strictness {
+                Strictness::Warn => log::warn!("No digits on line {}: {:?}", line_number, line),
+                Strictness::Error => log::error!("No digits on line {}: {:?}", line_number, line),
+            }
+            self.line_numbers.push(line_number);
+        }
+
+        fn summary(self) -> Result<()> {
+            if self.line_numbers.is_empty() {
+                return Ok(());
+            }
+            let count = self.line_numbers.len();
+            match self.strictness {
+                Strictness::Warn => {
+                    log::warn!("{} lines without digits", count);
+                    Ok(())
+                }
+                Strictness::Error => Err(Error::new(format!(
+                    "{} lines without digits: {}",
+                    count,
+                    self.line_numbers
+                        .iter()
+                        .map(|line_number| line_number.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))),
+            }
+        }
+    }
+
+    /// The calibration value of every line, for the code reusing the extraction outside of the
+    /// solver. A line without digits is worth 0
+    ///
+    /// ```
+    /// use day01::trebuchet::{calibration_values, DigitNames, IdentificationMode};
+    ///
+    /// let lines = "two1nine\neightwothree".lines();
+    /// let english = DigitNames::english();
+    /// let values: Vec<u8> =
+    ///     calibration_values(lines, IdentificationMode::DigitAndName, &english).collect();
+    /// assert_eq!(values, vec![29, 83]);
+    /// ```
+    pub fn calibration_values<'a>(
+        lines: impl Iterator<Item = &'a str> + 'a,
+        identification_mode: IdentificationMode,
+        digit_names: &DigitNames,
+    ) -> impl Iterator<Item = u8> + 'a {
+        let digit_matcher = DigitMatcher::new(identification_mode, digit_names);
+        lines.map(move |line| identify_calibration_value_single_line(line, &digit_matcher))
+    }
+
+    /// Report the lines without digits for --strict, the line numbers start at 1
+    pub fn check_lines(
+        input: &str,
+        part: Part,
+        digit_names: &DigitNames,
+        strictness: Strictness,
+    ) -> Result<()> {
+        let identification_mode = match part {
+            Part::Part1 => IdentificationMode::Digit,
+            Part::Part2 => IdentificationMode::DigitAndName,
+        };
+        let digit_matcher = DigitMatcher::new(identification_mode, digit_names);
+        let mut digit_free_lines = DigitFreeLines::new(strictness);
+        for (index, line) in aocstd::input::lines(input).enumerate() {
+            digit_free_lines.check(index + 1, line, &digit_matcher);
+        }
+        digit_free_lines.summary()
+    }
+
+    /// Write the calibration value of every line as soon as it is read, for --per-line, and
+    /// return their sum. The lines are read one by one instead of the whole input so it can be
+    /// used as a filter at the end of a pipe
+    pub fn stream_calibration_values(
+        mut input: impl BufRead,
+        mut output: impl Write,
+        part: Part,
+        format: LineFormat,
+        digit_names: &DigitNames,
+        strictness: Option<Strictness>,
+    ) -> Result<Answer> {
+        let identification_mode = match part {
+            Part::Part1 => IdentificationMode::Digit,
+            Part::Part2 => IdentificationMode::DigitAndName,
+        };
+        let digit_matcher = DigitMatcher::new(identification_mode, digit_names);
+        let mut digit_free_lines = strictness.map(DigitFreeLines::new);
+        let mut line = String::new();
+        let mut line_number = 0;
+        let mut sum: u32 = 0;
+        loop {
+            line.clear();
+            let read = input
+                .read_line(&mut line)
+                .map_err(|e| Error::new(format!("Cannot read line {}: {}", line_number + 1, e)))?;
+            if read == 0 {
+                break;
+            }
+            line_number += 1;
+            let line = line.trim_end_matches(['\n', '\r']);
+            if let Some(digit_free_lines) = &mut digit_free_lines {
+                digit_free_lines.check(line_number, line, &digit_matcher);
+            }
+            let calibration_value = identify_calibration_value_single_line(line, &digit_matcher);
+            sum += calibration_value as u32;
+            let written = match format {
+                LineFormat::Csv => writeln!(output, "{},{}", line_number, calibration_value),
+                LineFormat::Jsonl => writeln!(
+                    output,
+                    "{}",
+                    serde_json::json!({ "line": line_number, "value": calibration_value })
+                ),
+            };
+            written
+                .and_then(|_| output.flush())
+                .map_err(|e| Error::new(format!("Cannot write line {}: {}", line_number, e)))?;
+        }
+        if let Some(digit_free_lines) = digit_free_lines {
+            digit_free_lines.summary()?;
+        }
+        Ok(Answer::from(sum))
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        let calibration_values =
+            identify_calibration_values(input, DigitMatcher::get(IdentificationMode::Digit));
+        let sum: u32 = calibration_values.iter().map(|x| *x as u32).sum();
+        Ok(Answer::from(sum))
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        let calibration_values =
+            identify_calibration_values(input, DigitMatcher::get(IdentificationMode::DigitAndName));
+        let sum: u32 = calibration_values.iter().map(|x| *x as u32).sum();
+        Ok(Answer::from(sum))
+    }
+
+    #[cfg(test)]
+    mod test {
+        #[test]
+        fn test_digits_only() {
+            aocstd::init_tests();
+
+            let input = "1abc2\n\
+                 pqr3stu8vwx\n\
+                 a1b2c3d4e5f\n\
+                 treb7uchet";
+            let calibration_values = super::identify_calibration_values(
+                input,
+                super::DigitMatcher::get(super::IdentificationMode::Digit),
+            );
+            assert_eq!(calibration_values, vec![12, 38, 15, 77]);
+        }
+
+        #[test]
+        fn test_digits_and_names() {
+            aocstd::init_tests();
+
+            let input = "two1nine\n\
+                 eightwothree\n\
+                 abcone2threexyz\n\
+                 xtwone3four\n\
+                 4nineeightseven2\n\
+                 zoneight234\n\
+                 7pqrstsixteen";
+
+            let calibration_values = super::identify_calibration_values(
+                input,
+                super::DigitMatcher::get(super::IdentificationMode::DigitAndName),
+            );
+            assert_eq!(calibration_values, vec![29, 83, 13, 24, 42, 14, 76]);
+        }
+
+        #[test]
+        fn test_scan() {
+            aocstd::init_tests();
+
+            let input = "two1nine\n\
+                         eightwothree\n\
+                         abcone2threexyz\n\
+                         xtwone3four\n\
+                         4nineeightseven2\n\
+                         zoneight234\n\
+                         7pqrstsixteen\n\
+                         eightwo";
+            assert_eq!(
+                super::solve_with_algorithm(input, aocstd::Part::Part2, "scan").unwrap(),
+                aocstd::Answer::from(363u32)
+            );
+            assert_eq!(
+                super::solve_with_algorithm(input, aocstd::Part::Part1, "scan").unwrap(),
+                super::solve_part1(input).unwrap()
+            );
+        }
+
+        #[test]
+        fn test_digit_map() {
+            aocstd::init_tests();
+
+            let french =
+                super::DigitNames::from_toml(include_str!("../digit-maps/french.toml")).unwrap();
+            // "huitrois" overlaps like "eightwo", and "one" is not a digit anymore
+            let input = "deuxone7\n\
+                         xhuitrois\n\
+                         zérotwo";
+            for algorithm in super::ALGORITHMS {
+                assert_eq!(
+                    super::solve_with_digit_names(input, aocstd::Part::Part2, algorithm, &french)
+                        .unwrap(),
+                    aocstd::Answer::from(27u32 + 83)
+                );
+            }
+            // Every entry point uses the loaded names instead of the English ones
+            let values: Vec<u8> = super::calibration_values(
+                input.lines(),
+                super::IdentificationMode::DigitAndName,
+                &french,
+            )
+            .collect();
+            assert_eq!(values, vec![27, 83, 0]);
+            let explained = super::explain(input, aocstd::Part::Part2, &french).unwrap();
+            assert!(explained.ends_with("Sum of the calibration values: 110"));
+            let json = serde_json::to_string(&input.lines().collect::<Vec<_>>()).unwrap();
+            assert_eq!(
+                super::solve_json(&json, aocstd::Part::Part2, &french).unwrap(),
+                aocstd::Answer::from(27u32 + 83)
+            );
+            let repl = super::repl(input, aocstd::Part::Part2, &french).unwrap();
+            assert_eq!(
+                repl.query("line", &["1"]).unwrap(),
+                "[deuxone7] digits: 77, digits and names: 27"
+            );
+            assert_eq!(
+                super::DigitNames::from_toml("one = 1\ntwo = 2").unwrap(),
+                super::DigitNames {
+                    names: vec![(String::from("one"), 1), (String::from("two"), 2)]
+                }
+            );
+            assert!(super::DigitNames::from_toml("ten = 10").is_err());
+            assert!(super::DigitNames::from_toml("\"\" = 1").is_err());
+            assert!(super::DigitNames::from_toml("one = \"1\"").is_err());
+        }
+
+        #[test]
+        fn test_utf8() {
+            aocstd::init_tests();
+
+            let input = "é1è\n\
+                         ñtwo€3ü\n\
+                         日本eightwo語\n\
+                         çà";
+            for algorithm in super::ALGORITHMS {
+                assert_eq!(
+                    super::solve_with_algorithm(input, aocstd::Part::Part2, algorithm).unwrap(),
+                    aocstd::Answer::from(11u32 + 23 + 82)
+                );
+                assert_eq!(
+                    super::solve_with_algorithm(input, aocstd::Part::Part1, algorithm).unwrap(),
+                    aocstd::Answer::from(11u32 + 33)
+                );
+            }
+            // The colors surround whole characters
+            let explained =
+                super::explain("é1è", aocstd::Part::Part1, &super::DigitNames::english()).unwrap();
+            assert!(explained.starts_with("é\x1b"));
+            assert!(explained.contains("1\x1b[0mè -> 11"));
+            // The shared letter of the first and last digits, a single digit is only the first
+            let english = super::DigitNames::english();
+            let explained = super::explain("xeightwo", aocstd::Part::Part2, &english).unwrap();
+            assert!(explained.contains("h\x1b[0m\x1b[1;35mt\x1b[0m\x1b[1;34mw"));
+            assert!(explained.ends_with("-> 82\nSum of the calibration values: 82"));
+            let explained = super::explain("a7b", aocstd::Part::Part1, &english).unwrap();
+            assert!(explained.starts_with("a\x1b[1;32m7\x1b[0mb -> 77"));
+        }
+
+        #[test]
+        fn test_per_line() {
+            aocstd::init_tests();
+
+            let input = "two1nine\r\neightwothree\n\nabcone2threexyz";
+            let mut csv = Vec::new();
+            let sum = super::stream_calibration_values(
+                input.as_bytes(),
+                &mut csv,
+                aocstd::Part::Part2,
+                aocstd::LineFormat::Csv,
+                &super::DigitNames::english(),
+                None,
+            )
+            .unwrap();
+            assert_eq!(sum, aocstd::Answer::from(29u32 + 83 + 13));
+            assert_eq!(String::from_utf8(csv).unwrap(), "1,29\n2,83\n3,0\n4,13\n");
+
+            let mut jsonl = Vec::new();
+            super::stream_calibration_values(
+                "a1b2".as_bytes(),
+                &mut jsonl,
+                aocstd::Part::Part1,
+                aocstd::LineFormat::Jsonl,
+                &super::DigitNames::english(),
+                Some(aocstd::Strictness::Error),
+            )
+            .unwrap();
+            assert_eq!(
+                String::from_utf8(jsonl).unwrap(),
+                "{\"line\":1,\"value\":12}\n"
+            );
+        }
+
+        #[test]
+        fn test_strict() {
+            aocstd::init_tests();
+
+            let english = super::DigitNames::english();
+            // A 0 digit is still a digit, the third line has none in part 1
+            let input = "a0b\nthree\nxyz\n7";
+            let check = |part, strictness| super::check_lines(input, part, &english, strictness);
+            assert!(check(aocstd::Part::Part2, aocstd::Strictness::Warn).is_ok());
+            assert_eq!(
+                check(aocstd::Part::Part1, aocstd::Strictness::Error)
+                    .unwrap_err()
+                    .to_string(),
+                "2 lines without digits: 2, 3"
+            );
+            assert!(check(aocstd::Part::Part1, aocstd::Strictness::Warn).is_ok());
+            assert!(super::stream_calibration_values(
+                input.as_bytes(),
+                std::io::sink(),
+                aocstd::Part::Part2,
+                aocstd::LineFormat::Csv,
+                &english,
+                Some(aocstd::Strictness::Error),
+            )
+            .is_err());
+        }
+    }
+}
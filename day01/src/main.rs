@@ -1,164 +1,101 @@
 use clap::Parser;
-use std::io::BufRead;
-
-mod trebuchet {
-
-    use phf::phf_map;
-    use std::io::BufRead;
-
-    type CalibrationValue = u8;
-
-    #[derive(Copy, Clone, Eq, PartialEq, Debug)]
-    enum IdentificationMode {
-        Digit,
-        DigitAndName,
-    }
+use day01::trebuchet;
+use std::path::PathBuf;
+
+/// The shared options along with the ones of this day only
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(flatten)]
+    cli: aocstd::Cli,
+    /// TOML file of `word = value` giving the words of the digits instead of the English ones
+    #[arg(long)]
+    digit_map: Option<PathBuf>,
+    /// Write the calibration value of every input line to stdout as soon as it is read, then log
+    /// the sum
+    #[arg(long, value_enum)]
+    per_line: Option<aocstd::LineFormat>,
+    /// Report the lines without digits as warnings or as errors
+    #[arg(long, value_enum)]
+    strict: Option<aocstd::Strictness>,
+    /// Algorithm of the solver: automaton (the default) or scan
+    #[arg(long)]
+    algorithm: Option<String>,
+    /// Echo every line with its first and last digits colored instead of solving
+    #[arg(long)]
+    explain: bool,
+}
 
-    /// Digits and their associated values
-    /// We are using phf crate to create a static Map
-    static DIGITS: phf::Map<&'static str, u8> = phf_map! {
-        "zero" => 0,
-        "one" => 1,
-        "two" => 2,
-        "three" => 3,
-        "four" => 4,
-        "five" => 5,
-        "six" => 6,
-        "seven" => 7,
-        "eight" => 8,
-        "nine" => 9,
+fn main() {
+    let args = Args::parse();
+    let cli = args.cli;
+    aocstd::init_logger(&cli);
+    aocstd::init_threads(&cli);
+    aocstd::init_trace(&cli);
+    let digit_names = match &args.digit_map {
+        Some(path) => trebuchet::DigitNames::from_file(path),
+        None => Ok(trebuchet::DigitNames::english()),
     };
-
-    fn identify_calibration_value_single_line(
-        line: &str,
-        identification_mode: IdentificationMode,
-    ) -> CalibrationValue {
-        let mut first: Option<u8> = None;
-        let mut last: Option<u8> = None;
-
-        for (index, character) in line.chars().enumerate() {
-            let mut current_digit: Option<u8> = None;
-
-            if character.is_digit(10) {
-                current_digit = Some(character.to_digit(10).expect("Invalid digit") as u8);
-            } else if identification_mode == IdentificationMode::DigitAndName {
-                // if it is not a Digit we need to check if it is a digit from the enum
-                for (digit_name, digit_value) in DIGITS.entries() {
-                    // if the rest of the line is shorter than the name of the Digit, we can skip the rest of the line
-                    if index + digit_name.len() <= line.len() {
-                        // Create a slice of tjhe line from the current index to the end of the matching Digit
-                        let slice = &line[index..index + digit_name.len()];
-                        if slice == *digit_name {
-                            // if the slice is equal to the name of the Digit, we can add the value of the digit to the count
-                            current_digit = Some(*digit_value as u8);
-                        }
-                    }
-                }
-            }
-
-            // We have found a Digit
-            if current_digit.is_some() {
-                if first.is_none() {
-                    first = current_digit;
-                }
-                last = current_digit;
-            }
-        }
-
-        // find the first and the last Digit of the line
-        // Create the line number by associating the two Digits
-        let calibration_value = match (first, last) {
-            (Some(f), Some(l)) => f * 10 + l,
-            _ => 0,
-        };
-
-        log::debug!("line=[{}] calibration_value=[{}]", line, calibration_value);
-        return calibration_value;
-    }
-
-    fn identify_calibration_values(
-        input_stream: Box<dyn BufRead>,
-        identification_mode: IdentificationMode,
-    ) -> Vec<CalibrationValue> {
-        let mut calibration_values = Vec::new();
-
-        for line in input_stream.lines() {
-            let line = line.expect("Cannot read line");
-            let calibration_value =
-                identify_calibration_value_single_line(&line, identification_mode);
-            calibration_values.push(calibration_value);
+    let digit_names = match digit_names {
+        Ok(digit_names) => digit_names,
+        Err(error) => {
+            aocstd::report_answer(cli.part, Err(error));
+            return;
         }
+    };
 
-        return calibration_values;
+    // Before reading the whole input, the lines are streamed one by one
+    if let Some(format) = args.per_line {
+        let answer = trebuchet::stream_calibration_values(
+            aocstd::get_input_stream(&cli),
+            std::io::stdout().lock(),
+            cli.part,
+            format,
+            &digit_names,
+            args.strict,
+        );
+        aocstd::report_answer(cli.part, answer);
+        return;
     }
 
-    #[cfg(test)]
-    mod test {
-        #[test]
-        fn test_digits_only() {
-            aocstd::init_tests();
-
-            let input_stream = Box::new(std::io::BufReader::new(
-                "1abc2\n\
-                 pqr3stu8vwx\n\
-                 a1b2c3d4e5f\n\
-                 treb7uchet"
-                    .as_bytes(),
-            ));
-            let calibration_values =
-                super::identify_calibration_values(input_stream, super::IdentificationMode::Digit);
-            assert_eq!(calibration_values, vec![12, 38, 15, 77]);
-        }
-
-        #[test]
-        fn test_digits_and_names() {
-            aocstd::init_tests();
-
-            let input_stream = Box::new(std::io::BufReader::new(
-                "two1nine\n\
-                 eightwothree\n\
-                 abcone2threexyz\n\
-                 xtwone3four\n\
-                 4nineeightseven2\n\
-                 zoneight234\n\
-                 7pqrstsixteen"
-                    .as_bytes(),
-            ));
+    let input = aocstd::get_input_string(&cli);
+    aocstd::init_profile(&cli);
 
-            let calibration_values = super::identify_calibration_values(
-                input_stream,
-                super::IdentificationMode::DigitAndName,
-            );
-            assert_eq!(calibration_values, vec![29, 83, 13, 24, 42, 14, 76]);
-        }
+    if let Some(path) = &cli.dump_parsed {
+        aocstd::dump_parsed(path, trebuchet::parsed(&input, cli.part));
+        return;
     }
-
-    pub fn solve_part1(input_stream: Box<dyn BufRead>) {
-        let calibration_values =
-            identify_calibration_values(input_stream, IdentificationMode::Digit);
-        let sum: u32 = calibration_values.iter().map(|x| *x as u32).sum();
-        log::info!("Part 1: {}", sum);
+    if args.explain {
+        aocstd::print_visualization(trebuchet::explain(&input, cli.part, &digit_names));
+        return;
     }
-
-    pub fn solve_part2(input_stream: Box<dyn BufRead>) {
-        let calibration_values =
-            identify_calibration_values(input_stream, IdentificationMode::DigitAndName);
-        let sum: u32 = calibration_values.iter().map(|x| *x as u32).sum();
-        log::info!("Part 2: {}", sum);
+    if cli.repl {
+        aocstd::start_repl(&cli, trebuchet::repl(&input, cli.part, &digit_names));
+        return;
     }
-}
-
-fn main() {
-    let cli = aocstd::Cli::parse();
-    aocstd::init_logger(&cli);
-    let input_stream: Box<dyn BufRead> = aocstd::get_input_stream(&cli);
 
-    match cli.part {
-        aocstd::Part::Part1 => {
-            trebuchet::solve_part1(input_stream);
+    let answer = match (cli.input_format, cli.part, args.algorithm.as_deref()) {
+        (aocstd::InputFormat::Json, part, _) => trebuchet::solve_json(&input, part, &digit_names),
+        (aocstd::InputFormat::Text, part, algorithm) if args.digit_map.is_some() => {
+            trebuchet::solve_with_digit_names(
+                &input,
+                part,
+                algorithm.unwrap_or("automaton"),
+                &digit_names,
+            )
         }
-        aocstd::Part::Part2 => {
-            trebuchet::solve_part2(input_stream);
+        (aocstd::InputFormat::Text, part, Some(algorithm)) => {
+            trebuchet::solve_with_algorithm(&input, part, algorithm)
         }
-    }
+        (aocstd::InputFormat::Text, aocstd::Part::Part1, None) => trebuchet::solve_part1(&input),
+        (aocstd::InputFormat::Text, aocstd::Part::Part2, None) => trebuchet::solve_part2(&input),
+    };
+    // Checked after solving so the count of the lines without digits ends next to the answer
+    let answer = match (cli.input_format, args.strict) {
+        (aocstd::InputFormat::Text, Some(strictness)) => answer.and_then(|answer| {
+            trebuchet::check_lines(&input, cli.part, &digit_names, strictness).map(|_| answer)
+        }),
+        _ => answer,
+    };
+    aocstd::report_answer(cli.part, answer);
 }
@@ -0,0 +1,994 @@
+pub mod cube_conundrum {
+
+    use aocstd::parsers::nom::branch::alt;
+    use aocstd::parsers::nom::bytes::complete::tag;
+    use aocstd::parsers::nom::character::complete::{char, space0, space1};
+    use aocstd::parsers::nom::combinator::{map, map_opt, opt, value};
+    use aocstd::parsers::nom::multi::separated_list1;
+    use aocstd::parsers::nom::sequence::{delimited, pair, preceded, separated_pair, tuple};
+    use aocstd::parsers::{self, IResult};
+    use aocstd::repl::{self, Queryable};
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize, Serializer};
+    use std::collections::BTreeMap;
+    use std::vec::Vec;
+
+    /// A game is represented by each line of the input in the form
+    /// ex: Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+    struct Game {
+        id: i32,
+        sets: Vec<GameSet>,
+    }
+
+    struct GameSet {
+        cubes_played: Inventory,
+    }
+
+    type NbPlayed = u64;
+
+    /// A color of cube, its index in the [`Palette`] of the games so it is a copyable number
+    /// instead of a String. The three colors of the puzzle always have the same numbers
+    #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+    struct CubeColor(u32);
+
+    impl CubeColor {
+        const RED: CubeColor = CubeColor(0);
+        const GREEN: CubeColor = CubeColor(1);
+        const BLUE: CubeColor = CubeColor(2);
+        const KNOWN: [CubeColor; 3] = [CubeColor::RED, CubeColor::GREEN, CubeColor::BLUE];
+
+        /// The index of the color in the array of the known ones
+        fn known_index(self) -> Option<usize> {
+            Some(self.0 as usize).filter(|index| *index < Self::KNOWN.len())
+        }
+    }
+
+    /// The names of the colors of the parsed games, the other names found in the input get the
+    /// next numbers the first time they are seen
+    struct Palette {
+        names: Vec<String>,
+    }
+
+    impl Palette {
+        fn new() -> Self {
+            Palette {
+                names: ["red", "green", "blue"].map(String::from).to_vec(),
+            }
+        }
+
+        /// The color of a name, a new one if it was never seen
+        fn color(&mut self, name: &str) -> CubeColor {
+            self.find(name).unwrap_or_else(|| {
+                self.names.push(name.to_string());
+                CubeColor(self.names.len() as u32 - 1)
+            })
+        }
+
+        /// None for the names not in the games
+        fn find(&self, name: &str) -> Option<CubeColor> {
+            let index = self.names.iter().position(|known| known == name)?;
+            Some(CubeColor(index as u32))
+        }
+
+        fn name(&self, cube_color: CubeColor) -> &str {
+            &self.names[cube_color.0 as usize]
+        }
+
+        /// ex: 20 red, 13 green, 6 blue
+        fn describe(&self, inventory: &Inventory) -> String {
+            inventory
+                .iter()
+                .map(|(cube_color, nb_played)| format!("{} {}", nb_played, self.name(cube_color)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+
+        fn named(&self, inventory: &Inventory) -> NamedCubes {
+            NamedCubes(
+                inventory
+                    .iter()
+                    .map(|(cube_color, nb_played)| (self.name(cube_color).to_string(), nb_played))
+                    .collect(),
+            )
+        }
+    }
+
+    /// The games of the input along with the names of their colors
+    struct Games {
+        palette: Palette,
+        games: Vec<Game>,
+    }
+
+    impl Games {
+        /// Number the colors of the games in the order they are first seen. A color can only be
+        /// played once in a set
+        fn new(named_games: Vec<NamedGame>) -> Result<Self> {
+            let mut palette = Palette::new();
+            let mut games = Vec::with_capacity(named_games.len());
+            for named_game in named_games {
+                let mut sets = Vec::with_capacity(named_game.sets.len());
+                for (set_index, named_set) in named_game.sets.into_iter().enumerate() {
+                    let cubes = named_set.cubes_played.0;
+                    for (index, (name, _)) in cubes.iter().enumerate() {
+                        if cubes[..index].iter().any(|(seen, _)| seen == name) {
+                            return Err(Error::new(format!(
+                                "{} is played twice in set {} of game {}",
+                                name,
+                                set_index + 1,
+                                named_game.id
+                            )));
+                        }
+                    }
+                    let mut cubes_played = Inventory::new();
+                    for (name, nb_played) in cubes {
+                        cubes_played
+                            .add(palette.color(&name), nb_played)
+                            .ok_or_else(|| {
+                                Error::new(format!(
+                                    "Too many {} cubes in a set of game {}",
+                                    name, named_game.id
+                                ))
+                            })?;
+                    }
+                    sets.push(GameSet { cubes_played });
+                }
+                games.push(Game {
+                    id: named_game.id,
+                    sets,
+                });
+            }
+            Ok(Games { palette, games })
+        }
+
+        /// The games with the names of their colors again, for the JSON
+        fn named(&self) -> Vec<NamedGame> {
+            self.games
+                .iter()
+                .map(|game| NamedGame {
+                    id: game.id,
+                    sets: game
+                        .sets
+                        .iter()
+                        .map(|set| NamedSet {
+                            cubes_played: self.palette.named(&set.cubes_played),
+                        })
+                        .collect(),
+                })
+                .collect()
+        }
+    }
+
+    /// A game with the names of its colors, as it is in the input and in the JSON of
+    /// --dump-parsed
+    #[derive(Serialize, Deserialize)]
+    struct NamedGame {
+        id: i32,
+        sets: Vec<NamedSet>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct NamedSet {
+        cubes_played: NamedCubes,
+    }
+
+    /// The cubes of a set by name, a map in the JSON
+    #[derive(Deserialize)]
+    #[serde(from = "BTreeMap<String, NbPlayed>")]
+    struct NamedCubes(Vec<(String, NbPlayed)>);
+
+    /// In the order of the colors instead of the order of the names
+    impl Serialize for NamedCubes {
+        fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+            serializer.collect_map(self.0.iter().map(|(name, nb_played)| (name, nb_played)))
+        }
+    }
+
+    impl From<BTreeMap<String, NbPlayed>> for NamedCubes {
+        fn from(counts: BTreeMap<String, NbPlayed>) -> Self {
+            NamedCubes(counts.into_iter().collect())
+        }
+    }
+
+    /// The number of cubes of each color. The known colors are in an array so the sets of the
+    /// puzzle never allocate, the other colors are only counted aside. Both are in the order of
+    /// the colors so the logs and the JSON always list them the same way
+    #[derive(Clone, Default, PartialEq, Eq)]
+    struct Inventory {
+        known: [NbPlayed; 3],
+        /// Never with a count of 0
+        others: BTreeMap<CubeColor, NbPlayed>,
+    }
+
+    impl Inventory {
+        fn new() -> Self {
+            Self::default()
+        }
+
+        /// The new count of the color, None when it overflows
+        fn add(&mut self, cube_color: CubeColor, nb_played: NbPlayed) -> Option<NbPlayed> {
+            let count = match cube_color.known_index() {
+                Some(index) => &mut self.known[index],
+                None if nb_played == 0 => return Some(self.get(cube_color)),
+                None => self.others.entry(cube_color).or_insert(0),
+            };
+            *count = count.checked_add(nb_played)?;
+            Some(*count)
+        }
+
+        /// 0 for the colors never added
+        fn get(&self, cube_color: CubeColor) -> NbPlayed {
+            match cube_color.known_index() {
+                Some(index) => self.known[index],
+                None => self.others.get(&cube_color).copied().unwrap_or(0),
+            }
+        }
+
+        /// The colors with at least one cube
+        fn iter(&self) -> impl Iterator<Item = (CubeColor, NbPlayed)> + '_ {
+            CubeColor::KNOWN
+                .into_iter()
+                .zip(self.known)
+                .filter(|(_, nb_played)| *nb_played > 0)
+                .chain(
+                    self.others
+                        .iter()
+                        .map(|(cube_color, nb)| (*cube_color, *nb)),
+                )
+        }
+
+        /// Keep the larger count of every color of both inventories
+        fn merge_max(&mut self, other: &Inventory) {
+            for (mine, theirs) in self.known.iter_mut().zip(other.known) {
+                *mine = (*mine).max(theirs);
+            }
+            for (cube_color, nb_played) in &other.others {
+                let current = self.others.entry(*cube_color).or_insert(0);
+                *current = (*current).max(*nb_played);
+            }
+        }
+    }
+
+    impl NamedGame {
+        /// Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+        fn parser(input: &str) -> IResult<'_, NamedGame> {
+            map(
+                pair(
+                    parsers::header("Game"),
+                    separated_list1(pair(char(';'), space0), NamedSet::parser),
+                ),
+                |(id, sets)| NamedGame { id, sets },
+            )(input)
+        }
+    }
+
+    impl NamedSet {
+        /// 3 blue, 4 red
+        fn parser(input: &str) -> IResult<'_, NamedSet> {
+            map(
+                separated_list1(
+                    pair(char(','), space0),
+                    separated_pair(parsers::integer, space1, parsers::word),
+                ),
+                |cubes| NamedSet {
+                    cubes_played: NamedCubes(
+                        cubes
+                            .into_iter()
+                            .map(|(nb_played, name)| (name.to_string(), nb_played))
+                            .collect(),
+                    ),
+                },
+            )(input)
+        }
+    }
+
+    impl Game {
+        fn is_game_valid(&self, elf_inventory: &Inventory, palette: &Palette) -> bool {
+            log::debug!(" - Checking if game {} is valid", self.id);
+            log::debug!(" - Elf inventory is {}", palette.describe(elf_inventory));
+
+            for game_set in &self.sets {
+                if !game_set.is_set_valid(elf_inventory, palette) {
+                    log::debug!(" - The elf does not have enough cubes to play this game");
+                    return false;
+                }
+            }
+            true
+        }
+
+        /// The smallest inventory with which the game could have been played
+        fn get_larger_set(&self) -> Inventory {
+            let mut larger_set = Inventory::new();
+            for game_set in &self.sets {
+                larger_set.merge_max(&game_set.cubes_played);
+            }
+            larger_set
+        }
+
+        /// An error instead of wrapping around when the counts are too large to multiply
+        fn get_game_power(&self, palette: &Palette) -> Result<u64> {
+            let larger_set = self.get_larger_set();
+            log::debug!(" - Larger set is {}", palette.describe(&larger_set));
+
+            // The power of the set is the multiplication of the number of cubes of each cube_color
+            let mut power: u64 = 1;
+            for (_, nb_played) in larger_set.iter() {
+                power = power.checked_mul(nb_played).ok_or_else(|| {
+                    Error::new(format!(
+                        "The power of game {} overflows, its larger set is {}",
+                        self.id,
+                        palette.describe(&larger_set)
+                    ))
+                })?;
+            }
+            log::debug!(" - Power of the set is {}", power);
+
+            Ok(power)
+        }
+    }
+
+    impl GameSet {
+        fn is_set_valid(&self, elf_inventory: &Inventory, palette: &Palette) -> bool {
+            for (cube_color, nb_played) in self.cubes_played.iter() {
+                if elf_inventory.get(cube_color) < nb_played {
+                    log::debug!(
+                        "   - The elf does not have enough {} cubes to play this set",
+                        palette.name(cube_color)
+                    );
+                    return false;
+                }
+            }
+            true
+        }
+    }
+
+    /// One game per line, the errors give the line and the column of the problem along with the
+    /// game and the set it is in
+    fn parse_games(input: &str) -> Result<Games> {
+        let named_games =
+            parsers::parse_all(parsers::lines(NamedGame::parser), input).map_err(|error| {
+                match broken_game(input) {
+                    Some(context) => Error::new(format!("{} in {}", error, context)),
+                    None => error,
+                }
+            })?;
+        Games::new(named_games)
+    }
+
+    /// The id and the raw set of the first game that does not parse on its own, the parse
+    /// errors only know the position
+    fn broken_game(input: &str) -> Option<String> {
+        let line = aocstd::input::lines(input)
+            .find(|line| parsers::parse_all(NamedGame::parser, line).is_err())?;
+        let (sets, id) = parsers::header::<i32>("Game")(line).ok()?;
+        let broken_set = sets
+            .split(';')
+            .map(str::trim)
+            .find(|set| parsers::parse_all(NamedSet::parser, set).is_err());
+        Some(match broken_set {
+            Some(set) => format!("game {}, set {:?}", id, set),
+            None => format!("game {}", id),
+        })
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let games = parse_games(input)?.games;
+        let nb_of_sets: usize = games.iter().map(|game| game.sets.len()).sum();
+        Ok(format!("{} games, {} sets", games.len(), nb_of_sets))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        Ok(parse_games(input)?.named())
+    }
+
+    /// The cubes the elf has in the bag for part 1
+    fn elf_inventory() -> Inventory {
+        Inventory {
+            known: [12, 13, 14],
+            others: BTreeMap::new(),
+        }
+    }
+
+    /// One row per game with its larger set, validity and power, and the answers of both parts
+    /// as a footer
+    pub fn explain(input: &str, _part: Part) -> Result<String> {
+        let elf_inventory = elf_inventory();
+        let mut rows = vec![
+            format!(
+                "{:>5} {:>5} {:>5} {:>5} {:>5} {:>7}",
+                "Game", "Red", "Green", "Blue", "Valid", "Power"
+            ),
+            "-".repeat(37),
+        ];
+        let mut sum_of_valids_game_ids = 0;
+        let mut sum_of_the_sets_power: u64 = 0;
+        let Games { palette, games } = parse_games(input)?;
+        for game in &games {
+            let larger_set = game.get_larger_set();
+            let cubes = |cube_color| larger_set.get(cube_color);
+            let valid = game.is_game_valid(&elf_inventory, &palette);
+            let power = game.get_game_power(&palette)?;
+            if valid {
+                sum_of_valids_game_ids = add_id(sum_of_valids_game_ids, game)?;
+            }
+            sum_of_the_sets_power = add_power(sum_of_the_sets_power, power, game)?;
+            rows.push(format!(
+                "{:>5} {:>5} {:>5} {:>5} {:>5} {:>7}",
+                game.id,
+                cubes(CubeColor::RED),
+                cubes(CubeColor::GREEN),
+                cubes(CubeColor::BLUE),
+                if valid { "yes" } else { "no" },
+                power
+            ));
+        }
+        rows.push("-".repeat(37));
+        rows.push(format!(
+            "Sum of the valid game ids: {}, sum of the powers: {}",
+            sum_of_valids_game_ids, sum_of_the_sets_power
+        ));
+        Ok(rows.join("\n"))
+    }
+
+    struct GameRepl {
+        games: Games,
+    }
+
+    impl Queryable for GameRepl {
+        fn help(&self) -> Vec<String> {
+            vec![String::from(
+                "game <id>: larger set, power and validity of a game",
+            )]
+        }
+
+        fn query(&self, command: &str, args: &[&str]) -> Result<String> {
+            match command {
+                "game" => {
+                    let id: i32 = repl::arg(args, 0, "id")?;
+                    let Games { palette, games } = &self.games;
+                    let game = games
+                        .iter()
+                        .find(|game| game.id == id)
+                        .ok_or_else(|| Error::new(format!("No game {}", id)))?;
+                    Ok(format!(
+                        "{} sets, larger set: {}, power: {}, valid: {}",
+                        game.sets.len(),
+                        palette.describe(&game.get_larger_set()),
+                        game.get_game_power(palette)?,
+                        game.is_game_valid(&elf_inventory(), palette)
+                    ))
+                }
+                _ => Err(repl::unknown_command(command)),
+            }
+        }
+    }
+
+    /// Queries over the parsed games, the validity is always checked against the part 1 bag
+    pub fn repl(input: &str, _part: Part) -> Result<Box<dyn Queryable>> {
+        let games = parse_games(input)?;
+        Ok(Box::new(GameRepl { games }))
+    }
+
+    /// The smallest bag with which every game could have been played: the larger count of each
+    /// color in any set of the input
+    fn minimum_inventory(games: &[Game]) -> Inventory {
+        let mut minimum_inventory = Inventory::new();
+        for game in games {
+            minimum_inventory.merge_max(&game.get_larger_set());
+        }
+        minimum_inventory
+    }
+
+    /// Solve a part and log the smallest bag for all the games along with it, for --report
+    pub fn solve_with_report(input: &str, part: Part) -> Result<Answer> {
+        let games = parse_games(input)?;
+        let minimum_inventory = minimum_inventory(&games.games);
+        log::info!(
+            "Smallest bag for all the games: {}",
+            games.palette.describe(&minimum_inventory)
+        );
+        trace::emit(
+            "minimum_inventory",
+            || serde_json::json!({ "inventory": games.palette.named(&minimum_inventory) }),
+        );
+        match part {
+            Part::Part1 => solve_part1_parsed(&games),
+            Part::Part2 => solve_part2_parsed(&games),
+        }
+    }
+
+    /// A number of a game for --filter, the colors are names since the filter is parsed before
+    /// the games
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Stat {
+        Id,
+        Sets,
+        Power,
+        /// The most cubes of a color in a set
+        Max(String),
+        /// The fewest cubes of a color in a set, 0 when a set has none
+        Min(String),
+        /// The cubes of a color in all the sets
+        Sum(String),
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Comparison {
+        Less,
+        LessOrEqual,
+        Greater,
+        GreaterOrEqual,
+        Equal,
+        NotEqual,
+    }
+
+    /// The games to keep for --filter
+    /// ex: max(red)>10 && sets>=3
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Filter {
+        Compare(Stat, Comparison, u64),
+        Not(Box<Filter>),
+        And(Vec<Filter>),
+        Or(Vec<Filter>),
+    }
+
+    impl Stat {
+        /// id, sets, power, or max, min and sum of a color: max(red)
+        fn parser(input: &str) -> IResult<'_, Stat> {
+            map_opt(
+                pair(
+                    parsers::word,
+                    opt(delimited(
+                        pair(char('('), space0),
+                        parsers::word,
+                        pair(space0, char(')')),
+                    )),
+                ),
+                |(name, cube_color)| {
+                    let stat = match (name, cube_color.map(String::from)) {
+                        ("id", None) => Stat::Id,
+                        ("sets", None) => Stat::Sets,
+                        ("power", None) => Stat::Power,
+                        ("max", Some(cube_color)) => Stat::Max(cube_color),
+                        ("min", Some(cube_color)) => Stat::Min(cube_color),
+                        ("sum", Some(cube_color)) => Stat::Sum(cube_color),
+                        _ => return None,
+                    };
+                    Some(stat)
+                },
+            )(input)
+        }
+
+        /// The colors missing from the games have no cubes
+        fn of(&self, game: &Game, palette: &Palette) -> Result<u64> {
+            let counts = |name: &str| {
+                let cube_color = palette.find(name);
+                game.sets.iter().map(move |set| {
+                    cube_color.map_or(0, |cube_color| set.cubes_played.get(cube_color))
+                })
+            };
+            match self {
+                Stat::Id => u64::try_from(game.id)
+                    .map_err(|_| Error::new(format!("Negative game id {}", game.id))),
+                Stat::Sets => Ok(game.sets.len() as u64),
+                Stat::Power => game.get_game_power(palette),
+                Stat::Max(cube_color) => Ok(counts(cube_color).max().unwrap_or(0)),
+                Stat::Min(cube_color) => Ok(counts(cube_color).min().unwrap_or(0)),
+                Stat::Sum(cube_color) => counts(cube_color)
+                    .try_fold(0u64, |sum, nb_played| sum.checked_add(nb_played))
+                    .ok_or_else(|| {
+                        Error::new(format!(
+                            "The {} cubes of game {} overflow",
+                            cube_color, game.id
+                        ))
+                    }),
+            }
+        }
+    }
+
+    impl Comparison {
+        fn parser(input: &str) -> IResult<'_, Comparison> {
+            alt((
+                value(Comparison::LessOrEqual, tag("<=")),
+                value(Comparison::GreaterOrEqual, tag(">=")),
+                value(Comparison::Equal, tag("==")),
+                value(Comparison::NotEqual, tag("!=")),
+                value(Comparison::Less, tag("<")),
+                value(Comparison::Greater, tag(">")),
+            ))(input)
+        }
+
+        fn holds(self, left: u64, right: u64) -> bool {
+            match self {
+                Comparison::Less => left < right,
+                Comparison::LessOrEqual => left <= right,
+                Comparison::Greater => left > right,
+                Comparison::GreaterOrEqual => left >= right,
+                Comparison::Equal => left == right,
+                Comparison::NotEqual => left != right,
+            }
+        }
+    }
+
+    impl Filter {
+        /// The alternatives joined by ||, it binds less than &&
+        fn parser(input: &str) -> IResult<'_, Filter> {
+            map(
+                separated_list1(tag("||"), Self::all_of),
+                |mut filters| match filters.len() {
+                    1 => filters.remove(0),
+                    _ => Filter::Or(filters),
+                },
+            )(input)
+        }
+
+        fn all_of(input: &str) -> IResult<'_, Filter> {
+            map(
+                separated_list1(tag("&&"), Self::term),
+                |mut filters| match filters.len() {
+                    1 => filters.remove(0),
+                    _ => Filter::And(filters),
+                },
+            )(input)
+        }
+
+        /// A comparison, a negation or a filter in parentheses, with the spaces around it
+        fn term(input: &str) -> IResult<'_, Filter> {
+            delimited(
+                space0,
+                alt((
+                    map(preceded(char('!'), Self::term), |filter| {
+                        Filter::Not(Box::new(filter))
+                    }),
+                    delimited(char('('), Self::parser, char(')')),
+                    map(
+                        tuple((
+                            Stat::parser,
+                            delimited(space0, Comparison::parser, space0),
+                            parsers::integer,
+                        )),
+                        |(stat, comparison, number)| Filter::Compare(stat, comparison, number),
+                    ),
+                )),
+                space0,
+            )(input)
+        }
+
+        fn matches(&self, game: &Game, palette: &Palette) -> Result<bool> {
+            match self {
+                Filter::Compare(stat, comparison, number) => {
+                    Ok(comparison.holds(stat.of(game, palette)?, *number))
+                }
+                Filter::Not(filter) => Ok(!filter.matches(game, palette)?),
+                Filter::And(filters) => {
+                    for filter in filters {
+                        if !filter.matches(game, palette)? {
+                            return Ok(false);
+                        }
+                    }
+                    Ok(true)
+                }
+                Filter::Or(filters) => {
+                    for filter in filters {
+                        if filter.matches(game, palette)? {
+                            return Ok(true);
+                        }
+                    }
+                    Ok(false)
+                }
+            }
+        }
+    }
+
+    /// The games matching a filter expression for --filter, one per line with their stats
+    /// ex: max(red)>10 && sets>=3, or !(power<100) || id==2
+    pub fn filter(input: &str, expression: &str) -> Result<String> {
+        let filter = parsers::parse_all(Filter::parser, expression)
+            .map_err(|e| Error::new(format!("Invalid filter {:?}: {}", expression, e)))?;
+        log::debug!("Filter {:?}", filter);
+        let Games { palette, games } = parse_games(input)?;
+        let mut rows = Vec::new();
+        for game in &games {
+            if filter.matches(game, &palette)? {
+                rows.push(format!(
+                    "Game {}: {} sets, larger set: {}, power: {}",
+                    game.id,
+                    game.sets.len(),
+                    palette.describe(&game.get_larger_set()),
+                    game.get_game_power(&palette)?
+                ));
+            }
+        }
+        rows.push(format!("{} games out of {} match", rows.len(), games.len()));
+        Ok(rows.join("\n"))
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let named_games: Vec<NamedGame> = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        let games = Games::new(named_games)?;
+        match part {
+            Part::Part1 => solve_part1_parsed(&games),
+            Part::Part2 => solve_part2_parsed(&games),
+        }
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        let games = parse_games(input)?;
+        solve_part1_parsed(&games)
+    }
+
+    fn solve_part1_parsed(games: &Games) -> Result<Answer> {
+        let elf_inventory = elf_inventory();
+        let mut sum_of_valids_game_ids = 0;
+
+        for game in &games.games {
+            let valid = game.is_game_valid(&elf_inventory, &games.palette);
+            if valid {
+                sum_of_valids_game_ids = add_id(sum_of_valids_game_ids, game)?;
+                log::debug!("Game {} is valid", game.id);
+            } else {
+                log::debug!("Game {} is invalid", game.id);
+            }
+            trace::emit(
+                "game",
+                || serde_json::json!({ "id": game.id, "valid": valid }),
+            );
+        }
+
+        log::debug!(
+            "The sum of the valid game ids is {}",
+            sum_of_valids_game_ids
+        );
+        Ok(Answer::from(sum_of_valids_game_ids))
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        let games = parse_games(input)?;
+        solve_part2_parsed(&games)
+    }
+
+    /// The sum of the powers with one more game, an error instead of wrapping around
+    fn add_id(sum_of_valids_game_ids: i32, game: &Game) -> Result<i32> {
+        sum_of_valids_game_ids.checked_add(game.id).ok_or_else(|| {
+            Error::new(format!(
+                "The sum of the valid game ids overflows at game {}",
+                game.id
+            ))
+        })
+    }
+
+    fn add_power(sum_of_the_sets_power: u64, power: u64, game: &Game) -> Result<u64> {
+        sum_of_the_sets_power.checked_add(power).ok_or_else(|| {
+            Error::new(format!(
+                "The sum of the powers overflows at game {} of power {}",
+                game.id, power
+            ))
+        })
+    }
+
+    fn solve_part2_parsed(games: &Games) -> Result<Answer> {
+        let mut sum_of_the_sets_power: u64 = 0;
+
+        for game in &games.games {
+            let current_game_power = game.get_game_power(&games.palette)?;
+            trace::emit(
+                "game",
+                || serde_json::json!({ "id": game.id, "power": current_game_power }),
+            );
+            sum_of_the_sets_power = add_power(sum_of_the_sets_power, current_game_power, game)?;
+        }
+
+        log::debug!("The sum of the sets power is {}", sum_of_the_sets_power);
+        Ok(Answer::from(sum_of_the_sets_power))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        /// A single game with the palette of its colors
+        fn parse_game(line: &str) -> (Game, Palette) {
+            let Games { palette, mut games } = parse_games(line).unwrap();
+            (games.remove(0), palette)
+        }
+
+        #[test]
+        fn test_is_game_valid() {
+            aocstd::init_tests();
+
+            let elf_inventory = elf_inventory();
+
+            let (game1, palette) =
+                parse_game("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green");
+            assert!(game1.is_game_valid(&elf_inventory, &palette));
+
+            let (game3, palette) = parse_game(
+                "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+            );
+            assert!(!game3.is_game_valid(&elf_inventory, &palette));
+        }
+
+        #[test]
+        fn test_get_game_power() {
+            aocstd::init_tests();
+
+            let (game1, palette) =
+                parse_game("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green");
+            assert_eq!(48, game1.get_game_power(&palette).unwrap());
+
+            let (game3, palette) = parse_game(
+                "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+            );
+            assert_eq!(1560, game3.get_game_power(&palette).unwrap());
+        }
+
+        #[test]
+        fn test_parse_error() {
+            aocstd::init_tests();
+
+            let error = parse_games("Game 1: 3 blue\nGame 2: 4 red, 2\n")
+                .err()
+                .unwrap()
+                .to_string();
+            assert!(error.contains("line 2, column 14"), "{}", error);
+            assert!(error.ends_with("in game 2, set \"4 red, 2\""), "{}", error);
+
+            let error = parse_games("Game 5: 1 red; 2 blue;\n")
+                .err()
+                .unwrap()
+                .to_string();
+            assert!(error.ends_with("in game 5, set \"\""), "{}", error);
+            // Without an id there is only the position
+            let error = parse_games("Game x: 1 red").err().unwrap().to_string();
+            assert!(error.ends_with("\"x: 1 red\""), "{}", error);
+        }
+
+        #[test]
+        fn test_unseen_colors() {
+            aocstd::init_tests();
+
+            let games = parse_games("Game 7: 3 purple, 2 red; 4 purple, 1 teal; 1 purple").unwrap();
+            let (game, palette) = (&games.games[0], &games.palette);
+            let larger_set = game.get_larger_set();
+            let purple = palette.find("purple").unwrap();
+            assert_eq!(larger_set.get(purple), 4);
+            assert_eq!(larger_set.get(CubeColor::RED), 2);
+            assert_eq!(larger_set.get(CubeColor::BLUE), 0);
+            assert_eq!(game.get_game_power(palette).unwrap(), 8);
+            assert!(!game.is_game_valid(&elf_inventory(), palette));
+            assert_eq!(palette.find("teal"), Some(CubeColor(4)));
+            assert_eq!(palette.name(CubeColor(4)), "teal");
+            assert_eq!(palette.find("black"), None);
+            // Every parse numbers its own colors
+            let other = parse_games("Game 1: 1 teal").unwrap();
+            assert_eq!(other.palette.find("teal"), Some(CubeColor(3)));
+
+            let json = serde_json::to_string(&games.named()).unwrap();
+            assert!(json.contains("{\"red\":2,\"purple\":3}"), "{}", json);
+            assert_eq!(solve_json(&json, Part::Part2).unwrap(), Answer::from(8u64));
+        }
+
+        #[test]
+        fn test_minimum_inventory() {
+            aocstd::init_tests();
+
+            let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green\n\
+                         Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue\n\
+                         Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red";
+            let games = parse_games(input).unwrap();
+            let minimum_inventory = minimum_inventory(&games.games);
+            assert_eq!(
+                games.palette.describe(&minimum_inventory),
+                "20 red, 13 green, 6 blue"
+            );
+            assert!(games
+                .games
+                .iter()
+                .all(|game| game.is_game_valid(&minimum_inventory, &games.palette)));
+            assert_eq!(
+                solve_with_report(input, Part::Part2).unwrap(),
+                solve_part2(input).unwrap()
+            );
+        }
+
+        #[test]
+        fn test_huge_counts() {
+            aocstd::init_tests();
+
+            // 2^32 - 1 times 2^32 + 1 is the largest power there is
+            let largest = "Game 1: 4294967295 red; 4294967297 green, 1 blue";
+            assert_eq!(solve_part2(largest).unwrap(), Answer::from(u64::MAX));
+            let error = solve_part2("Game 9: 4294967296 red, 4294967296 green")
+                .unwrap_err()
+                .to_string();
+            assert!(
+                error.starts_with("The power of game 9 overflows"),
+                "{}",
+                error
+            );
+            let error = solve_part2(&format!("{}\nGame 2: 1 red", largest))
+                .unwrap_err()
+                .to_string();
+            assert!(error.contains("at game 2"), "{}", error);
+
+            let (game, palette) =
+                parse_game("Game 3: 18446744073709551615 red, 1 green; 18446744073709551615 teal");
+            let larger_set = game.get_larger_set();
+            assert_eq!(larger_set.get(CubeColor::RED), u64::MAX);
+            assert_eq!(larger_set.get(palette.find("teal").unwrap()), u64::MAX);
+            assert_eq!(
+                palette.describe(&larger_set),
+                "18446744073709551615 red, 1 green, 18446744073709551615 teal"
+            );
+
+            // Nor does the sum of the valid game ids
+            let error = solve_part1("Game 2147483647: 1 red\nGame 1: 1 blue")
+                .unwrap_err()
+                .to_string();
+            assert!(error.contains("at game 1"), "{}", error);
+            assert!(solve_part1("Game 2147483647: 1 red\nGame -1: 1 blue").is_ok());
+
+            // The counts do not wrap around either
+            let teal = palette.find("teal").unwrap();
+            for cube_color in [CubeColor::RED, teal] {
+                let mut inventory = larger_set.clone();
+                assert_eq!(inventory.add(cube_color, 0), Some(u64::MAX));
+                assert_eq!(inventory.add(cube_color, 1), None);
+            }
+        }
+
+        #[test]
+        fn test_repeated_colors() {
+            aocstd::init_tests();
+
+            for (input, color) in [
+                ("Game 4: 1 red; 2 blue, 3 red, 2 blue", "blue"),
+                ("Game 4: 1 red; 2 teal, 3 red, 2 teal", "teal"),
+            ] {
+                let error = parse_games(input).err().unwrap().to_string();
+                assert_eq!(
+                    error,
+                    format!("{} is played twice in set 2 of game 4", color)
+                );
+            }
+            // Only within a set
+            assert!(parse_games("Game 4: 1 red; 2 red").is_ok());
+        }
+
+        #[test]
+        fn test_filter() {
+            aocstd::init_tests();
+
+            let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green\n\
+                         Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue\n\
+                         Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red\n\
+                         Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red\n\
+                         Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+            let games = parse_games(input).unwrap();
+            let ids = |expression| {
+                let filter = parsers::parse_all(Filter::parser, expression).unwrap();
+                games
+                    .games
+                    .iter()
+                    .filter(|game| filter.matches(game, &games.palette).unwrap())
+                    .map(|game| game.id)
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(ids("max(red)>10 && sets>=3"), vec![3, 4]);
+            assert_eq!(ids("!(power < 100) || id == 2"), vec![2, 3, 4]);
+            assert_eq!(ids("min(green)>=1"), vec![2, 3, 4, 5]);
+            assert_eq!(ids("sum(blue)==9 || max(purple)>0"), vec![1]);
+            assert_eq!(ids("sets<3 && (power!=36 || id<=5)"), vec![5]);
+
+            let filtered = filter(input, "max( red ) > 10&&sets>=3").unwrap();
+            assert!(filtered.starts_with("Game 3: 3 sets, larger set: 20 red, 13 green, 6 blue"));
+            assert!(filtered.ends_with("2 games out of 5 match"), "{}", filtered);
+            assert!(filter(input, "max(red) >").is_err());
+            assert!(filter(input, "color(red) > 1").is_err());
+            assert!(filter(input, "max > 1").is_err());
+        }
+    }
+}
@@ -0,0 +1,675 @@
+pub mod gear_ratios {
+
+    use aocstd::geometry::{Direction, Point};
+    use aocstd::grid::Grid;
+    use aocstd::repl::{self, Queryable};
+    use aocstd::viz::{self, Color, Frame, Style};
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+    enum SchematicPart {
+        Nothing,
+        Symbol(char),
+        PartialPartId(char),
+    }
+
+    #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+    struct PartId {
+        id: u32,
+        position: Point,
+        length: u32,
+    }
+
+    /// Used to return more informations about the symbols when checking if a part id is next to a symbol
+    /// This is used to determine if a symbol is a gear
+    #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+    struct SymbolInformations {
+        symbol: char,
+        position: Point,
+    }
+
+    /// The rows shorter than the longest one are padded with nothing
+    #[derive(Serialize, Deserialize)]
+    #[serde(transparent)]
+    struct Schematic {
+        grid: Grid<SchematicPart>,
+    }
+
+    impl Schematic {
+        fn from_input(input: &str) -> Self {
+            let grid = Grid::from_lines(input, SchematicPart::Nothing, |c| {
+                if c == '.' {
+                    SchematicPart::Nothing
+                } else if c.is_ascii_digit() {
+                    SchematicPart::PartialPartId(c)
+                } else {
+                    SchematicPart::Symbol(c)
+                }
+            });
+            Schematic { grid }
+        }
+
+        /// The ids too large for a u32 are errors
+        fn identify_part_ids(&self) -> Result<Vec<PartId>> {
+            let mut part_ids = Vec::new();
+
+            for (y, row) in self.grid.rows().enumerate() {
+                let mut current_part_id: Option<PartId> = None;
+
+                for (x, part) in row.iter().enumerate() {
+                    if let SchematicPart::PartialPartId(c) = part {
+                        // The parsed form can have anything there
+                        let digit = c.to_digit(10).ok_or_else(|| {
+                            Error::new(format!("Invalid part id digit {:?} at {},{}", c, x, y))
+                        })?;
+                        match current_part_id {
+                            None => {
+                                current_part_id = Some(PartId {
+                                    id: digit,
+                                    position: Point::new(x as i64, y as i64),
+                                    length: 1,
+                                });
+                            }
+                            Some(id) => {
+                                let next_id = id
+                                    .id
+                                    .checked_mul(10)
+                                    .and_then(|next_id| next_id.checked_add(digit))
+                                    .ok_or_else(|| {
+                                        Error::new(format!(
+                                            "The part id at {} is too large",
+                                            id.position
+                                        ))
+                                    })?;
+                                current_part_id = Some(PartId {
+                                    id: next_id,
+                                    position: id.position,
+                                    length: id.length + 1,
+                                });
+                            }
+                        }
+                    } else {
+                        if let Some(id) = current_part_id {
+                            part_ids.push(id);
+                            current_part_id = None;
+                        }
+                    }
+                }
+                if let Some(id) = current_part_id {
+                    part_ids.push(id);
+                }
+            }
+            Ok(part_ids)
+        }
+
+        fn print(&self, log_level: log::Level) {
+            // Drawing the whole map is not free, only do it when it is logged
+            if !log::log_enabled!(log_level) {
+                return;
+            }
+            for row_str in self.draw(&HashMap::new()).lines() {
+                log::log!(log_level, "{}", row_str);
+            }
+        }
+
+        /// Draw the map, with the style of the cells found in `styles`
+        fn draw(&self, styles: &HashMap<Point, Style>) -> Frame {
+            let mut frame = Frame::new(self.grid.width(), self.grid.height());
+            for (position, part) in self.grid.iter() {
+                let c = match part {
+                    SchematicPart::Nothing => '.',
+                    SchematicPart::Symbol(c) => *c,
+                    SchematicPart::PartialPartId(c) => *c,
+                };
+                let style = styles.get(&position).copied().unwrap_or_default();
+                frame.set(position.x as usize, position.y as usize, c, style);
+            }
+            frame
+        }
+    }
+
+    impl PartId {
+        /// The cells of the id, from left to right
+        fn cells(&self) -> impl Iterator<Item = Point> + '_ {
+            (0..self.length as i64).map(|dx| self.position + Point::new(dx, 0))
+        }
+
+        fn scan_adjacent_symbols(&self, schematic: &Schematic) -> Vec<SymbolInformations> {
+            let mut symbols: Vec<SymbolInformations> = Vec::new();
+
+            for cell in self.cells() {
+                for direction in Direction::ALL {
+                    let position = cell + direction;
+                    // The cells around the middle digits are shared with their neighbors
+                    if symbols.iter().any(|symbol| symbol.position == position) {
+                        continue;
+                    }
+                    if let Some(SchematicPart::Symbol(c)) = schematic.grid.get(position) {
+                        symbols.push(SymbolInformations {
+                            symbol: *c,
+                            position,
+                        });
+                    }
+                }
+            }
+            symbols
+        }
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let schematic = Schematic::from_input(input);
+        Ok(format!(
+            "{}x{} schematic, {} part ids",
+            schematic.grid.width(),
+            schematic.grid.height(),
+            schematic.identify_part_ids()?.len()
+        ))
+    }
+
+    /// Draw the schematic with colors: the part ids next to a symbol are green, the isolated
+    /// ones dim and the gears highlighted
+    fn draw_highlighted(input: &str) -> Result<Frame> {
+        let schematic = Schematic::from_input(input);
+        let part_ids = schematic.identify_part_ids()?;
+
+        let mut styles: HashMap<Point, Style> = HashMap::new();
+        for part_id in &part_ids {
+            let style = if part_id.scan_adjacent_symbols(&schematic).is_empty() {
+                Style::fg(Color::GRAY)
+            } else {
+                Style::fg(Color::GREEN)
+            };
+            for cell in part_id.cells() {
+                styles.insert(cell, style);
+            }
+        }
+        for (gear, _) in find_gears(&schematic, &part_ids) {
+            styles.insert(gear.position, Style::fg(Color::YELLOW).bold());
+        }
+        Ok(schematic.draw(&styles))
+    }
+
+    pub fn visualize(input: &str, _part: Part) -> Result<String> {
+        viz::terminal_string(&[draw_highlighted(input)?])
+    }
+
+    pub fn frames(input: &str, _part: Part) -> Result<Vec<Frame>> {
+        Ok(vec![draw_highlighted(input)?])
+    }
+
+    pub fn render_svg(input: &str, _part: Part) -> Result<String> {
+        viz::svg_string(&[draw_highlighted(input)?])
+    }
+
+    fn describe_symbols(symbols: &[SymbolInformations]) -> String {
+        let symbols: Vec<String> = symbols
+            .iter()
+            .map(|symbol| format!("'{}' at {}", symbol.symbol, symbol.position))
+            .collect();
+        if symbols.is_empty() {
+            String::from("none")
+        } else {
+            symbols.join(", ")
+        }
+    }
+
+    const HTML_HEADER: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Day 3: Gear Ratios</title>
+<style>
+body { background: #1e1e1e; color: #666; }
+pre { font-size: 14px; line-height: 1.2; }
+.part { color: #50c850; cursor: help; }
+.part.isolated { color: #888; }
+.symbol { color: #ddd; cursor: help; }
+.gear { color: #e6c83c; font-weight: bold; }
+.part:hover, .symbol:hover, .linked { background: #444; color: #fff; }
+</style>
+</head>
+<body>
+<pre>
+"#;
+
+    const HTML_FOOTER: &str = r#"</pre>
+<script>
+for (const cell of document.querySelectorAll("[data-links]")) {
+  const linked = cell.dataset.links.split(" ").filter((id) => id).map((id) => document.getElementById(id));
+  cell.addEventListener("mouseenter", () => linked.forEach((other) => other.classList.add("linked")));
+  cell.addEventListener("mouseleave", () => linked.forEach((other) => other.classList.remove("linked")));
+}
+</script>
+</body>
+</html>
+"#;
+
+    fn escape_html(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    fn html_id(position: &Point) -> String {
+        format!("c{}-{}", position.x, position.y)
+    }
+
+    /// A standalone HTML page of the schematic: hovering a part id shows its adjacent symbols
+    /// and hovering a symbol shows its adjacent part ids, and the ratio for a gear
+    pub fn export_html(input: &str, _part: Part) -> Result<String> {
+        let schematic = Schematic::from_input(input);
+        let part_ids = schematic.identify_part_ids()?;
+
+        let mut part_ids_at: HashMap<Point, (&PartId, Vec<SymbolInformations>)> = HashMap::new();
+        let mut part_ids_of_symbol: HashMap<Point, Vec<&PartId>> = HashMap::new();
+        for part_id in &part_ids {
+            let symbols = part_id.scan_adjacent_symbols(&schematic);
+            for symbol in &symbols {
+                part_ids_of_symbol
+                    .entry(symbol.position)
+                    .or_default()
+                    .push(part_id);
+            }
+            part_ids_at.insert(part_id.position, (part_id, symbols));
+        }
+
+        let mut html = String::from(HTML_HEADER);
+        for (y, row) in schematic.grid.rows().enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                let position = Point::new(x as i64, y as i64);
+                if let Some((part_id, symbols)) = part_ids_at.get(&position) {
+                    // The digits are taken from the map to keep the leading zeros
+                    let digits: String = row[x..x + part_id.length as usize]
+                        .iter()
+                        .map(|part| match part {
+                            SchematicPart::PartialPartId(c) => *c,
+                            _ => '?',
+                        })
+                        .collect();
+                    let class = if symbols.is_empty() {
+                        "part isolated"
+                    } else {
+                        "part"
+                    };
+                    let links: Vec<String> = symbols
+                        .iter()
+                        .map(|symbol| html_id(&symbol.position))
+                        .collect();
+                    let title = format!(
+                        "{}, adjacent symbols: {}",
+                        part_id.id,
+                        describe_symbols(symbols)
+                    );
+                    html.push_str(&format!(
+                        r#"<span id="{}" class="{}" data-links="{}" title="{}">{}</span>"#,
+                        html_id(&position),
+                        class,
+                        links.join(" "),
+                        escape_html(&title),
+                        digits
+                    ));
+                    x += part_id.length as usize;
+                    continue;
+                }
+                match row[x] {
+                    SchematicPart::Nothing => html.push('.'),
+                    SchematicPart::PartialPartId(c) => html.push(c),
+                    SchematicPart::Symbol(c) => {
+                        let adjacent_parts = part_ids_of_symbol
+                            .get(&position)
+                            .map(Vec::as_slice)
+                            .unwrap_or_default();
+                        let (class, title) = match adjacent_parts {
+                            [a, b] if c == '*' => (
+                                "symbol gear",
+                                format!(
+                                    "gear: {} * {} = {}",
+                                    a.id,
+                                    b.id,
+                                    a.id as u64 * b.id as u64
+                                ),
+                            ),
+                            _ => {
+                                let ids: Vec<String> = adjacent_parts
+                                    .iter()
+                                    .map(|part_id| part_id.id.to_string())
+                                    .collect();
+                                (
+                                    "symbol",
+                                    format!("'{}', adjacent parts: [{}]", c, ids.join(", ")),
+                                )
+                            }
+                        };
+                        let links: Vec<String> = adjacent_parts
+                            .iter()
+                            .map(|part_id| html_id(&part_id.position))
+                            .collect();
+                        html.push_str(&format!(
+                            r#"<span id="{}" class="{}" data-links="{}" title="{}">{}</span>"#,
+                            html_id(&position),
+                            class,
+                            links.join(" "),
+                            escape_html(&title),
+                            escape_html(&c.to_string())
+                        ));
+                    }
+                }
+                x += 1;
+            }
+            html.push('\n');
+        }
+        html.push_str(HTML_FOOTER);
+        Ok(html)
+    }
+
+    /// The structured form of the input for --dump-parsed, when read back with solve_json the
+    /// part ids are used as they are instead of being identified again from the map
+    #[derive(Serialize, Deserialize)]
+    struct ParsedSchematic {
+        schematic: Schematic,
+        part_ids: Vec<PartId>,
+    }
+
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        let schematic = Schematic::from_input(input);
+        let part_ids = schematic.identify_part_ids()?;
+        Ok(ParsedSchematic {
+            schematic,
+            part_ids,
+        })
+    }
+
+    struct SchematicRepl {
+        schematic: Schematic,
+        part_ids: Vec<PartId>,
+    }
+
+    impl Queryable for SchematicRepl {
+        fn help(&self) -> Vec<String> {
+            vec![
+                String::from("part <id>: position and adjacent symbols of every part with this id"),
+                String::from("at <x> <y>: content of a cell, and the parts around it for a '*'"),
+            ]
+        }
+
+        fn query(&self, command: &str, args: &[&str]) -> Result<String> {
+            match command {
+                "part" => {
+                    let id: u32 = repl::arg(args, 0, "id")?;
+                    let parts: Vec<String> = self
+                        .part_ids
+                        .iter()
+                        .filter(|part_id| part_id.id == id)
+                        .map(|part_id| {
+                            format!(
+                                "{} at {}, length {}, adjacent symbols: {}",
+                                part_id.id,
+                                part_id.position,
+                                part_id.length,
+                                describe_symbols(&part_id.scan_adjacent_symbols(&self.schematic))
+                            )
+                        })
+                        .collect();
+                    if parts.is_empty() {
+                        return Err(Error::new(format!("No part {}", id)));
+                    }
+                    Ok(parts.join("\n"))
+                }
+                "at" => {
+                    let position = Point::new(repl::arg(args, 0, "x")?, repl::arg(args, 1, "y")?);
+                    let cell = self
+                        .schematic
+                        .grid
+                        .get(position)
+                        .ok_or_else(|| Error::new(format!("{} is out of the map", position)))?;
+                    match *cell {
+                        SchematicPart::Nothing => Ok(String::from("nothing")),
+                        SchematicPart::PartialPartId(c) => Ok(format!("digit {}", c)),
+                        SchematicPart::Symbol(c) => {
+                            let symbol = SymbolInformations {
+                                symbol: c,
+                                position,
+                            };
+                            let adjacent_parts: Vec<String> = self
+                                .part_ids
+                                .iter()
+                                .filter(|part_id| {
+                                    part_id
+                                        .scan_adjacent_symbols(&self.schematic)
+                                        .contains(&symbol)
+                                })
+                                .map(|part_id| part_id.id.to_string())
+                                .collect();
+                            let gear = if c == '*' && adjacent_parts.len() == 2 {
+                                " (gear)"
+                            } else {
+                                ""
+                            };
+                            Ok(format!(
+                                "symbol '{}'{}, adjacent parts: [{}]",
+                                c,
+                                gear,
+                                adjacent_parts.join(", ")
+                            ))
+                        }
+                    }
+                }
+                _ => Err(repl::unknown_command(command)),
+            }
+        }
+    }
+
+    /// Queries over the schematic and the part ids found in it, the same for both parts
+    pub fn repl(input: &str, _part: Part) -> Result<Box<dyn Queryable>> {
+        let schematic = Schematic::from_input(input);
+        let part_ids = schematic.identify_part_ids()?;
+        Ok(Box::new(SchematicRepl {
+            schematic,
+            part_ids,
+        }))
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let parsed: ParsedSchematic = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        parsed.schematic.grid.check_size()?;
+        match part {
+            Part::Part1 => solve_part1_parsed(&parsed.schematic, parsed.part_ids),
+            Part::Part2 => solve_part2_parsed(&parsed.schematic, parsed.part_ids),
+        }
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        let schematic = Schematic::from_input(input);
+        let part_ids = schematic.identify_part_ids()?;
+        solve_part1_parsed(&schematic, part_ids)
+    }
+
+    fn solve_part1_parsed(schematic: &Schematic, part_ids: Vec<PartId>) -> Result<Answer> {
+        log::debug!("Schematic:");
+        schematic.print(log::Level::Debug);
+        log::debug!("Part ids: {:?}", part_ids);
+
+        // check witch part ids are next to a symbol and build the sum of the part_ids
+        let mut sum: u64 = 0;
+        for part_id in part_ids {
+            let next_to_a_symbol = !part_id.scan_adjacent_symbols(schematic).is_empty();
+            if next_to_a_symbol {
+                log::debug!("Part id {} is next to a symbol", part_id.id);
+                sum += part_id.id as u64;
+            }
+            trace::emit("part", || {
+                serde_json::json!({
+                    "id": part_id.id,
+                    "position": part_id.position,
+                    "next_to_a_symbol": next_to_a_symbol,
+                })
+            });
+        }
+        log::debug!("Sum of part ids: {}", sum);
+        Ok(Answer::from(sum))
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        let schematic = Schematic::from_input(input);
+        let part_ids = schematic.identify_part_ids()?;
+        solve_part2_parsed(&schematic, part_ids)
+    }
+
+    /// A gear is a '*' next to exactly two part ids
+    fn find_gears(
+        schematic: &Schematic,
+        part_ids: &[PartId],
+    ) -> Vec<(SymbolInformations, Vec<PartId>)> {
+        let mut potential_gears: HashMap<SymbolInformations, Vec<PartId>> = HashMap::new();
+
+        // find all the adjacent symbols for each part id in order to find the gears
+        for part_id in part_ids {
+            let adjacent_symbols = part_id.scan_adjacent_symbols(schematic);
+            for symbol in adjacent_symbols {
+                // The gear always has a '*' symbol
+                if symbol.symbol == '*' {
+                    if let Some(part_ids) = potential_gears.get_mut(&symbol) {
+                        part_ids.push(part_id.clone());
+                    } else {
+                        potential_gears.insert(symbol, vec![part_id.clone()]);
+                    }
+                }
+            }
+        }
+
+        potential_gears
+            .into_iter()
+            .filter(|(_, part_ids)| part_ids.len() == 2)
+            .collect()
+    }
+
+    fn solve_part2_parsed(schematic: &Schematic, part_ids: Vec<PartId>) -> Result<Answer> {
+        log::debug!("Schematic:");
+        schematic.print(log::Level::Debug);
+        log::debug!("Part ids: {:?}", part_ids);
+
+        let gears = find_gears(schematic, &part_ids);
+        for (symbol, part_ids) in &gears {
+            trace::emit("gear", || {
+                serde_json::json!({
+                    "position": symbol.position,
+                    "parts": [part_ids[0].id, part_ids[1].id],
+                })
+            });
+        }
+        let gear_ratios = gears.iter().try_fold(0u64, |sum, (symbol, part_ids)| {
+            sum.checked_add(part_ids[0].id as u64 * part_ids[1].id as u64)
+                .ok_or_else(|| {
+                    Error::new(format!(
+                        "The sum of the gear ratios overflows at the gear at {}",
+                        symbol.position
+                    ))
+                })
+        })?;
+
+        log::debug!("Gear ratios: {}", gear_ratios);
+        Ok(Answer::from(gear_ratios))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn identify_part_ids_and_scan_adjacent_symbols() {
+            aocstd::init_tests();
+
+            let input = "467..114..\n\
+                 ...*......\n\
+                 ..35..633.\n\
+                 ......#...";
+
+            let schematic = Schematic::from_input(input);
+            let part_ids = schematic.identify_part_ids().unwrap();
+            assert_eq!(
+                part_ids,
+                vec![
+                    PartId {
+                        id: 467,
+                        position: Point::new(0, 0),
+                        length: 3
+                    },
+                    PartId {
+                        id: 114,
+                        position: Point::new(5, 0),
+                        length: 3
+                    },
+                    PartId {
+                        id: 35,
+                        position: Point::new(2, 2),
+                        length: 2
+                    },
+                    PartId {
+                        id: 633,
+                        position: Point::new(6, 2),
+                        length: 3
+                    }
+                ]
+            );
+            let adjacent_symbols = part_ids[0].scan_adjacent_symbols(&schematic);
+            assert_eq!(
+                adjacent_symbols,
+                vec![SymbolInformations {
+                    symbol: '*',
+                    position: Point::new(3, 1)
+                },]
+            );
+            assert_eq!(part_ids[1].scan_adjacent_symbols(&schematic), vec![]);
+
+            // No gears is a sum of 0, too long an id is an error
+            assert_eq!(solve_part2("").unwrap(), Answer::from(0u64));
+            assert_eq!(solve_part2("467.\n...*").unwrap(), Answer::from(0u64));
+            assert!(solve_part1("1234567890123.").is_err());
+            assert!(solve_part2("1234567890123.").is_err());
+            assert_eq!(
+                solve_part1("4294967295*").unwrap(),
+                Answer::from(4294967295u64)
+            );
+
+            // Two of the largest gears do not fit in the sum
+            let largest_gear = "4294967295*4294967295";
+            assert_eq!(
+                solve_part2(largest_gear).unwrap(),
+                Answer::from(4294967295u64 * 4294967295u64)
+            );
+            let error = solve_part2(&format!("{}\n.\n{}", largest_gear, largest_gear))
+                .unwrap_err()
+                .to_string();
+            assert!(
+                error.starts_with("The sum of the gear ratios overflows"),
+                "{}",
+                error
+            );
+        }
+
+        #[test]
+        fn test_export_html() {
+            aocstd::init_tests();
+
+            let html = export_html(
+                "467..114..\n\
+                 ...*......\n\
+                 ..35..&33.",
+                Part::Part2,
+            )
+            .unwrap();
+            assert!(html.contains(
+                r#"<span id="c3-1" class="symbol gear" data-links="c0-0 c2-2" title="gear: 467 * 35 = 16345">*</span>"#
+            ));
+            assert!(html.contains(r#"class="part isolated" data-links="" title="114, adjacent symbols: none">114</span>"#));
+            assert!(html.contains(r#"title="'&amp;', adjacent parts: [33]">&amp;</span>"#));
+        }
+    }
+}
@@ -0,0 +1,538 @@
+pub mod scratchcards {
+    use aocstd::bitset::BitSet;
+    use aocstd::repl::{self, Queryable};
+    use aocstd::viz::{Color, Frame, Style};
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize};
+    use std::cell::OnceCell;
+    use std::collections::HashSet;
+
+    /// A card contains a set of winning numbers and a set of numbers represented by:
+    /// Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+    /// where the first 5 numbers are the winning numbers and the last 8 numbers are the numbers of the card
+    #[derive(Serialize, Deserialize)]
+    struct Card {
+        id: u32,
+        winning_numbers: Vec<u32>,
+        numbers: Vec<u32>,
+        /// Computed on the first use, both rulesets need it
+        #[serde(skip)]
+        nb_of_matching_numbers: OnceCell<u32>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct CardSet {
+        cards: Vec<Card>,
+    }
+
+    impl Card {
+        fn from_line(line: &str) -> Result<Self> {
+            log::debug!("Parsing line: {}", line);
+
+            // Split the line into the different parts:
+            // the header contains the card id:                       Card 1
+            // the winning numbers part contains the winning numbers: 41 48 83 86 17
+            // the numbers part contains the numbers of the card:     83 86  6 31 17  9 48 53
+            let header_line = aocstd::parse::header_line::<u32>(line, '|')?;
+            let [winning_numbers_part_of_the_line, numbers_part_of_the_line] =
+                header_line.segments[..]
+            else {
+                return Err(Error::new(format!(
+                    "Expected the winning numbers and the numbers separated by a '|' in \"{}\"",
+                    line
+                )));
+            };
+            log::debug!(
+                "found parts of the line: id=[{}], winning_numbers=[{}], numbers=[{}]",
+                header_line.id,
+                winning_numbers_part_of_the_line,
+                numbers_part_of_the_line
+            );
+
+            // Parse every parts into the corresponding data structure
+            let winning_numbers = aocstd::parse::ints::<u32>(winning_numbers_part_of_the_line)?;
+            let numbers = aocstd::parse::ints::<u32>(numbers_part_of_the_line)?;
+
+            // Return the Card
+            Ok(Card {
+                id: header_line.id,
+                winning_numbers,
+                numbers,
+                nb_of_matching_numbers: OnceCell::new(),
+            })
+        }
+
+        fn compute_nb_of_matching_numbers(&self) -> u32 {
+            *self
+                .nb_of_matching_numbers
+                .get_or_init(|| self.count_matching_numbers())
+        }
+
+        fn count_matching_numbers(&self) -> u32 {
+            let all_numbers = self.winning_numbers.iter().chain(&self.numbers);
+            // The numbers of the puzzle are below 100 so the winning numbers fit in a few bits,
+            // a set is only needed for the larger ones
+            let nb_of_matching_numbers = if all_numbers.clone().all(|number| *number < 1 << 16) {
+                let winning_numbers: BitSet = self
+                    .winning_numbers
+                    .iter()
+                    .map(|number| *number as usize)
+                    .collect();
+                self.numbers
+                    .iter()
+                    .filter(|number| winning_numbers.contains(**number as usize))
+                    .count()
+            } else {
+                let winning_numbers: HashSet<u32> = self.winning_numbers.iter().copied().collect();
+                self.numbers
+                    .iter()
+                    .filter(|number| winning_numbers.contains(number))
+                    .count()
+            };
+            log::debug!(
+                "Found {} winning numbers for card {}",
+                nb_of_matching_numbers,
+                self.id
+            );
+            nb_of_matching_numbers as u32
+        }
+
+        fn nb_of_points_won(&self) -> Result<u64> {
+            let nb_of_matching_numbers = self.compute_nb_of_matching_numbers();
+            if nb_of_matching_numbers > 0 {
+                2u64.checked_pow(nb_of_matching_numbers - 1).ok_or_else(|| {
+                    Error::new(format!("The points of card {} overflow u64", self.id))
+                })
+            } else {
+                Ok(0)
+            }
+        }
+    }
+
+    impl CardSet {
+        fn from_input(input: &str) -> Result<Self> {
+            let mut card_set = Vec::new();
+            for line in aocstd::input::lines(input) {
+                let card = Card::from_line(line)?;
+                card_set.push(card);
+            }
+            log::debug!("Found {} cards in CardSet", card_set.len());
+            Ok(CardSet { cards: card_set })
+        }
+
+        /// Returns the total number of points won by the card set
+        /// The ruleset 1 concerns the first part of the exercise when the individual cards win
+        /// points
+        fn nb_of_points_won_with_ruleset1(&self) -> Result<Answer> {
+            let mut nb_of_points_won: u64 = 0;
+            for card in self.cards.iter() {
+                let points = card.nb_of_points_won();
+                match points.map(|points| nb_of_points_won.checked_add(points)) {
+                    Ok(Some(sum)) => nb_of_points_won = sum,
+                    #[cfg(feature = "num-bigint")]
+                    _ => return Ok(self.big_nb_of_points_won()),
+                    #[cfg(not(feature = "num-bigint"))]
+                    Ok(None) => return Err(Error::new("The number of points won overflows u64")),
+                    #[cfg(not(feature = "num-bigint"))]
+                    Err(error) => return Err(error),
+                }
+            }
+            log::debug!("Found {} points won in CardSet", nb_of_points_won);
+            Ok(Answer::from(nb_of_points_won))
+        }
+
+        /// The points again without a bound, only once u64 overflowed since it allocates
+        #[cfg(feature = "num-bigint")]
+        fn big_nb_of_points_won(&self) -> Answer {
+            let nb_of_points_won: aocstd::BigUint = self
+                .cards
+                .iter()
+                .map(|card| match card.compute_nb_of_matching_numbers() {
+                    0 => aocstd::BigUint::from(0u8),
+                    n => aocstd::BigUint::from(1u8) << (n - 1),
+                })
+                .sum();
+            Answer::from(nb_of_points_won)
+        }
+
+        /// For the ruleset2 we need to compute the nb of card won.
+        /// each matching nb of one card give a subsequent card to the player in the following fashion:
+        /// Card 1: has 2 matching numbers, so the player wins one copy of the next 2 card (Card 2 and Card 3)
+        /// Card 2: has 1 matching number, so the player wins one copy of the next card (Card 3)
+        /// Card 3: has 1 matching number, because the player as 2 copies of Card 3, he wins two copy of the next card (Card 4)
+        /// Card 4: has 0 matching number, so game ends
+        fn nb_of_cards_won_with_ruleset2(&self) -> Result<Answer> {
+            // Compute the total number of cards won
+            let nb_of_cards_won = self.nb_of_copy_of_cards().and_then(|nb_of_copy_of_cards| {
+                let nb_of_cards_won = nb_of_copy_of_cards
+                    .iter()
+                    .try_fold(0u64, |sum, copies| sum.checked_add(*copies))
+                    .ok_or_else(|| Error::new("The number of cards won overflows u64"))?;
+                log::debug!(
+                    "Found {} cards won in CardSet, nb_of_copy_of_cards={:?}",
+                    nb_of_cards_won,
+                    nb_of_copy_of_cards
+                );
+                Ok(nb_of_cards_won)
+            });
+            match nb_of_cards_won {
+                Ok(nb_of_cards_won) => Ok(Answer::from(nb_of_cards_won)),
+                #[cfg(feature = "num-bigint")]
+                Err(_) => Ok(self.big_nb_of_cards_won()),
+                #[cfg(not(feature = "num-bigint"))]
+                Err(error) => Err(error),
+            }
+        }
+
+        /// The ruleset2 game again without a bound, only once u64 overflowed since it allocates
+        #[cfg(feature = "num-bigint")]
+        fn big_nb_of_cards_won(&self) -> Answer {
+            let mut nb_of_copy_of_cards = vec![aocstd::BigUint::from(1u8); self.cards.len()];
+            for (current_card_index, current_card) in self.cards.iter().enumerate() {
+                let nb_of_copy_of_current_card = nb_of_copy_of_cards[current_card_index].clone();
+                let last_card_index_won = (current_card_index
+                    + current_card.compute_nb_of_matching_numbers() as usize)
+                    .min(self.cards.len() - 1);
+                for copies in &mut nb_of_copy_of_cards[current_card_index + 1..=last_card_index_won]
+                {
+                    *copies += &nb_of_copy_of_current_card;
+                }
+            }
+            Answer::from(nb_of_copy_of_cards.into_iter().sum::<aocstd::BigUint>())
+        }
+
+        /// Number of copies of each card (by index) at the end of the ruleset2 game
+        fn nb_of_copy_of_cards(&self) -> Result<Vec<u64>> {
+            self.play_ruleset2(|_, _| {})
+        }
+
+        /// Play the ruleset2 game, calling `on_card_played` with the index of each card and the
+        /// number of copies of every card once it has been played
+        fn play_ruleset2(&self, mut on_card_played: impl FnMut(usize, &[u64])) -> Result<Vec<u64>> {
+            // We starts with one copy of each card in the input
+            let mut nb_of_copy_of_cards: Vec<u64> = vec![1; self.cards.len()];
+            for (current_card_index, current_card) in self.cards.iter().enumerate() {
+                let nb_of_copy_of_current_card = nb_of_copy_of_cards[current_card_index];
+                log::debug!(
+                    "Processing card {} with {} copies",
+                    current_card_index,
+                    nb_of_copy_of_current_card
+                );
+                let nb_of_matching_numbers = current_card.compute_nb_of_matching_numbers();
+                // All the copies of the current card have been won by the previous cards
+                trace::emit("card", || {
+                    serde_json::json!({
+                        "id": current_card.id,
+                        "matches": nb_of_matching_numbers,
+                        "copies": nb_of_copy_of_current_card,
+                    })
+                });
+                let mut cards_indexes_won = Vec::new();
+                // Compute the indexes of the cards won by the current card
+                for i in 0..nb_of_matching_numbers {
+                    let card_id_won = current_card_index + i as usize + 1;
+                    // Cards will never make you copy a card past the end of the table
+                    if card_id_won < self.cards.len() {
+                        cards_indexes_won.push(card_id_won);
+                    }
+                }
+                log::debug!(
+                    "Found {} cards won by card {}, indexes (NOT IDs!) are: {:?}",
+                    nb_of_matching_numbers,
+                    current_card.id,
+                    cards_indexes_won
+                );
+                // For each card won, we add the number of copy of the current card to the number of copy of the card won
+                for card_index_won in cards_indexes_won {
+                    nb_of_copy_of_cards[card_index_won] = nb_of_copy_of_cards[card_index_won]
+                        .checked_add(nb_of_copy_of_current_card)
+                        .ok_or_else(|| {
+                            Error::new(format!(
+                                "The copies of card {} overflow u64",
+                                self.cards[card_index_won].id
+                            ))
+                        })?;
+                }
+                on_card_played(current_card_index, &nb_of_copy_of_cards);
+            }
+            Ok(nb_of_copy_of_cards)
+        }
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let card_set = CardSet::from_input(input)?;
+        Ok(format!("{} cards", card_set.cards.len()))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        CardSet::from_input(input)
+    }
+
+    /// Horizontal bar of `value / max` of the width, with eighths of characters for precision
+    fn bar(value: f64, max: f64, width: usize) -> String {
+        const EIGHTHS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+        let eighths = if max > 0.0 {
+            (value / max * (width * 8) as f64).round() as usize
+        } else {
+            0
+        };
+        let mut bar = "█".repeat(eighths / 8);
+        if eighths % 8 != 0 {
+            bar.push(EIGHTHS[eighths % 8]);
+        }
+        bar
+    }
+
+    /// Bar chart of the number of copies of every card at the end of the ruleset2 game, to see
+    /// how the copies cascade through the deck
+    pub fn visualize(input: &str, _part: Part, log_scale: bool) -> Result<String> {
+        let card_set = CardSet::from_input(input)?;
+        let nb_of_copy_of_cards = card_set.nb_of_copy_of_cards()?;
+        let max = nb_of_copy_of_cards.iter().copied().max().unwrap_or(0);
+        Ok(copies_chart(&card_set, &nb_of_copy_of_cards, max, log_scale).join("\n"))
+    }
+
+    /// The cascade of the copies as an animation, one frame per card played with the card in
+    /// yellow and the cards it won in green
+    pub fn frames(input: &str, _part: Part, log_scale: bool) -> Result<Vec<Frame>> {
+        let card_set = CardSet::from_input(input)?;
+        // The scale of the last frame is used for every frame
+        let max = card_set
+            .nb_of_copy_of_cards()?
+            .into_iter()
+            .max()
+            .unwrap_or(0);
+        let mut frames = Vec::with_capacity(card_set.cards.len());
+        card_set.play_ruleset2(|card_index, nb_of_copy_of_cards| {
+            let chart = copies_chart(&card_set, nb_of_copy_of_cards, max, log_scale);
+            let mut frame = Frame::from_text(&chart.join("\n"));
+            let nb_of_cards_won = card_set.cards[card_index].compute_nb_of_matching_numbers();
+            for y in 0..frame.height() {
+                let style = if y == card_index {
+                    Style::fg(Color::YELLOW).bold()
+                } else if y > card_index && y <= card_index + nb_of_cards_won as usize {
+                    Style::fg(Color::GREEN)
+                } else {
+                    Style::fg(Color::BLUE)
+                };
+                for x in 0..frame.width() {
+                    frame.style(x, y, style);
+                }
+            }
+            frames.push(frame);
+        })?;
+        Ok(frames)
+    }
+
+    /// One line per card with a bar of its number of copies, scaled on `max`
+    fn copies_chart(
+        card_set: &CardSet,
+        nb_of_copy_of_cards: &[u64],
+        max: u64,
+        log_scale: bool,
+    ) -> Vec<String> {
+        const WIDTH: usize = 60;
+        // With the log scale a single copy is an empty bar
+        let scale = |copies: u64| match log_scale {
+            true => (copies as f64).log10(),
+            false => copies as f64,
+        };
+        let id_width = card_set
+            .cards
+            .last()
+            .map(|card| card.id.to_string().len())
+            .unwrap_or(1);
+        let mut lines = Vec::with_capacity(card_set.cards.len());
+        for (card, copies) in card_set.cards.iter().zip(nb_of_copy_of_cards) {
+            lines.push(format!(
+                "Card {:>id_width$} |{:<WIDTH$}| {}",
+                card.id,
+                bar(scale(*copies), scale(max), WIDTH),
+                copies,
+            ));
+        }
+        lines.push(format!(
+            "{} cards in total{}",
+            // Every number of copies fits in u64 so their sum fits in u128
+            nb_of_copy_of_cards
+                .iter()
+                .map(|copies| *copies as u128)
+                .sum::<u128>(),
+            if log_scale { " (log scale)" } else { "" }
+        ));
+        lines
+    }
+
+    struct CardRepl {
+        card_set: CardSet,
+        nb_of_copy_of_cards: Vec<u64>,
+    }
+
+    impl Queryable for CardRepl {
+        fn help(&self) -> Vec<String> {
+            vec![String::from(
+                "card <id>: matching numbers, points and copies won of a card",
+            )]
+        }
+
+        fn query(&self, command: &str, args: &[&str]) -> Result<String> {
+            match command {
+                "card" => {
+                    let id: u32 = repl::arg(args, 0, "id")?;
+                    let (index, card) = self
+                        .card_set
+                        .cards
+                        .iter()
+                        .enumerate()
+                        .find(|(_, card)| card.id == id)
+                        .ok_or_else(|| Error::new(format!("No card {}", id)))?;
+                    let matching_numbers: Vec<String> = card
+                        .numbers
+                        .iter()
+                        .filter(|number| card.winning_numbers.contains(number))
+                        .map(|number| number.to_string())
+                        .collect();
+                    Ok(format!(
+                        "matching numbers: [{}], points: {}, copies: {}",
+                        matching_numbers.join(", "),
+                        card.nb_of_points_won()?,
+                        self.nb_of_copy_of_cards[index]
+                    ))
+                }
+                _ => Err(repl::unknown_command(command)),
+            }
+        }
+    }
+
+    /// Queries over the cards, showing the results of both rulesets
+    pub fn repl(input: &str, _part: Part) -> Result<Box<dyn Queryable>> {
+        let card_set = CardSet::from_input(input)?;
+        let nb_of_copy_of_cards = card_set.nb_of_copy_of_cards()?;
+        Ok(Box::new(CardRepl {
+            card_set,
+            nb_of_copy_of_cards,
+        }))
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let card_set: CardSet = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        match part {
+            Part::Part1 => card_set.nb_of_points_won_with_ruleset1(),
+            Part::Part2 => card_set.nb_of_cards_won_with_ruleset2(),
+        }
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        let card_set = CardSet::from_input(input)?;
+        card_set.nb_of_points_won_with_ruleset1()
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        let card_set = CardSet::from_input(input)?;
+        card_set.nb_of_cards_won_with_ruleset2()
+    }
+
+    #[cfg(test)]
+    mod test {
+        use aocstd::Answer;
+
+        /// `nb_of_cards` cards each matching all of their `nb_of_matches` numbers
+        fn matching_cards(nb_of_cards: usize, nb_of_matches: usize) -> String {
+            let numbers: Vec<String> = (1..=nb_of_matches).map(|n| n.to_string()).collect();
+            let numbers = numbers.join(" ");
+            (1..=nb_of_cards)
+                .map(|id| format!("Card {}: {} | {}", id, numbers, numbers))
+                .collect::<Vec<String>>()
+                .join("\n")
+        }
+
+        #[test]
+        fn test_card() {
+            aocstd::init_tests();
+
+            let card =
+                super::Card::from_line("Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53").unwrap();
+            assert_eq!(card.id, 1);
+            assert_eq!(card.winning_numbers, vec![41, 48, 83, 86, 17]);
+            assert_eq!(card.numbers, vec![83, 86, 6, 31, 17, 9, 48, 53]);
+
+            let nb_of_matching_numbers = card.compute_nb_of_matching_numbers();
+            assert_eq!(nb_of_matching_numbers, 4);
+
+            let card = super::Card::from_line("Card 2: 41 1000 17 | 1000 6 17 41 9").unwrap();
+            assert_eq!(card.compute_nb_of_matching_numbers(), 3);
+
+            // A number beyond u32 is an error instead of a panic
+            let Err(error) = super::Card::from_line("Card 1: 99999999999 | 1") else {
+                panic!("99999999999 does not fit in u32");
+            };
+            assert!(error.to_string().contains("99999999999"), "{}", error);
+        }
+
+        #[test]
+        fn test_card_set() {
+            aocstd::init_tests();
+
+            let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53\n\
+                         Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19\n\
+                         Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1\n\
+                         Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83\n\
+                         Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36\n\
+                         Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+            let card_set = super::CardSet::from_input(input).unwrap();
+            // Test ruleset 1
+            let nb_of_points_won = card_set.nb_of_points_won_with_ruleset1().unwrap();
+            assert_eq!(nb_of_points_won, Answer::from(13u64));
+            // Test ruleset 2
+            let nb_of_cards_won = card_set.nb_of_cards_won_with_ruleset2().unwrap();
+            assert_eq!(nb_of_cards_won, Answer::from(30u64));
+            assert_eq!(
+                card_set.nb_of_copy_of_cards().unwrap(),
+                vec![1, 2, 4, 8, 14, 1]
+            );
+        }
+
+        #[test]
+        fn test_overflow() {
+            aocstd::init_tests();
+
+            // Over u32 but not over u64
+            assert_eq!(
+                super::solve_part1(&matching_cards(1, 40)).unwrap(),
+                Answer::from(1u64 << 39)
+            );
+            assert_eq!(
+                super::solve_part2(&matching_cards(39, 5)).unwrap(),
+                Answer::from(318931980409u64)
+            );
+
+            // 2^64 points for a single card, and the copies won by 80 cards going over u64
+            let points = super::solve_part1(&matching_cards(1, 65));
+            let cards = super::solve_part2(&matching_cards(80, 5));
+            #[cfg(not(feature = "num-bigint"))]
+            {
+                assert!(points.is_err());
+                assert!(cards.is_err());
+            }
+            #[cfg(feature = "num-bigint")]
+            {
+                assert_eq!(points.unwrap().to_string(), "18446744073709551616");
+                assert_eq!(cards.unwrap().to_string(), "346859262060802162889174");
+            }
+        }
+
+        #[test]
+        fn test_bar() {
+            aocstd::init_tests();
+
+            assert_eq!(super::bar(14.0, 14.0, 4), "████");
+            assert_eq!(super::bar(7.0, 14.0, 4), "██");
+            assert_eq!(super::bar(1.0, 14.0, 4), "▎");
+            assert_eq!(super::bar(0.0, 0.0, 4), "");
+        }
+    }
+}
@@ -0,0 +1,987 @@
+pub mod giveaseedafertilizer {
+    use aocstd::ranges::{IntervalMap, IntervalSet, Offset};
+    use aocstd::repl::{self, Queryable};
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use regex::Regex;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::ops::Range;
+    use std::sync::OnceLock;
+
+    /// The regexes are compiled once, on their first use
+    fn seeds_line_regex() -> &'static Regex {
+        static REGEX: OnceLock<Regex> = OnceLock::new();
+        REGEX.get_or_init(|| Regex::new(r"^seeds: \d+( \d+)*$").unwrap())
+    }
+
+    fn map_header_regex() -> &'static Regex {
+        static REGEX: OnceLock<Regex> = OnceLock::new();
+        REGEX.get_or_init(|| Regex::new(r"^(\w+)-to-(\w+) map:$").unwrap())
+    }
+
+    // The almanac contains a list of transofrmations to apply to the seeds
+    // they are represented by maps of the form:
+    //   seed-to-soil map:
+    //   50 98 2
+    //   52 50 48
+    // where each line is:
+    //   <destination category> <source start range> <source range>
+    // Every transformation is applied the same way and the almanac seems to be in order, so we are
+    // using that to build a generic vector of transformations to apply
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Almanac {
+        /// Merged when they overlap, the seeds are only looked up
+        seeds: IntervalSet<u64>,
+        transformation_maps: Vec<TransformationMap>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, Ord, PartialOrd, Serialize, Deserialize)]
+    struct SeedRange {
+        start: u64,
+        length: u64,
+    }
+
+    impl From<&Range<u64>> for SeedRange {
+        fn from(range: &Range<u64>) -> Self {
+            SeedRange {
+                start: range.start,
+                length: range.end - range.start,
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    enum SeedParsingMode {
+        OneSeed,
+        SeedRange,
+    }
+
+    /// The transformations are keyed by their source range, they are written as a plain list in
+    /// the parsed form
+    #[derive(Debug, Serialize, Deserialize)]
+    struct TransformationMap {
+        source: String,
+        destination: String,
+        #[serde(
+            serialize_with = "serialize_transformations",
+            deserialize_with = "deserialize_transformations"
+        )]
+        transformations: IntervalMap<u64, Transformation>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Transformation {
+        destination_category: u64,
+        source_start_range: u64,
+        source_range: u64,
+    }
+
+    /// Every map of the almanac composed into one function from the seeds to the locations:
+    /// sorted, non overlapping source ranges each sent to a destination start
+    #[derive(Debug)]
+    struct ComposedTransformation {
+        pieces: Vec<(SeedRange, u64)>,
+    }
+
+    impl Almanac {
+        fn from_input(input: &str, seed_parsing_mode: SeedParsingMode) -> Result<Self> {
+            // The input is made of blocks separated by blank lines
+            let mut sections = aocstd::input::sections(input);
+
+            // The first block is the list of seeds in the form:
+            // seeds: 79 14 55 13
+            let seeds_line = sections
+                .next()
+                .ok_or_else(|| Error::new("No seeds line found"))?;
+            // Do a quick check with a regex to make sure the line is well formed and avoid unecessary debugging
+            if !seeds_line_regex().is_match(seeds_line) {
+                return Err(Error::new(format!("Invalid seeds line: {}", seeds_line)));
+            }
+
+            let nb_from_seed_line = aocstd::parse::ints::<u64>(seeds_line)?;
+            let seeds = if seed_parsing_mode == SeedParsingMode::SeedRange {
+                // In seed range mode the first number represent the start of the range and the second the length
+                if !nb_from_seed_line.len().is_multiple_of(2) {
+                    return Err(Error::new(format!(
+                        "The seed ranges are not pairs of numbers: {}",
+                        seeds_line
+                    )));
+                }
+                let mut result_seeds = IntervalSet::new();
+                for pair in nb_from_seed_line.chunks(2) {
+                    let (start, length) = (pair[0], pair[1]);
+                    let end = start.checked_add(length).ok_or_else(|| {
+                        Error::new(format!("The seed range {} {} is too large", start, length))
+                    })?;
+                    // push the range of seeds
+                    result_seeds.insert(start..end);
+                }
+                result_seeds
+            } else {
+                // In one seed mode each number represent a seed with a 1 length
+                nb_from_seed_line
+                    .iter()
+                    .map(|s| {
+                        let end = s
+                            .checked_add(1)
+                            .ok_or_else(|| Error::new(format!("The seed {} is too large", s)))?;
+                        Ok(*s..end)
+                    })
+                    .collect::<Result<IntervalSet<u64>>>()?
+            };
+            log::debug!("Found seeds: {:?}", seeds);
+
+            // Each of the next blocks is a transformation map
+            let mut transformation_maps = Vec::new();
+            for section in sections {
+                let Some(transformation_map) = TransformationMap::parse(section)? else {
+                    break;
+                };
+                log::debug!("Found transformation map: {:?}", transformation_map);
+                transformation_maps.push(transformation_map);
+            }
+
+            Ok(Almanac {
+                seeds,
+                transformation_maps,
+            })
+        }
+
+        /// Compose the maps by pushing the whole range of values through them, splitting it on
+        /// the boundaries of the transformations of every map
+        fn compose(&self) -> Result<ComposedTransformation> {
+            let identity = SeedRange {
+                start: 0,
+                length: u64::MAX,
+            };
+            let mut pieces = vec![(identity, 0)];
+            for transformation_map in &self.transformation_maps {
+                let mut next_pieces = Vec::with_capacity(pieces.len());
+                for (source, destination_start) in pieces {
+                    let destination = SeedRange {
+                        start: destination_start,
+                        length: source.length,
+                    };
+                    for (piece, next_destination) in
+                        transformation_map.apply_to_range(destination)?
+                    {
+                        let piece_source = SeedRange {
+                            start: source.start + (piece.start - destination_start),
+                            length: piece.length,
+                        };
+                        next_pieces.push((piece_source, next_destination.start));
+                    }
+                }
+                pieces = next_pieces;
+            }
+            pieces.sort();
+            log::debug!("{} pieces in the composed transformation", pieces.len());
+            Ok(ComposedTransformation { pieces })
+        }
+
+        /// The lowest location of the seeds, every seed range is looked up in the composed
+        /// transformation
+        fn apply_transformations_and_keep_lower_result(&self) -> Result<u64> {
+            let composed = self.compose()?;
+            let mut lower_result: Option<u64> = None;
+            for seedrange in self.seeds.iter().map(SeedRange::from) {
+                let Some(lowest) = composed.lowest_in(seedrange) else {
+                    // An empty seed range
+                    continue;
+                };
+                trace::emit("seed_range", || {
+                    serde_json::json!({
+                        "start": seedrange.start,
+                        "length": seedrange.length,
+                        "lowest_location": lowest,
+                    })
+                });
+                log::debug!(
+                    "Seeds: {}..{}, lowest: {}",
+                    seedrange.start,
+                    seedrange.start + seedrange.length,
+                    lowest
+                );
+                if lower_result.is_none() || lowest < lower_result.unwrap() {
+                    lower_result = Some(lowest);
+                }
+            }
+            log::debug!("Lower result: {:?}", lower_result);
+            lower_result.ok_or_else(|| Error::new("The almanac has no seeds"))
+        }
+
+        /// Walk the locations upward from 0 and map each one back to its seed through the
+        /// inverse maps, the first one whose seed is one of the seeds is the answer
+        fn reverse_search(&self) -> Result<u64> {
+            let inverse_maps = self
+                .transformation_maps
+                .iter()
+                .rev()
+                .map(TransformationMap::inverse)
+                .collect::<Result<Vec<TransformationMap>>>()?;
+            for location in 0..u64::MAX {
+                let seed = inverse_maps
+                    .iter()
+                    .try_fold(location, |value, inverse_map| {
+                        inverse_map.apply_transformation(value)
+                    })?;
+                if self.is_seed(seed) {
+                    log::debug!("Location {} comes from seed {}", location, seed);
+                    return Ok(location);
+                }
+            }
+            Err(Error::new("No location comes from a seed"))
+        }
+
+        fn is_seed(&self, value: u64) -> bool {
+            self.seeds.contains(value)
+        }
+
+        /// The value of a seed in every category (ex: "seed 79 -> soil 81 -> fertilizer 81")
+        fn path(&self, seed: u64) -> Result<String> {
+            let first_category = self
+                .transformation_maps
+                .first()
+                .map(|transformation_map| transformation_map.source.as_str())
+                .unwrap_or("seed");
+            let mut steps = vec![format!("{} {}", first_category, seed)];
+            let mut value = seed;
+            for transformation_map in &self.transformation_maps {
+                value = transformation_map.apply_transformation(value)?;
+                steps.push(format!("{} {}", transformation_map.destination, value));
+            }
+            Ok(steps.join(" -> "))
+        }
+    }
+
+    impl ComposedTransformation {
+        /// Index of the first piece that ends after the value
+        fn first_piece_after(&self, value: u64) -> usize {
+            self.pieces
+                .partition_point(|(source, _)| source.start + source.length <= value)
+        }
+
+        fn apply(&self, value: u64) -> u64 {
+            match self.pieces.get(self.first_piece_after(value)) {
+                Some((source, destination_start)) if source.start <= value => {
+                    destination_start + (value - source.start)
+                }
+                _ => value,
+            }
+        }
+
+        /// The lowest location of a range of seeds, each piece is increasing so only the first
+        /// seed of the range in every piece is a candidate
+        fn lowest_in(&self, range: SeedRange) -> Option<u64> {
+            let end = range.start + range.length;
+            self.pieces[self.first_piece_after(range.start)..]
+                .iter()
+                .take_while(|(source, _)| source.start < end)
+                .map(|(source, destination_start)| {
+                    destination_start + (range.start.max(source.start) - source.start)
+                })
+                .min()
+        }
+    }
+
+    impl TransformationMap {
+        /// None when the section is not a map, the almanac stops there
+        fn parse(section: &str) -> Result<Option<Self>> {
+            let mut line_itr = aocstd::input::lines(section);
+            // Read the transformation map header (ex: "seed-to-soil map:")
+            let Some(header) = line_itr
+                .next()
+                .and_then(|header_line| map_header_regex().captures(header_line))
+            else {
+                return Ok(None);
+            };
+            let source = header[1].to_string();
+            let destination = header[2].to_string();
+            log::debug!("Found transformation map header: {}", &header[0]);
+            let transformations = line_itr
+                .map(Transformation::parse)
+                .collect::<Result<Vec<Transformation>>>()?;
+
+            TransformationMap::new(source, destination, transformations).map(Some)
+        }
+
+        /// This fails when two transformations overlap since a value would have two destinations
+        fn new(
+            source: String,
+            destination: String,
+            transformations: Vec<Transformation>,
+        ) -> Result<Self> {
+            for transformation in &transformations {
+                transformation.check()?;
+            }
+            let transformations = build_transformations(transformations).map_err(|error| {
+                Error::new(format!(
+                    "Overlapping transformations in the {}-to-{} map: {}",
+                    source, destination, error
+                ))
+            })?;
+            Ok(TransformationMap {
+                source,
+                destination,
+                transformations,
+            })
+        }
+
+        /// The map from the destination back to the source, the maps of the puzzle are one to
+        /// one so the values outside of every transformation are still kept as they are
+        fn inverse(&self) -> Result<TransformationMap> {
+            TransformationMap::new(
+                self.destination.clone(),
+                self.source.clone(),
+                self.transformations
+                    .values()
+                    .map(|transformation| Transformation {
+                        destination_category: transformation.source_start_range,
+                        source_start_range: transformation.destination_category,
+                        source_range: transformation.source_range,
+                    })
+                    .collect(),
+            )
+        }
+
+        /// An error when the value is sent out of u64
+        fn apply_transformation(&self, initial_value: u64) -> Result<u64> {
+            self.transformations
+                .map_value(initial_value)
+                .ok_or_else(|| self.out_of_range(initial_value..initial_value + 1))
+        }
+
+        fn out_of_range(&self, range: Range<u64>) -> Error {
+            Error::new(format!(
+                "The {}-to-{} map sends {}..{} out of u64",
+                self.source, self.destination, range.start, range.end
+            ))
+        }
+
+        /// The transformation that applies to a value, if any
+        fn find_transformation(&self, value: u64) -> Option<&Transformation> {
+            self.transformations
+                .get(value)
+                .map(|(_, transformation)| transformation)
+        }
+
+        /// Split a range of values on the boundaries of the transformations, returning each
+        /// piece of the range along with where it is sent (the values outside of every
+        /// transformation are kept as they are)
+        fn apply_to_range(&self, range: SeedRange) -> Result<Vec<(SeedRange, SeedRange)>> {
+            let range = range.start..range.start + range.length;
+            let pieces = self
+                .transformations
+                .map_range(range.clone())
+                .ok_or_else(|| self.out_of_range(range))?;
+            Ok(pieces
+                .iter()
+                .map(|(piece, destination)| (SeedRange::from(piece), SeedRange::from(destination)))
+                .collect())
+        }
+    }
+
+    fn build_transformations(
+        transformations: Vec<Transformation>,
+    ) -> Result<IntervalMap<u64, Transformation>> {
+        IntervalMap::from_entries(
+            transformations
+                .into_iter()
+                .map(|transformation| (transformation.source(), transformation)),
+        )
+    }
+
+    fn serialize_transformations<S: Serializer>(
+        transformations: &IntervalMap<u64, Transformation>,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(transformations.values())
+    }
+
+    /// The sizes and the overlaps are checked when reading the parsed form back too
+    fn deserialize_transformations<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<IntervalMap<u64, Transformation>, D::Error> {
+        let transformations = Vec::<Transformation>::deserialize(deserializer)?;
+        for transformation in &transformations {
+            transformation.check().map_err(serde::de::Error::custom)?;
+        }
+        build_transformations(transformations).map_err(serde::de::Error::custom)
+    }
+
+    impl Transformation {
+        fn parse(line: &str) -> Result<Self> {
+            // The transformation is a line of the form:
+            // 50 98 2
+            // where each number is:
+            // <destination category> <source start range> <source range>
+            let invalid = || Error::new(format!("Invalid transformation line: {}", line));
+            let numbers = line
+                .split_whitespace()
+                .map(|number| number.parse::<u64>().map_err(|_| invalid()))
+                .collect::<Result<Vec<u64>>>()?;
+            let [destination_category, source_start_range, source_range] = numbers[..] else {
+                return Err(invalid());
+            };
+            Ok(Transformation {
+                destination_category,
+                source_start_range,
+                source_range,
+            })
+        }
+
+        /// Both ranges end within u64 and the shift from one to the other fits in i64
+        fn check(&self) -> Result<()> {
+            let shift = self.destination_category as i128 - self.source_start_range as i128;
+            if self
+                .source_start_range
+                .checked_add(self.source_range)
+                .is_none()
+                || self
+                    .destination_category
+                    .checked_add(self.source_range)
+                    .is_none()
+                || i64::try_from(shift).is_err()
+            {
+                return Err(Error::new(format!(
+                    "The transformation {} {} {} is too large",
+                    self.destination_category, self.source_start_range, self.source_range
+                )));
+            }
+            Ok(())
+        }
+
+        fn source(&self) -> Range<u64> {
+            self.source_start_range..self.source_start_range + self.source_range
+        }
+    }
+
+    impl Offset for Transformation {
+        /// It fits, the transformations are checked when the maps are built
+        fn offset(&self) -> i64 {
+            (self.destination_category as i128 - self.source_start_range as i128) as i64
+        }
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let almanac = Almanac::from_input(input, SeedParsingMode::SeedRange)?;
+        Ok(format!(
+            "{} seed ranges, {} transformation maps",
+            almanac.seeds.len(),
+            almanac.transformation_maps.len()
+        ))
+    }
+
+    fn seed_parsing_mode(part: Part) -> SeedParsingMode {
+        match part {
+            Part::Part1 => SeedParsingMode::OneSeed,
+            Part::Part2 => SeedParsingMode::SeedRange,
+        }
+    }
+
+    /// Draw the almanac as an SVG: one axis per category, each transformation map as gray bands
+    /// from its source to its destination intervals, and the seed ranges flowing through every
+    /// map in color
+    pub fn render_svg(input: &str, part: Part) -> Result<String> {
+        const WIDTH_PER_MAP: f64 = 160.0;
+        const HEIGHT: f64 = 800.0;
+        const MARGIN: f64 = 40.0;
+
+        let almanac = Almanac::from_input(input, seed_parsing_mode(part))?;
+
+        // Every category shares the same vertical scale
+        let mut max_value = almanac.seeds.bounds().map_or(1, |bounds| bounds.end.max(1));
+        for transformation_map in &almanac.transformation_maps {
+            for transformation in transformation_map.transformations.values() {
+                max_value =
+                    max_value.max(transformation.source_start_range + transformation.source_range);
+                max_value = max_value
+                    .max(transformation.destination_category + transformation.source_range);
+            }
+        }
+        let x = |column: usize| MARGIN + column as f64 * WIDTH_PER_MAP;
+        let y = |value: u64| MARGIN + value as f64 / max_value as f64 * HEIGHT;
+        // The single seeds of part 1 would be invisible without a minimal thickness
+        let band = |from: usize, source: SeedRange, destination: SeedRange, style: &str| {
+            let thickness =
+                |range: SeedRange| (y(range.start + range.length) - y(range.start)).max(1.0);
+            format!(
+                "  <polygon points=\"{:.1},{:.1} {:.1},{:.1} {:.1},{:.1} {:.1},{:.1}\" {}/>\n",
+                x(from),
+                y(source.start),
+                x(from),
+                y(source.start) + thickness(source),
+                x(from + 1),
+                y(destination.start) + thickness(destination),
+                x(from + 1),
+                y(destination.start),
+                style
+            )
+        };
+
+        let nb_of_columns = almanac.transformation_maps.len() + 1;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\">\n",
+            x(nb_of_columns - 1) + MARGIN,
+            HEIGHT + 2.0 * MARGIN
+        );
+        for (index, transformation_map) in almanac.transformation_maps.iter().enumerate() {
+            for transformation in transformation_map.transformations.values() {
+                let source = SeedRange {
+                    start: transformation.source_start_range,
+                    length: transformation.source_range,
+                };
+                let destination = SeedRange {
+                    start: transformation.destination_category,
+                    length: transformation.source_range,
+                };
+                svg.push_str(&band(
+                    index,
+                    source,
+                    destination,
+                    "fill=\"#999\" fill-opacity=\"0.3\"",
+                ));
+            }
+        }
+        for (seed_index, seed_range) in almanac.seeds.iter().enumerate() {
+            let style = format!(
+                "fill=\"hsl({}, 80%, 45%)\" fill-opacity=\"0.7\"",
+                seed_index * 360 / almanac.seeds.len()
+            );
+            let mut ranges = vec![SeedRange::from(seed_range)];
+            for (index, transformation_map) in almanac.transformation_maps.iter().enumerate() {
+                let mut next_ranges = Vec::new();
+                for range in ranges {
+                    for (source, destination) in transformation_map.apply_to_range(range)? {
+                        svg.push_str(&band(index, source, destination, &style));
+                        next_ranges.push(destination);
+                    }
+                }
+                ranges = next_ranges;
+            }
+        }
+        let categories = almanac
+            .transformation_maps
+            .first()
+            .map(|transformation_map| transformation_map.source.as_str())
+            .into_iter()
+            .chain(
+                almanac
+                    .transformation_maps
+                    .iter()
+                    .map(|transformation_map| transformation_map.destination.as_str()),
+            );
+        for (column, category) in categories.enumerate() {
+            svg.push_str(&format!(
+                "  <line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"black\"/>\n",
+                x(column),
+                MARGIN,
+                x(column),
+                MARGIN + HEIGHT
+            ));
+            svg.push_str(&format!(
+                "  <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" font-family=\"sans-serif\" font-size=\"12\">{}</text>\n",
+                x(column),
+                MARGIN / 2.0,
+                category
+            ));
+        }
+        svg.push_str("</svg>\n");
+        Ok(svg)
+    }
+
+    /// The structured form of the input for --dump-parsed, the seeds are ranges for part 2
+    pub fn parsed(input: &str, part: Part) -> Result<impl Serialize> {
+        Almanac::from_input(input, seed_parsing_mode(part))
+    }
+
+    struct AlmanacRepl {
+        almanac: Almanac,
+        composed: ComposedTransformation,
+    }
+
+    impl Queryable for AlmanacRepl {
+        fn help(&self) -> Vec<String> {
+            vec![
+                String::from("seeds: the seeds (or seed ranges) of the almanac"),
+                String::from("map <seed>: every step of the transformation of a seed"),
+                String::from("location <seed>...: the location of each seed"),
+            ]
+        }
+
+        fn query(&self, command: &str, args: &[&str]) -> Result<String> {
+            match command {
+                "seeds" => {
+                    let seeds: Vec<String> = self
+                        .almanac
+                        .seeds
+                        .iter()
+                        .map(|seed_range| match seed_range.end - seed_range.start {
+                            1 => seed_range.start.to_string(),
+                            _ => format!("{}..{}", seed_range.start, seed_range.end),
+                        })
+                        .collect();
+                    Ok(seeds.join(", "))
+                }
+                "map" => {
+                    let seed: u64 = repl::arg(args, 0, "seed")?;
+                    self.almanac.path(seed)
+                }
+                "location" => {
+                    let locations = (0..args.len().max(1))
+                        .map(|index| {
+                            let seed: u64 = repl::arg(args, index, "seed")?;
+                            Ok(self.composed.apply(seed).to_string())
+                        })
+                        .collect::<Result<Vec<String>>>()?;
+                    Ok(locations.join(" "))
+                }
+                _ => Err(repl::unknown_command(command)),
+            }
+        }
+    }
+
+    /// Queries over the almanac, the seeds are read as ranges for part 2
+    pub fn repl(input: &str, part: Part) -> Result<Box<dyn Queryable>> {
+        let almanac = Almanac::from_input(input, seed_parsing_mode(part))?;
+        let composed = almanac.compose()?;
+        Ok(Box::new(AlmanacRepl { almanac, composed }))
+    }
+
+    /// Every step of the transformation of one seed, with the line of each map that applies, to
+    /// compare with a computation by hand. The seed does not have to be one of the seeds
+    pub fn trace_seed(input: &str, part: Part, seed: u64) -> Result<String> {
+        let almanac = Almanac::from_input(input, seed_parsing_mode(part))?;
+        let seed_range = almanac.seeds.range_containing(seed);
+        let mut lines = vec![match (seed_parsing_mode(part), seed_range) {
+            (SeedParsingMode::OneSeed, Some(_)) => format!("seed {} is one of the seeds", seed),
+            (SeedParsingMode::OneSeed, None) => format!("seed {} is not one of the seeds", seed),
+            (SeedParsingMode::SeedRange, Some(seed_range)) => format!(
+                "seed {} is in the seed range {}..{}",
+                seed, seed_range.start, seed_range.end
+            ),
+            (SeedParsingMode::SeedRange, None) => {
+                format!("seed {} is not in any seed range", seed)
+            }
+        }];
+
+        let mut value = seed;
+        for transformation_map in &almanac.transformation_maps {
+            let transformation = transformation_map.find_transformation(value);
+            let next_value = transformation_map.apply_transformation(value)?;
+            let reason = match transformation {
+                Some(transformation) => format!(
+                    "by {} {} {}",
+                    transformation.destination_category,
+                    transformation.source_start_range,
+                    transformation.source_range
+                ),
+                None => String::from("not mapped"),
+            };
+            lines.push(format!(
+                "{}-to-{} map: {} -> {} ({})",
+                transformation_map.source,
+                transformation_map.destination,
+                value,
+                next_value,
+                reason
+            ));
+            value = next_value;
+        }
+        lines.push(almanac.path(seed)?);
+        Ok(lines.join("\n"))
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input, the
+    /// seeds are used as they are (single seeds or ranges) whatever the part
+    pub fn solve_json(input: &str, _part: Part) -> Result<Answer> {
+        let almanac: Almanac = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        if almanac.seeds.is_empty() {
+            return Err(Error::new("The almanac has no seeds"));
+        }
+        let lowest_result = almanac.apply_transformations_and_keep_lower_result()?;
+        Ok(Answer::from(lowest_result))
+    }
+
+    /// The algorithms of --algorithm: `ranges` pushes the seed ranges through the composed maps
+    /// (the default), `reverse` walks the locations back to the seeds
+    pub const ALGORITHMS: [&str; 2] = ["ranges", "reverse"];
+
+    pub fn solve_with_algorithm(input: &str, part: Part, algorithm: &str) -> Result<Answer> {
+        let almanac = Almanac::from_input(input, seed_parsing_mode(part))?;
+        if almanac.seeds.is_empty() {
+            return Err(Error::new("The almanac has no seeds"));
+        }
+        match algorithm {
+            "ranges" => almanac
+                .apply_transformations_and_keep_lower_result()
+                .map(Answer::from),
+            "reverse" => almanac.reverse_search().map(Answer::from),
+            _ => Err(Error::new(format!(
+                "Unknown algorithm {}, expected one of: {}",
+                algorithm,
+                ALGORITHMS.join(", ")
+            ))),
+        }
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        let almanac = Almanac::from_input(input, SeedParsingMode::OneSeed)?;
+        let lowest_result = almanac.apply_transformations_and_keep_lower_result()?;
+        Ok(Answer::from(lowest_result))
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        let almanac = Almanac::from_input(input, SeedParsingMode::SeedRange)?;
+        let lowest_result = almanac.apply_transformations_and_keep_lower_result()?;
+        Ok(Answer::from(lowest_result))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn test_transformation_one_seed() {
+            aocstd::init_tests();
+
+            let input = "seeds: 79 14 55 13\n\
+                \n\
+                seed-to-soil map:
+                50 98 2
+                52 50 48\n\
+                \n\
+                soil-to-fertilizer map:\n\
+                0 15 37\n\
+                37 52 2\n\
+                39 0 15";
+
+            let almanac = Almanac::from_input(input, SeedParsingMode::OneSeed).unwrap();
+            let seed_transformation_result = almanac
+                .apply_transformations_and_keep_lower_result()
+                .unwrap();
+            assert_eq!(seed_transformation_result, 52);
+            assert_eq!(almanac.transformation_maps[1].source, "soil");
+            assert_eq!(almanac.transformation_maps[1].destination, "fertilizer");
+
+            let pieces = almanac.transformation_maps[0]
+                .apply_to_range(SeedRange {
+                    start: 45,
+                    length: 10,
+                })
+                .unwrap();
+            assert_eq!(
+                pieces,
+                vec![
+                    (
+                        SeedRange {
+                            start: 45,
+                            length: 5
+                        },
+                        SeedRange {
+                            start: 45,
+                            length: 5
+                        }
+                    ),
+                    (
+                        SeedRange {
+                            start: 50,
+                            length: 5
+                        },
+                        SeedRange {
+                            start: 52,
+                            length: 5
+                        }
+                    ),
+                ]
+            );
+
+            let composed = almanac.compose().unwrap();
+            let repl = AlmanacRepl { almanac, composed };
+            assert_eq!(
+                repl.query("map", &["79"]).unwrap(),
+                "seed 79 -> soil 81 -> fertilizer 81"
+            );
+            assert_eq!(repl.query("location", &["79", "14"]).unwrap(), "81 53");
+        }
+
+        const EXAMPLE: &str = "seeds: 79 14 55 13\n\
+                \n\
+                seed-to-soil map:\n\
+                50 98 2\n\
+                52 50 48\n\
+                \n\
+                soil-to-fertilizer map:\n\
+                0 15 37\n\
+                37 52 2\n\
+                39 0 15\n\
+                \n\
+                fertilizer-to-water map:\n\
+                49 53 8\n\
+                0 11 42\n\
+                42 0 7\n\
+                57 7 4\n\
+                \n\
+                water-to-light map:\n\
+                88 18 7\n\
+                18 25 70\n\
+                \n\
+                light-to-temperature map:\n\
+                45 77 23\n\
+                81 45 19\n\
+                68 64 13\n\
+                \n\
+                temperature-to-humidity map:\n\
+                0 69 1\n\
+                1 0 69\n\
+                \n\
+                humidity-to-location map:\n\
+                60 56 37\n\
+                56 93 4";
+
+        #[test]
+        fn test_solve_with_ranges() {
+            aocstd::init_tests();
+
+            assert_eq!(solve_part1(EXAMPLE).unwrap(), Answer::from(35u64));
+            assert_eq!(solve_part2(EXAMPLE).unwrap(), Answer::from(46u64));
+            assert_eq!(
+                solve_with_algorithm(EXAMPLE, Part::Part1, "reverse").unwrap(),
+                Answer::from(35u64)
+            );
+            assert_eq!(
+                solve_with_algorithm(EXAMPLE, Part::Part2, "reverse").unwrap(),
+                Answer::from(46u64)
+            );
+            assert!(solve_with_algorithm(EXAMPLE, Part::Part2, "guess").is_err());
+
+            // The broken almanacs are errors instead of panics
+            for input in [
+                "",
+                "seeds:",
+                "seeds: 79 14 55",
+                "seeds: 79\n\nseed-to-soil map:\n50 98",
+                "seeds: 79\n\nseed-to-soil map:\n50 98 x",
+                "seeds: 79\n\nseed-to-soil map:\n50 18446744073709551615 2",
+            ] {
+                assert!(solve_part2(input).is_err(), "{:?}", input);
+            }
+            // Single seeds do not come in pairs
+            assert_eq!(solve_part1("seeds: 79 14 55").unwrap(), Answer::from(14u64));
+            assert!(solve_part1("seeds: 18446744073709551615").is_err());
+            let error = solve_part1("seeds: 99999999999999999999 1").unwrap_err();
+            assert!(
+                error.to_string().contains("99999999999999999999"),
+                "{}",
+                error
+            );
+
+            // A shift beyond i64 would wrap around, in the text and in the parsed form
+            let error = solve_part1("seeds: 79\n\nseed-to-soil map:\n18446744073709551000 0 100")
+                .unwrap_err()
+                .to_string();
+            assert_eq!(
+                error,
+                "The transformation 18446744073709551000 0 100 is too large"
+            );
+            let json = r#"{"seeds": [{"start": 0, "end": 1}], "transformation_maps": [{"source": "seed", "destination": "soil", "transformations": [{"destination_category": 0, "source_start_range": 18446744073709551615, "source_range": 2}]}]}"#;
+            assert!(solve_json(json, Part::Part1).is_err());
+        }
+
+        #[test]
+        fn test_compose() {
+            aocstd::init_tests();
+
+            let almanac = Almanac::from_input(EXAMPLE, SeedParsingMode::OneSeed).unwrap();
+            let composed = almanac.compose().unwrap();
+            for seed in 0..120 {
+                let mut location = seed;
+                for transformation_map in &almanac.transformation_maps {
+                    location = transformation_map.apply_transformation(location).unwrap();
+                }
+                assert_eq!(composed.apply(seed), location, "seed {}", seed);
+            }
+            assert_eq!(
+                composed.lowest_in(SeedRange {
+                    start: 82,
+                    length: 1
+                }),
+                Some(46)
+            );
+        }
+
+        #[test]
+        fn test_transformation_map() {
+            aocstd::init_tests();
+
+            let new = |lines: &[&str]| {
+                TransformationMap::new(
+                    String::from("seed"),
+                    String::from("soil"),
+                    lines
+                        .iter()
+                        .map(|line| Transformation::parse(line).unwrap())
+                        .collect(),
+                )
+            };
+            let transformation_map = new(&["50 98 2", "52 50 48"]).unwrap();
+            assert_eq!(
+                transformation_map
+                    .transformations
+                    .values()
+                    .next()
+                    .unwrap()
+                    .source_start_range,
+                50
+            );
+            assert_eq!(transformation_map.apply_transformation(49).unwrap(), 49);
+            assert_eq!(transformation_map.apply_transformation(97).unwrap(), 99);
+            assert_eq!(transformation_map.apply_transformation(99).unwrap(), 51);
+            assert_eq!(transformation_map.apply_transformation(100).unwrap(), 100);
+            assert_eq!(
+                transformation_map
+                    .inverse()
+                    .unwrap()
+                    .apply_transformation(51)
+                    .unwrap(),
+                99
+            );
+
+            assert!(new(&["50 98 2", "52 50 48", "0 90 5"]).is_err());
+            let overlapping = "seeds: 79\n\nseed-to-soil map:\n50 98 2\n52 50 48\n0 90 9";
+            let error = solve_part1(overlapping).unwrap_err().to_string();
+            assert!(error.starts_with("Overlapping transformations in the seed-to-soil map"));
+
+            // The parsed form is checked the same way
+            let json = r#"{"seeds": [{"start": 0, "end": 1}], "transformation_maps": [{"source": "seed", "destination": "soil", "transformations": [{"destination_category": 50, "source_start_range": 98, "source_range": 2}, {"destination_category": 0, "source_start_range": 90, "source_range": 9}]}]}"#;
+            assert!(solve_json(json, Part::Part1).is_err());
+        }
+
+        #[test]
+        fn test_trace_seed() {
+            aocstd::init_tests();
+
+            let input = "seeds: 79 14 55 13\n\
+                \n\
+                seed-to-soil map:\n\
+                50 98 2\n\
+                52 50 48\n\
+                \n\
+                soil-to-fertilizer map:\n\
+                0 15 37\n\
+                37 52 2\n\
+                39 0 15";
+            assert_eq!(
+                trace_seed(input, Part::Part2, 80).unwrap(),
+                "seed 80 is in the seed range 79..93\n\
+                 seed-to-soil map: 80 -> 82 (by 52 50 48)\n\
+                 soil-to-fertilizer map: 82 -> 82 (not mapped)\n\
+                 seed 80 -> soil 82 -> fertilizer 82"
+            );
+            assert!(trace_seed(input, Part::Part1, 80)
+                .unwrap()
+                .starts_with("seed 80 is not one of the seeds\n"));
+        }
+    }
+}
@@ -0,0 +1,492 @@
+pub mod waitforit {
+    use aocstd::repl::{self, Queryable};
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize};
+    use std::sync::OnceLock;
+    use textplots::{Chart, Plot, Shape};
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    struct Race {
+        time: u64,
+        distance: u64,
+    }
+
+    fn parse_races(input: &str) -> Result<Vec<Race>> {
+        // The input looks like this:
+        //   Time:      7  15   30
+        //   Distance:  9  40  200
+        let mut line_itr = aocstd::input::lines(input);
+        let time_line = line_itr.next().ok_or_else(|| Error::new("No time line"))?;
+        let distance_line = line_itr
+            .next()
+            .ok_or_else(|| Error::new("No distance line"))?;
+
+        // The headers have no digits
+        let time_values = aocstd::parse::ints::<u64>(time_line)?;
+        let distance_values = aocstd::parse::ints::<u64>(distance_line)?;
+
+        if time_values.len() != distance_values.len() {
+            return Err(Error::new(format!(
+                "{} times for {} distances",
+                time_values.len(),
+                distance_values.len()
+            )));
+        }
+        if time_values.is_empty() {
+            return Err(Error::new("No races"));
+        }
+        let mut races = Vec::with_capacity(time_values.len());
+        for (time, distance) in time_values.iter().zip(distance_values.iter()) {
+            races.push(Race {
+                time: *time,
+                distance: *distance,
+            });
+        }
+        log::debug!("Parsed races: {:?}", races);
+        Ok(races)
+    }
+
+    /// Time and distance of the race when holding the button for the given time and then
+    /// traveling just long enough to reach the record. In u128 since the kerned numbers of part 2
+    /// can overflow u64 once multiplied
+    #[cfg(test)]
+    fn simulate_race(hold_button_time: u64, record: Race) -> (u128, u128) {
+        // The time actualy represent the speed of the boat, so we can just divide the distance by
+        // the time rounding upwards.
+        let hold_button_time = hold_button_time as u128;
+        let travel_time = (record.distance as u128).div_ceil(hold_button_time);
+        (
+            travel_time + hold_button_time,
+            travel_time * hold_button_time,
+        )
+    }
+
+    impl Race {
+        /// Distance traveled when holding the button for the given time, it can exceed u64 for the
+        /// long races
+        fn distance_for(&self, hold_button_time: u64) -> u128 {
+            hold_button_time as u128 * self.time.saturating_sub(hold_button_time) as u128
+        }
+
+        /// First and last hold times beating the record, the distance curve is symmetric around
+        /// the middle of the race so the last one mirrors the first one. The distance grows until
+        /// the middle so the first one is found with a binary search
+        fn winning_interval(&self) -> Option<(u64, u64)> {
+            let middle = self.time / 2;
+            let first = aocstd::search::partition_point(0, middle + 1, |hold| {
+                self.distance_for(hold) <= self.distance as u128
+            });
+            (first <= middle).then(|| (first, self.time - first))
+        }
+
+        /// The hold times between the first and the last winning one all win
+        fn compute_nb_of_faster_solutions(&self) -> u64 {
+            let nb_of_solutions = self
+                .winning_interval()
+                .map_or(0, |(first, last)| last - first + 1);
+            trace::emit("race", || {
+                serde_json::json!({
+                    "time": self.time,
+                    "distance": self.distance,
+                    "solutions": nb_of_solutions,
+                })
+            });
+            log::debug!(
+                "There is {:?} solutions for race {:?}",
+                nb_of_solutions,
+                self
+            );
+            nb_of_solutions
+        }
+
+        /// Test every hold time, only to check the interval on small races
+        #[cfg(test)]
+        fn brute_force_nb_of_faster_solutions(&self) -> u64 {
+            // Test all the solutions for the range, faster than the Race record time
+            let mut nb_of_solutions = 0;
+            for hold_button_time in 1..self.time {
+                let (time, distance) = simulate_race(hold_button_time, *self);
+                if distance >= self.distance as u128
+                    && time <= self.time as u128
+                    && (time, distance) != (self.time as u128, self.distance as u128)
+                {
+                    aocstd::debug_sampled!(
+                        1000,
+                        "Found solution for race {:?}: holding button for {} ms, the race takes {} ms for {} mm",
+                        self,
+                        hold_button_time,
+                        time,
+                        distance
+                    );
+                    nb_of_solutions += 1;
+                } else {
+                    aocstd::debug_sampled!(
+                        1000,
+                        "NOT A solution for race {:?}: holding button for {} ms, the race takes {} ms for {} mm",
+                        self,
+                        hold_button_time,
+                        time,
+                        distance
+                    );
+                }
+            }
+            nb_of_solutions
+        }
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let races = parse_races(input)?;
+        Ok(format!("{} races", races.len()))
+    }
+
+    /// Part2 is the same as part1 but we need to remove the spaces between all the numbers of
+    /// the input
+    fn kern_input(input: &str) -> String {
+        // Use a regex to remove the spaces between the numbers, compiled on the first use
+        static REGEX: OnceLock<regex::Regex> = OnceLock::new();
+        REGEX
+            .get_or_init(|| regex::Regex::new(r"(\d)\s+(\d)").unwrap())
+            .replace_all(input, "$1$2")
+            .to_string()
+    }
+
+    fn races(input: &str, part: Part) -> Result<Vec<Race>> {
+        match part {
+            Part::Part1 => parse_races(input),
+            Part::Part2 => parse_races(&kern_input(input)),
+        }
+    }
+
+    /// The structured form of the input for --dump-parsed, a single kerned race for part 2
+    pub fn parsed(input: &str, part: Part) -> Result<impl Serialize> {
+        races(input, part)
+    }
+
+    struct RaceRepl {
+        races: Vec<Race>,
+    }
+
+    impl RaceRepl {
+        fn race(&self, args: &[&str]) -> Result<Race> {
+            let race_number: usize = repl::arg(args, 0, "n")?;
+            race_number
+                .checked_sub(1)
+                .and_then(|index| self.races.get(index))
+                .copied()
+                .ok_or_else(|| Error::new(format!("No race {}", race_number)))
+        }
+    }
+
+    impl Queryable for RaceRepl {
+        fn help(&self) -> Vec<String> {
+            vec![
+                String::from(
+                    "race <n>: time, record and number of ways to beat it (starting at 1)",
+                ),
+                String::from("hold <n> <ms>: distance traveled in race n holding the button ms"),
+            ]
+        }
+
+        fn query(&self, command: &str, args: &[&str]) -> Result<String> {
+            match command {
+                "race" => {
+                    let race = self.race(args)?;
+                    Ok(format!(
+                        "time: {} ms, record: {} mm, ways to beat it: {}",
+                        race.time,
+                        race.distance,
+                        race.compute_nb_of_faster_solutions()
+                    ))
+                }
+                "hold" => {
+                    let race = self.race(args)?;
+                    let hold_button_time: u64 = repl::arg(args, 1, "ms")?;
+                    if hold_button_time > race.time {
+                        return Err(Error::new(format!("The race only lasts {} ms", race.time)));
+                    }
+                    let distance = race.distance_for(hold_button_time);
+                    Ok(format!(
+                        "{} mm, record {}",
+                        distance,
+                        if distance > race.distance as u128 {
+                            "beaten"
+                        } else {
+                            "not beaten"
+                        }
+                    ))
+                }
+                _ => Err(repl::unknown_command(command)),
+            }
+        }
+    }
+
+    /// Queries over the races, for part 2 there is a single race with the kerned numbers
+    pub fn repl(input: &str, part: Part) -> Result<Box<dyn Queryable>> {
+        Ok(Box::new(RaceRepl {
+            races: races(input, part)?,
+        }))
+    }
+
+    fn describe_race(index: usize, race: &Race) -> String {
+        match race.winning_interval() {
+            Some((first, last)) => format!(
+                "Race {}: {} ms, record {} mm, beaten holding the button from {} to {} ms ({} ways)",
+                index + 1,
+                race.time,
+                race.distance,
+                first,
+                last,
+                last - first + 1
+            ),
+            None => format!(
+                "Race {}: {} ms, record {} mm, cannot be beaten",
+                index + 1,
+                race.time,
+                race.distance
+            ),
+        }
+    }
+
+    /// Plot the distance against the hold time of every race in the terminal, along with the
+    /// record to beat
+    pub fn visualize(input: &str, part: Part) -> Result<String> {
+        let mut plots = Vec::new();
+        for (index, race) in races(input, part)?.iter().enumerate() {
+            let time = race.time as f32;
+            let record = race.distance as f32;
+            let curve = Shape::Continuous(Box::new(move |hold| hold * (time - hold)));
+            let record_line = Shape::Continuous(Box::new(move |_| record));
+            let ymax = (time * time / 4.0).max(record) * 1.05;
+            let mut chart = Chart::new_with_y_range(160, 60, 0.0, time, 0.0, ymax);
+            let chart = chart.lineplot(&curve).lineplot(&record_line);
+            chart.axis();
+            chart.figures();
+            plots.push(format!("{}\n{}", describe_race(index, race), chart));
+        }
+        Ok(plots.join("\n"))
+    }
+
+    /// Same plots as `visualize` as an SVG, with the winning interval shaded
+    pub fn render_svg(input: &str, part: Part) -> Result<String> {
+        const WIDTH: f64 = 600.0;
+        const HEIGHT: f64 = 240.0;
+        const MARGIN: f64 = 30.0;
+        const SAMPLES: u64 = 200;
+
+        let races = races(input, part)?;
+        let panel_height = HEIGHT + 2.0 * MARGIN;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" font-family=\"sans-serif\" font-size=\"12\">\n",
+            WIDTH + 2.0 * MARGIN,
+            panel_height * races.len() as f64
+        );
+        for (index, race) in races.iter().enumerate() {
+            let top = index as f64 * panel_height + MARGIN;
+            let ymax = ((race.time as f64).powi(2) / 4.0).max(race.distance as f64) * 1.05;
+            let x = |hold: f64| MARGIN + hold / race.time.max(1) as f64 * WIDTH;
+            let y = |distance: f64| top + HEIGHT - distance / ymax * HEIGHT;
+
+            if let Some((first, last)) = race.winning_interval() {
+                svg.push_str(&format!(
+                    "  <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"green\" fill-opacity=\"0.2\"/>\n",
+                    x(first as f64),
+                    top,
+                    x(last as f64) - x(first as f64),
+                    HEIGHT
+                ));
+            }
+            let points: Vec<String> = (0..=SAMPLES)
+                .map(|sample| {
+                    // Below the time of the race once divided, but not before
+                    let hold = (race.time as u128 * sample as u128 / SAMPLES as u128) as u64;
+                    let distance = race.distance_for(hold) as f64;
+                    format!("{:.1},{:.1}", x(hold as f64), y(distance))
+                })
+                .collect();
+            svg.push_str(&format!(
+                "  <polyline points=\"{}\" fill=\"none\" stroke=\"steelblue\" stroke-width=\"2\"/>\n",
+                points.join(" ")
+            ));
+            svg.push_str(&format!(
+                "  <line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"red\" stroke-dasharray=\"4\"/>\n",
+                x(0.0),
+                y(race.distance as f64),
+                x(race.time as f64),
+                y(race.distance as f64)
+            ));
+            svg.push_str(&format!(
+                "  <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"none\" stroke=\"black\"/>\n",
+                MARGIN, top, WIDTH, HEIGHT
+            ));
+            svg.push_str(&format!(
+                "  <text x=\"{:.1}\" y=\"{:.1}\">{}</text>\n",
+                MARGIN,
+                top - 8.0,
+                describe_race(index, race)
+            ));
+        }
+        svg.push_str("</svg>\n");
+        Ok(svg)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input, the
+    /// races are used as they are whatever the part
+    pub fn solve_json(input: &str, _part: Part) -> Result<Answer> {
+        let races: Vec<Race> = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        solve_races(&races)
+    }
+
+    fn solve_races(races: &[Race]) -> Result<Answer> {
+        let mut result: u64 = 1;
+        for race in races {
+            match result.checked_mul(race.compute_nb_of_faster_solutions()) {
+                Some(product) => result = product,
+                #[cfg(feature = "num-bigint")]
+                None => return Ok(big_product(races)),
+                #[cfg(not(feature = "num-bigint"))]
+                None => {
+                    return Err(Error::new(
+                        "The product of the numbers of ways to win overflows u64",
+                    ))
+                }
+            }
+        }
+        Ok(Answer::from(result))
+    }
+
+    /// The product again without a bound, only once u64 overflowed since it allocates
+    #[cfg(feature = "num-bigint")]
+    fn big_product(races: &[Race]) -> Answer {
+        let product: aocstd::BigUint = races
+            .iter()
+            .map(|race| aocstd::BigUint::from(race.compute_nb_of_faster_solutions()))
+            .product();
+        Answer::from(product)
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        let races = parse_races(input)?;
+        solve_races(&races)
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        log::debug!("Part2 input: {}", input);
+        let rep_input_content = kern_input(input);
+        log::debug!("Part2 input: {}", rep_input_content);
+        let races = parse_races(&rep_input_content)?;
+        solve_races(&races)
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn test_races() {
+            aocstd::init_tests();
+
+            let input = "Time:      7  15   30\n\
+                Distance:   9  40  200";
+
+            let races = parse_races(input).unwrap();
+            assert!(races.len() == 3);
+            for input in ["", "Time: 7 15", "Time:\nDistance:"] {
+                assert!(solve_part1(input).is_err(), "{:?}", input);
+                assert!(solve_part2(input).is_err(), "{:?}", input);
+            }
+            // Kerned, the numbers of part 2 always match
+            assert!(solve_part1("Time: 7 15\nDistance: 9").is_err());
+
+            let first_race = races[0];
+            assert!(first_race.compute_nb_of_faster_solutions() == 4);
+            for time in 0..40 {
+                for distance in 0..(time * time / 4 + 2) {
+                    let race = Race { time, distance };
+                    assert_eq!(
+                        race.compute_nb_of_faster_solutions(),
+                        race.brute_force_nb_of_faster_solutions(),
+                        "{:?}",
+                        race
+                    );
+                }
+            }
+            assert_eq!(first_race.winning_interval(), Some((2, 5)));
+            assert_eq!(races[2].winning_interval(), Some((11, 19)));
+
+            let json = serde_json::to_string(&races).unwrap();
+            assert_eq!(
+                solve_json(&json, Part::Part1).unwrap(),
+                Answer::from(288u64)
+            );
+        }
+
+        #[test]
+        fn test_svg() {
+            aocstd::init_tests();
+
+            let svg = render_svg("Time: 7 15\nDistance: 9 40", Part::Part1).unwrap();
+            assert_eq!(svg.matches("<polyline").count(), 2);
+            // The sampled hold times go over u64 before being divided
+            for time in ["100000000000000000", "18446744073709551615"] {
+                let input = format!("Time: {}\nDistance: 9", time);
+                assert!(render_svg(&input, Part::Part1).is_ok());
+            }
+        }
+
+        #[test]
+        fn test_overflow_boundary() {
+            aocstd::init_tests();
+
+            // A time beyond u64 is an error instead of a panic
+            let input = "Time: 99999999999999999999\nDistance: 9";
+            assert!(solve_part1(input).is_err());
+            assert!(solve_part2(input).is_err());
+
+            // A record of u64::MAX needs a travel of 2^63 ms holding 2 ms, 2^64 mm in total
+            let record = Race {
+                time: u64::MAX,
+                distance: u64::MAX,
+            };
+            assert_eq!(simulate_race(2, record), ((1u128 << 63) + 2, 1u128 << 64));
+
+            // Half of a 2^34 ms race squared is 2^66
+            let race = Race {
+                time: 1 << 34,
+                distance: 0,
+            };
+            assert_eq!(race.distance_for(1 << 33), 1u128 << 66);
+
+            // Holding 1 ms gives exactly the record, 2 ms beats it while going over u64
+            let race = Race {
+                time: u64::MAX,
+                distance: u64::MAX - 1,
+            };
+            assert_eq!(race.winning_interval(), Some((2, u64::MAX - 2)));
+            // Counted without trying every hold time
+            assert_eq!(race.compute_nb_of_faster_solutions(), u64::MAX - 3);
+
+            // 8191 ways for each race, 8191^5 does not fit in u64
+            let races = vec![
+                Race {
+                    time: 8192,
+                    distance: 0,
+                };
+                5
+            ];
+            assert_eq!(races[0].compute_nb_of_faster_solutions(), 8191);
+            #[cfg(not(feature = "num-bigint"))]
+            assert!(solve_races(&races).is_err());
+            #[cfg(feature = "num-bigint")]
+            assert_eq!(
+                solve_races(&races).unwrap().to_string(),
+                "36870975646169341951"
+            );
+            assert_eq!(
+                solve_races(&races[..4]).unwrap(),
+                Answer::from(8191u64.pow(4))
+            );
+        }
+    }
+}
@@ -0,0 +1,242 @@
+pub mod camel_cards {
+    use aocstd::collections::Counter;
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize};
+
+    /// A hand of five cards and its bid, one per line of the input
+    /// ex: 32T3K 765
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    struct Hand {
+        cards: [char; 5],
+        bid: u64,
+    }
+
+    /// The types of hands from the weakest to the strongest
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    enum HandType {
+        HighCard,
+        OnePair,
+        TwoPair,
+        ThreeOfAKind,
+        FullHouse,
+        FourOfAKind,
+        FiveOfAKind,
+    }
+
+    /// In part 2 the J cards are jokers: they act like whatever card makes the strongest type,
+    /// but are the weakest cards when comparing two hands of the same type
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Rules {
+        Standard,
+        Jokers,
+    }
+
+    impl Rules {
+        fn of(part: Part) -> Self {
+            match part {
+                Part::Part1 => Rules::Standard,
+                Part::Part2 => Rules::Jokers,
+            }
+        }
+
+        /// The cards from the weakest to the strongest
+        fn card_order(&self) -> &'static str {
+            match self {
+                Rules::Standard => "23456789TJQKA",
+                Rules::Jokers => "J23456789TQKA",
+            }
+        }
+    }
+
+    impl Hand {
+        fn from_line(line: &str) -> Result<Self> {
+            let (cards, bid) = line
+                .split_once(' ')
+                .ok_or_else(|| Error::new(format!("No bid after the hand in {:?}", line)))?;
+            let cards: Vec<char> = cards.chars().collect();
+            let cards: [char; 5] = cards
+                .try_into()
+                .map_err(|_| Error::new(format!("A hand has 5 cards: {:?}", line)))?;
+            if let Some(card) = cards
+                .iter()
+                .find(|card| !Rules::Standard.card_order().contains(**card))
+            {
+                return Err(Error::new(format!("Unknown card {} in {:?}", card, line)));
+            }
+            let bid = bid
+                .trim()
+                .parse()
+                .map_err(|_| Error::new(format!("Invalid bid in {:?}", line)))?;
+            Ok(Hand { cards, bid })
+        }
+
+        fn hand_type(&self, rules: Rules) -> HandType {
+            let (jokers, others): (Vec<char>, Vec<char>) = self
+                .cards
+                .iter()
+                .partition(|card| rules == Rules::Jokers && **card == 'J');
+            let counter: Counter<char> = others.into_iter().collect();
+            let mut counts: Vec<u64> = counter
+                .most_common()
+                .into_iter()
+                .map(|(_, count)| count)
+                .collect();
+            // The jokers always join the most common card, five jokers are five of a kind
+            match counts.first_mut() {
+                Some(count) => *count += jokers.len() as u64,
+                None => counts.push(jokers.len() as u64),
+            }
+            match counts[..] {
+                [5] => HandType::FiveOfAKind,
+                [4, ..] => HandType::FourOfAKind,
+                [3, 2] => HandType::FullHouse,
+                [3, ..] => HandType::ThreeOfAKind,
+                [2, 2, ..] => HandType::TwoPair,
+                [2, ..] => HandType::OnePair,
+                _ => HandType::HighCard,
+            }
+        }
+
+        /// Hands are ordered by type, then card by card from the first one
+        fn strength(&self, rules: Rules) -> (HandType, [usize; 5]) {
+            let order = rules.card_order();
+            (
+                self.hand_type(rules),
+                self.cards.map(|card| order.find(card).unwrap_or(0)),
+            )
+        }
+    }
+
+    fn parse_hands(input: &str) -> Result<Vec<Hand>> {
+        aocstd::input::lines(input)
+            .filter(|line| !line.trim().is_empty())
+            .map(Hand::from_line)
+            .collect()
+    }
+
+    /// The sum of the bids each multiplied by the rank of its hand, the weakest hand being 1
+    fn total_winnings(hands: &[Hand], rules: Rules) -> Result<u64> {
+        let mut ranked: Vec<(&Hand, (HandType, [usize; 5]))> = hands
+            .iter()
+            .map(|hand| (hand, hand.strength(rules)))
+            .collect();
+        ranked.sort_by_key(|(_, strength)| *strength);
+        ranked
+            .iter()
+            .enumerate()
+            .try_fold(0u64, |total, (index, (hand, (hand_type, _)))| {
+                let rank = index as u64 + 1;
+                log::debug!(
+                    "Hand {} is a {:?} ranked {}",
+                    hand.cards.iter().collect::<String>(),
+                    hand_type,
+                    rank
+                );
+                trace::emit("hand", || {
+                    serde_json::json!({
+                        "cards": hand.cards.iter().collect::<String>(),
+                        "type": format!("{:?}", hand_type),
+                        "rank": rank,
+                    })
+                });
+                rank.checked_mul(hand.bid)
+                    .and_then(|winnings| total.checked_add(winnings))
+                    .ok_or_else(|| Error::new("The total winnings overflow u64"))
+            })
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let hands = parse_hands(input)?;
+        Ok(format!("{} hands", hands.len()))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        parse_hands(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let hands: Vec<Hand> = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        Ok(Answer::from(total_winnings(&hands, Rules::of(part))?))
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        let hands = parse_hands(input)?;
+        Ok(Answer::from(total_winnings(&hands, Rules::Standard)?))
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        let hands = parse_hands(input)?;
+        Ok(Answer::from(total_winnings(&hands, Rules::Jokers)?))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        const EXAMPLE: &str = "32T3K 765\n\
+                               T55J5 684\n\
+                               KK677 28\n\
+                               KTJJT 220\n\
+                               QQQJA 483";
+
+        #[test]
+        fn test_hand_types() {
+            aocstd::init_tests();
+
+            let hand_type = |cards: &str, rules| {
+                Hand::from_line(&format!("{} 1", cards))
+                    .unwrap()
+                    .hand_type(rules)
+            };
+            assert_eq!(hand_type("AAAAA", Rules::Standard), HandType::FiveOfAKind);
+            assert_eq!(hand_type("23332", Rules::Standard), HandType::FullHouse);
+            assert_eq!(hand_type("23432", Rules::Standard), HandType::TwoPair);
+            assert_eq!(hand_type("23456", Rules::Standard), HandType::HighCard);
+            assert_eq!(hand_type("KTJJT", Rules::Standard), HandType::TwoPair);
+            assert_eq!(hand_type("KTJJT", Rules::Jokers), HandType::FourOfAKind);
+            assert_eq!(hand_type("JJJJJ", Rules::Jokers), HandType::FiveOfAKind);
+            assert_eq!(hand_type("2345J", Rules::Jokers), HandType::OnePair);
+
+            // Both four of a kind with the jokers, the joker is then weaker than the queen
+            let (joker, queen) = (
+                Hand::from_line("JKKK2 1").unwrap(),
+                Hand::from_line("QQQQ2 1").unwrap(),
+            );
+            assert_eq!(joker.hand_type(Rules::Jokers), HandType::FourOfAKind);
+            assert!(joker.strength(Rules::Jokers) < queen.strength(Rules::Jokers));
+            // Same type without the jokers, the jack is stronger than a ten
+            let ten = Hand::from_line("T2345 1").unwrap();
+            let jack = Hand::from_line("J2345 1").unwrap();
+            assert!(ten.strength(Rules::Standard) < jack.strength(Rules::Standard));
+            assert!(Hand::from_line("2345 1").is_err());
+            assert!(Hand::from_line("2345X 1").is_err());
+        }
+
+        #[test]
+        fn test_total_winnings() {
+            aocstd::init_tests();
+
+            assert_eq!(solve_part1(EXAMPLE).unwrap(), Answer::from(6440u64));
+            assert_eq!(solve_part2(EXAMPLE).unwrap(), Answer::from(5905u64));
+
+            let json = serde_json::to_string(&parse_hands(EXAMPLE).unwrap()).unwrap();
+            assert_eq!(
+                solve_json(&json, Part::Part2).unwrap(),
+                Answer::from(5905u64)
+            );
+
+            // The second hand alone already wins twice its bid
+            let input = "23456 18446744073709551615\nAAAAA 18446744073709551615";
+            assert!(solve_part1(input).is_err());
+            let input = "23456 9223372036854775807\nAAAAA 1";
+            assert_eq!(
+                solve_part1(input).unwrap(),
+                Answer::from(9223372036854775809u64)
+            );
+        }
+    }
+}
@@ -0,0 +1,414 @@
+pub mod haunted_wasteland {
+    use aocstd::cycle::{self, Cycle};
+    use aocstd::math;
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    enum Direction {
+        Left,
+        Right,
+    }
+
+    /// A node of the network and the two nodes it leads to
+    /// ex: AAA = (BBB, CCC)
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    struct Node {
+        name: String,
+        left: String,
+        right: String,
+    }
+
+    /// The instructions, repeated forever, and the nodes as written in the input
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct Network {
+        instructions: Vec<Direction>,
+        nodes: Vec<Node>,
+    }
+
+    /// The network with the nodes numbered, for the walks
+    struct Map<'a> {
+        network: &'a Network,
+        indexes: HashMap<&'a str, usize>,
+        /// (left, right) of every node
+        next: Vec<(usize, usize)>,
+    }
+
+    /// Where a walk is: the node and the position in the instructions
+    type State = (usize, usize);
+
+    impl Network {
+        fn from_input(input: &str) -> Result<Self> {
+            let mut sections = aocstd::input::sections(input);
+            let instructions = sections
+                .next()
+                .ok_or_else(|| Error::new("No instructions"))?
+                .trim()
+                .chars()
+                .map(|c| match c {
+                    'L' => Ok(Direction::Left),
+                    'R' => Ok(Direction::Right),
+                    _ => Err(Error::new(format!("Unknown instruction {}", c))),
+                })
+                .collect::<Result<Vec<Direction>>>()?;
+            let nodes = sections
+                .next()
+                .map(aocstd::input::lines)
+                .into_iter()
+                .flatten()
+                .map(Node::from_line)
+                .collect::<Result<Vec<Node>>>()?;
+            Ok(Network {
+                instructions,
+                nodes,
+            })
+        }
+    }
+
+    impl Node {
+        fn from_line(line: &str) -> Result<Self> {
+            let invalid = || Error::new(format!("Invalid node {:?}", line));
+            let (name, targets) = line.split_once(" = ").ok_or_else(invalid)?;
+            let (left, right) = targets
+                .trim()
+                .strip_prefix('(')
+                .and_then(|targets| targets.strip_suffix(')'))
+                .and_then(|targets| targets.split_once(", "))
+                .ok_or_else(invalid)?;
+            Ok(Node {
+                name: name.trim().to_string(),
+                left: left.to_string(),
+                right: right.to_string(),
+            })
+        }
+    }
+
+    impl<'a> Map<'a> {
+        fn new(network: &'a Network) -> Result<Self> {
+            if network.instructions.is_empty() {
+                return Err(Error::new("No instructions"));
+            }
+            let indexes: HashMap<&str, usize> = network
+                .nodes
+                .iter()
+                .enumerate()
+                .map(|(index, node)| (node.name.as_str(), index))
+                .collect();
+            let index_of = |name: &str| {
+                indexes
+                    .get(name)
+                    .copied()
+                    .ok_or_else(|| Error::new(format!("Unknown node {}", name)))
+            };
+            let next = network
+                .nodes
+                .iter()
+                .map(|node| Ok((index_of(&node.left)?, index_of(&node.right)?)))
+                .collect::<Result<Vec<(usize, usize)>>>()?;
+            Ok(Map {
+                network,
+                indexes,
+                next,
+            })
+        }
+
+        fn name(&self, node: usize) -> &str {
+            &self.network.nodes[node].name
+        }
+
+        fn step(&self, (node, position): &State) -> State {
+            let (left, right) = self.next[*node];
+            let node = match self.network.instructions[*position] {
+                Direction::Left => left,
+                Direction::Right => right,
+            };
+            (node, (position + 1) % self.network.instructions.len())
+        }
+
+        /// Every walk repeats itself after that many steps at most
+        fn nb_of_states(&self) -> u64 {
+            (self.next.len() * self.network.instructions.len()) as u64
+        }
+
+        /// The steps from a node to the first node matching `is_end`, None when the walk loops
+        /// without reaching one
+        fn steps_to(&self, start: usize, is_end: impl Fn(&str) -> bool) -> Option<u64> {
+            let mut state = (start, 0);
+            for steps in 1..=self.nb_of_states() {
+                state = self.step(&state);
+                if is_end(self.name(state.0)) {
+                    return Some(steps);
+                }
+            }
+            None
+        }
+    }
+
+    /// When a ghost is on a node ending with Z once in its cycle: at the hits of its first turn of
+    /// the cycle, then every `cycle.length` steps after them
+    struct GhostWalk {
+        cycle: Cycle,
+        cycle_hits: Vec<u64>,
+    }
+
+    impl GhostWalk {
+        fn new(map: &Map, start: usize) -> Self {
+            let initial = (start, 0);
+            let cycle = cycle::brent(initial, |state| map.step(state));
+            let mut state = initial;
+            let mut cycle_hits = Vec::new();
+            for steps in 0..cycle.start + cycle.length {
+                if steps >= cycle.start && map.name(state.0).ends_with('Z') {
+                    cycle_hits.push(steps);
+                }
+                state = map.step(&state);
+            }
+            GhostWalk { cycle, cycle_hits }
+        }
+    }
+
+    /// The first step from `lowest` among `solution` plus a multiple of `period`, None when it
+    /// does not fit in u64
+    fn first_at_or_after(solution: u64, period: u64, lowest: u64) -> Option<u64> {
+        match solution >= lowest {
+            true => Some(solution),
+            false => period
+                .checked_mul((lowest - solution).div_ceil(period))?
+                .checked_add(solution),
+        }
+    }
+
+    /// The first step where every ghost is on a node ending with Z. Once all the ghosts are in
+    /// their cycles the steps are given by the chinese remainder theorem, which is only the lcm
+    /// of the cycle lengths on the real inputs (a single Z per cycle, at the end of the cycle)
+    fn ghost_steps(map: &Map) -> Result<u64> {
+        let starts: Vec<usize> = (0..map.next.len())
+            .filter(|node| map.name(*node).ends_with('A'))
+            .collect();
+        if starts.is_empty() {
+            return Err(Error::new("No node ending with A"));
+        }
+        let walks: Vec<GhostWalk> = starts
+            .iter()
+            .map(|start| GhostWalk::new(map, *start))
+            .collect();
+        for (start, walk) in starts.iter().zip(&walks) {
+            log::debug!(
+                "Ghost from {} loops every {} steps after {}, on Z at {:?}",
+                map.name(*start),
+                walk.cycle.length,
+                walk.cycle.start,
+                walk.cycle_hits
+            );
+            trace::emit("ghost", || {
+                serde_json::json!({
+                    "start": map.name(*start),
+                    "cycle_start": walk.cycle.start,
+                    "cycle_length": walk.cycle.length,
+                    "hits": walk.cycle_hits,
+                })
+            });
+        }
+
+        // Before the last ghost enters its cycle the ghosts are walked together
+        let settled = walks.iter().map(|walk| walk.cycle.start).max().unwrap_or(0);
+        let mut states: Vec<State> = starts.iter().map(|start| (*start, 0)).collect();
+        for steps in 1..=settled {
+            states = states.iter().map(|state| map.step(state)).collect();
+            if states
+                .iter()
+                .all(|(node, _)| map.name(*node).ends_with('Z'))
+            {
+                return Ok(steps);
+            }
+        }
+
+        let lowest = settled.max(1);
+        let at_cycle_ends = walks
+            .iter()
+            .all(|walk| walk.cycle_hits.len() == 1 && walk.cycle_hits[0] % walk.cycle.length == 0);
+        let overflow = || Error::new("The ghosts meet on Z nodes after more than u64 steps");
+        if at_cycle_ends {
+            return math::checked_lcm_all(walks.iter().map(|walk| walk.cycle.length))
+                .and_then(|lcm| first_at_or_after(0, lcm, lowest))
+                .ok_or_else(overflow);
+        }
+
+        // One congruence per ghost, for every choice of the Z hit of each one
+        let mut choices: Vec<Vec<(i128, i128)>> = vec![Vec::new()];
+        for walk in &walks {
+            choices = choices
+                .iter()
+                .flat_map(|choice| {
+                    walk.cycle_hits.iter().map(|hit| {
+                        let mut choice = choice.clone();
+                        choice.push((*hit as i128, walk.cycle.length as i128));
+                        choice
+                    })
+                })
+                .collect();
+        }
+        // A choice going over u64 only matters when no other choice fits
+        let mut overflowed = false;
+        let steps = choices
+            .iter()
+            .filter_map(|congruences| math::crt(congruences))
+            .filter_map(|(solution, lcm)| {
+                let steps = u64::try_from(solution)
+                    .ok()
+                    .zip(u64::try_from(lcm).ok())
+                    .and_then(|(solution, lcm)| first_at_or_after(solution, lcm, lowest));
+                overflowed |= steps.is_none();
+                steps
+            })
+            .min();
+        match (steps, overflowed) {
+            (Some(steps), _) => Ok(steps),
+            (None, true) => Err(overflow()),
+            (None, false) => Err(Error::new(
+                "The ghosts are never on Z nodes at the same time",
+            )),
+        }
+    }
+
+    fn solve_network(network: &Network, part: Part) -> Result<Answer> {
+        let map = Map::new(network)?;
+        let steps = match part {
+            Part::Part1 => {
+                let start = *map
+                    .indexes
+                    .get("AAA")
+                    .ok_or_else(|| Error::new("No node AAA"))?;
+                map.steps_to(start, |name| name == "ZZZ")
+                    .ok_or_else(|| Error::new("ZZZ cannot be reached from AAA"))?
+            }
+            Part::Part2 => ghost_steps(&map)?,
+        };
+        Ok(Answer::from(steps))
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let network = Network::from_input(input)?;
+        Ok(format!(
+            "{} instructions, {} nodes",
+            network.instructions.len(),
+            network.nodes.len()
+        ))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        Network::from_input(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let network: Network = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        solve_network(&network, part)
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        solve_network(&Network::from_input(input)?, Part::Part1)
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        solve_network(&Network::from_input(input)?, Part::Part2)
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        const EXAMPLE1: &str = "RL\n\
+                                \n\
+                                AAA = (BBB, CCC)\n\
+                                BBB = (DDD, EEE)\n\
+                                CCC = (ZZZ, GGG)\n\
+                                DDD = (DDD, DDD)\n\
+                                EEE = (EEE, EEE)\n\
+                                GGG = (GGG, GGG)\n\
+                                ZZZ = (ZZZ, ZZZ)\n";
+
+        const EXAMPLE2: &str = "LLR\n\
+                                \n\
+                                AAA = (BBB, BBB)\n\
+                                BBB = (AAA, ZZZ)\n\
+                                ZZZ = (ZZZ, ZZZ)\n";
+
+        const EXAMPLE3: &str = "LR\n\
+                                \n\
+                                11A = (11B, XXX)\n\
+                                11B = (XXX, 11Z)\n\
+                                11Z = (11B, XXX)\n\
+                                22A = (22B, XXX)\n\
+                                22B = (22C, 22C)\n\
+                                22C = (22Z, 22Z)\n\
+                                22Z = (22B, 22B)\n\
+                                XXX = (XXX, XXX)\n";
+
+        #[test]
+        fn test_examples() {
+            aocstd::init_tests();
+
+            assert_eq!(solve_part1(EXAMPLE1).unwrap(), Answer::from(2u64));
+            assert_eq!(solve_part1(EXAMPLE2).unwrap(), Answer::from(6u64));
+            assert_eq!(solve_part2(EXAMPLE3).unwrap(), Answer::from(6u64));
+
+            let json = serde_json::to_string(&Network::from_input(EXAMPLE3).unwrap()).unwrap();
+            assert_eq!(solve_json(&json, Part::Part2).unwrap(), Answer::from(6u64));
+            assert!(solve_part1("LR\n\nAAA = (AAA, BBB)\nBBB = (AAA, CCC)").is_err());
+            assert!(solve_part1("LR\n\nAAA = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)").is_err());
+        }
+
+        #[test]
+        fn test_ghost_offsets() {
+            aocstd::init_tests();
+
+            // The ghost from 11A is on a Z every 2 steps from step 2, the one from 33A every 3
+            // steps from step 4 (its cycle does not end on the Z): 4 is the first common step
+            let input = "L\n\
+                         \n\
+                         11A = (11B, 11B)\n\
+                         11B = (11Z, 11Z)\n\
+                         11Z = (11B, 11B)\n\
+                         33A = (33B, 33B)\n\
+                         33B = (33C, 33C)\n\
+                         33C = (33D, 33D)\n\
+                         33D = (33Z, 33Z)\n\
+                         33Z = (33C, 33C)\n";
+            assert_eq!(solve_part2(input).unwrap(), Answer::from(4u64));
+
+            // Hits on 3k + 1 (k >= 1) and 2k + 1 (k >= 1): 7
+            let input = "L\n\
+                         \n\
+                         11A = (11B, 11B)\n\
+                         11B = (11C, 11C)\n\
+                         11C = (11D, 11D)\n\
+                         11D = (11Z, 11Z)\n\
+                         11Z = (11C, 11C)\n\
+                         22A = (22B, 22B)\n\
+                         22B = (22C, 22C)\n\
+                         22C = (22Z, 22Z)\n\
+                         22Z = (22C, 22C)\n";
+            assert_eq!(solve_part2(input).unwrap(), Answer::from(7u64));
+        }
+
+        #[test]
+        fn test_first_at_or_after() {
+            aocstd::init_tests();
+
+            assert_eq!(first_at_or_after(7, 3, 2), Some(7));
+            assert_eq!(first_at_or_after(1, 3, 8), Some(10));
+            assert_eq!(first_at_or_after(0, 6, 1), Some(6));
+            assert_eq!(first_at_or_after(0, u64::MAX, 1), Some(u64::MAX));
+            assert_eq!(first_at_or_after(1, u64::MAX, 2), None);
+            assert_eq!(
+                first_at_or_after(0, u64::MAX / 2 + 1, u64::MAX / 2 + 2),
+                None
+            );
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use clap::Parser;
+use day08::haunted_wasteland;
+
+fn main() {
+    let cli = aocstd::Cli::parse();
+    aocstd::init_logger(&cli);
+    aocstd::init_threads(&cli);
+    aocstd::init_trace(&cli);
+    let input = aocstd::get_input_string(&cli);
+    aocstd::init_profile(&cli);
+
+    if let Some(path) = &cli.dump_parsed {
+        aocstd::dump_parsed(path, haunted_wasteland::parsed(&input, cli.part));
+        return;
+    }
+
+    let answer = match (cli.input_format, cli.part) {
+        (aocstd::InputFormat::Json, part) => haunted_wasteland::solve_json(&input, part),
+        (aocstd::InputFormat::Text, aocstd::Part::Part1) => haunted_wasteland::solve_part1(&input),
+        (aocstd::InputFormat::Text, aocstd::Part::Part2) => haunted_wasteland::solve_part2(&input),
+    };
+    aocstd::report_answer(cli.part, answer);
+}
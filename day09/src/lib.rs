@@ -0,0 +1,191 @@
+pub mod mirage_maintenance {
+    use aocstd::iter::IteratorExt;
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::Serialize;
+
+    /// The values of one reading of the OASIS over time, one history per line
+    /// ex: 0 3 6 9 12 15
+    type History = Vec<i64>;
+
+    fn parse_history(line: &str) -> Result<History> {
+        line.split_whitespace()
+            .map(|value| {
+                value.parse().map_err(|_| {
+                    Error::new(format!("Invalid value {:?} in history: {}", value, line))
+                })
+            })
+            .collect()
+    }
+
+    fn parse_histories(input: &str) -> Result<Vec<History>> {
+        aocstd::input::lines(input)
+            .filter(|line| !line.trim().is_empty())
+            .map(parse_history)
+            .collect()
+    }
+
+    fn overflow(history: &[i64]) -> Error {
+        Error::new(format!("The extrapolation of {:?} overflows i64", history))
+    }
+
+    /// The history, then the differences between its values, then the differences of these
+    /// differences... until a level of zeros (not included)
+    fn difference_levels(history: &[i64]) -> Result<Vec<Vec<i64>>> {
+        let mut levels = Vec::new();
+        let mut level = history.to_vec();
+        while level.iter().any(|value| *value != 0) {
+            let next = level
+                .iter()
+                .pairwise()
+                .map(|(a, b)| b.checked_sub(*a))
+                .collect::<Option<Vec<i64>>>()
+                .ok_or_else(|| overflow(history))?;
+            levels.push(level);
+            level = next;
+        }
+        Ok(levels)
+    }
+
+    /// The next value: each level is extended by the value extended below it, from the bottom
+    fn next_value(history: &[i64]) -> Result<i64> {
+        difference_levels(history)?
+            .iter()
+            .filter_map(|level| level.last())
+            .try_fold(0i64, |sum, last| sum.checked_add(*last))
+            .ok_or_else(|| overflow(history))
+    }
+
+    /// The value before the first one, each level extended backwards: a first value minus the
+    /// one extended below it, so the firsts alternate signs from the top
+    fn previous_value(history: &[i64]) -> Result<i64> {
+        difference_levels(history)?
+            .iter()
+            .filter_map(|level| level.first())
+            .rev()
+            .try_fold(0i64, |below, first| first.checked_sub(below))
+            .ok_or_else(|| overflow(history))
+    }
+
+    fn extrapolate(histories: &[History], part: Part) -> Result<i64> {
+        histories.iter().try_fold(0i64, |sum, history| {
+            let value = match part {
+                Part::Part1 => next_value(history)?,
+                Part::Part2 => previous_value(history)?,
+            };
+            log::debug!("{:?} extrapolated to {}", history, value);
+            trace::emit(
+                "history",
+                || serde_json::json!({ "history": history, "value": value }),
+            );
+            sum.checked_add(value)
+                .ok_or_else(|| Error::new("The sum of the extrapolated values overflows i64"))
+        })
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let histories = parse_histories(input)?;
+        let nb_of_values: usize = histories.iter().map(Vec::len).sum();
+        Ok(format!(
+            "{} histories, {} values",
+            histories.len(),
+            nb_of_values
+        ))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        parse_histories(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let histories: Vec<History> = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        Ok(Answer::from(extrapolate(&histories, part)?))
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        Ok(Answer::from(extrapolate(
+            &parse_histories(input)?,
+            Part::Part1,
+        )?))
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        Ok(Answer::from(extrapolate(
+            &parse_histories(input)?,
+            Part::Part2,
+        )?))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        #[test]
+        fn test_extrapolation() {
+            aocstd::init_tests();
+
+            let input = "0 3 6 9 12 15\n\
+                         1 3 6 10 15 21\n\
+                         10 13 16 21 30 45";
+            assert_eq!(solve_part1(input).unwrap(), Answer::from(114i64));
+            assert_eq!(solve_part2(input).unwrap(), Answer::from(2i64));
+            assert_eq!(previous_value(&[10, 13, 16, 21, 30, 45]).unwrap(), 5);
+            assert_eq!(next_value(&[-4, -2, 0]).unwrap(), 2);
+            assert_eq!(next_value(&[]).unwrap(), 0);
+        }
+
+        #[test]
+        fn test_invalid_histories() {
+            aocstd::init_tests();
+
+            assert!(solve_part1("0 3 6 x 12").is_err());
+            assert!(solve_part1("0 3 6 9.5 12").is_err());
+            assert!(solve_part2("1-3 5").is_err());
+            assert!(solve_part2("0 3 99999999999999999999").is_err());
+            assert_eq!(parse_history("-4 -2 0").unwrap(), vec![-4, -2, 0]);
+
+            // Valid values whose differences or extrapolations do not fit in i64
+            let input = "-9223372036854775808 9223372036854775807";
+            assert!(solve_part1(input).is_err());
+            assert!(solve_part2(input).is_err());
+            assert!(solve_part1("9223372036854775806 9223372036854775807").is_err());
+            assert!(solve_part2("-9223372036854775808 -9223372036854775807").is_err());
+            assert!(solve_part1("9223372036854775807\n1").is_err());
+        }
+
+        #[test]
+        fn test_reversed_histories() {
+            aocstd::init_tests();
+
+            // Histories of random polynomials: extrapolating forwards continues the polynomial,
+            // and the reversed history extrapolated backwards gives the same value
+            let mut rng = StdRng::seed_from_u64(9);
+            for _ in 0..200 {
+                let degree = rng.gen_range(0..6);
+                let coefficients: Vec<i64> =
+                    (0..=degree).map(|_| rng.gen_range(-20..=20)).collect();
+                let polynomial = |x: i64| {
+                    coefficients
+                        .iter()
+                        .rev()
+                        .fold(0, |value, coefficient| value * x + coefficient)
+                };
+                let length = rng.gen_range(degree + 2..=degree + 10);
+                let history: Vec<i64> = (0..length as i64).map(polynomial).collect();
+                let reversed: Vec<i64> = history.iter().rev().copied().collect();
+
+                let next = next_value(&history).unwrap();
+                let previous = previous_value(&history).unwrap();
+                assert_eq!(next, polynomial(length as i64));
+                assert_eq!(previous, polynomial(-1));
+                assert_eq!(previous_value(&reversed).unwrap(), next);
+                assert_eq!(next_value(&reversed).unwrap(), previous);
+            }
+        }
+    }
+}
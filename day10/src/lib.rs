@@ -0,0 +1,399 @@
+pub mod pipe_maze {
+    use aocstd::geometry::{Direction, Point};
+    use aocstd::grid::Grid;
+    use aocstd::viz::{self, Color, Frame, Style};
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize};
+
+    /// A tile of the field, a pipe connects two of the cardinal directions
+    /// ex: | connects North and South, F connects East and South
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    enum Tile {
+        Ground,
+        Start,
+        Pipe(Direction, Direction),
+    }
+
+    impl Tile {
+        fn from_char(c: char) -> Option<Tile> {
+            use Direction::*;
+            let tile = match c {
+                '.' => Tile::Ground,
+                'S' => Tile::Start,
+                '|' => Tile::Pipe(North, South),
+                '-' => Tile::Pipe(East, West),
+                'L' => Tile::Pipe(North, East),
+                'J' => Tile::Pipe(North, West),
+                '7' => Tile::Pipe(South, West),
+                'F' => Tile::Pipe(East, South),
+                _ => return None,
+            };
+            Some(tile)
+        }
+
+        fn connects(&self, direction: Direction) -> bool {
+            match self {
+                Tile::Pipe(a, b) => *a == direction || *b == direction,
+                _ => false,
+            }
+        }
+
+        /// The box drawing character of the tile, the pipes are easier to follow this way
+        fn symbol(&self) -> char {
+            use Direction::*;
+            match self {
+                Tile::Ground => '.',
+                Tile::Start => 'S',
+                Tile::Pipe(North, South) | Tile::Pipe(South, North) => '│',
+                Tile::Pipe(East, West) | Tile::Pipe(West, East) => '─',
+                Tile::Pipe(North, East) | Tile::Pipe(East, North) => '└',
+                Tile::Pipe(North, West) | Tile::Pipe(West, North) => '┘',
+                Tile::Pipe(South, West) | Tile::Pipe(West, South) => '┐',
+                Tile::Pipe(East, South) | Tile::Pipe(South, East) => '┌',
+                Tile::Pipe(_, _) => '?',
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct Field {
+        grid: Grid<Tile>,
+        start: Point,
+    }
+
+    /// The main loop: its tiles in the order they are walked from the start, and the pipe hidden
+    /// under the start
+    #[derive(Debug, Clone)]
+    struct PipeLoop {
+        tiles: Vec<Point>,
+        start_pipe: Tile,
+    }
+
+    fn parse_field(input: &str) -> Result<Field> {
+        let mut rows = Vec::new();
+        let mut start = None;
+        for (y, line) in aocstd::input::lines(input)
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+        {
+            let mut row = Vec::new();
+            for (x, c) in line.trim_end().chars().enumerate() {
+                let tile = Tile::from_char(c)
+                    .ok_or_else(|| Error::new(format!("Unknown tile {:?} at {},{}", c, x, y)))?;
+                if tile == Tile::Start {
+                    if start.is_some() {
+                        return Err(Error::new(format!("A second start at {},{}", x, y)));
+                    }
+                    start = Some(Point::new(x as i64, y as i64));
+                }
+                row.push(tile);
+            }
+            rows.push(row);
+        }
+        let start = start.ok_or_else(|| Error::new(String::from("No start in the field")))?;
+        Ok(Field {
+            grid: Grid::from_rows(rows, Tile::Ground),
+            start,
+        })
+    }
+
+    impl Field {
+        /// Follow the pipes from the start leaving towards a direction, the tiles walked if the
+        /// pipes lead back to the start, with the direction it is entered from
+        fn follow(&self, direction: Direction) -> Option<(Vec<Point>, Direction)> {
+            let mut tiles = vec![self.start];
+            let mut position = self.start;
+            let mut direction = direction;
+            loop {
+                position += direction;
+                if position == self.start {
+                    return Some((tiles, direction));
+                }
+                let Some(Tile::Pipe(a, b)) = self.grid.get(position) else {
+                    return None;
+                };
+                let from = direction.opposite();
+                direction = match (*a == from, *b == from) {
+                    (true, _) => *b,
+                    (_, true) => *a,
+                    _ => return None,
+                };
+                tiles.push(position);
+            }
+        }
+
+        /// The loop going through the start, whatever pipe the start turns out to be
+        fn find_loop(&self) -> Result<PipeLoop> {
+            Direction::CARDINALS
+                .iter()
+                .filter(|direction| {
+                    self.grid
+                        .get(self.start + **direction)
+                        .is_some_and(|tile| tile.connects(direction.opposite()))
+                })
+                .find_map(|direction| {
+                    let (tiles, back) = self.follow(*direction)?;
+                    Some(PipeLoop {
+                        tiles,
+                        start_pipe: Tile::Pipe(*direction, back.opposite()),
+                    })
+                })
+                .ok_or_else(|| Error::new(format!("No loop through the start {}", self.start)))
+        }
+
+        fn tile(&self, pipe_loop: &PipeLoop, position: Point) -> Tile {
+            match self.grid.get(position) {
+                Some(Tile::Start) => pipe_loop.start_pipe,
+                Some(tile) => *tile,
+                None => Tile::Ground,
+            }
+        }
+
+        /// The tiles enclosed by the loop found by casting a ray along each row: the ray gets
+        /// in or out of the loop every time it crosses a loop pipe going North. Slower than
+        /// Pick's theorem but it tells which tiles are inside
+        fn enclosed_tiles(&self, pipe_loop: &PipeLoop) -> Vec<Point> {
+            let mut on_loop = Grid::new(self.grid.width(), self.grid.height(), false);
+            for position in &pipe_loop.tiles {
+                if let Some(cell) = on_loop.get_mut(*position) {
+                    *cell = true;
+                }
+            }
+            let mut enclosed = Vec::new();
+            for y in 0..self.grid.height() as i64 {
+                let mut inside = false;
+                for x in 0..self.grid.width() as i64 {
+                    let position = Point::new(x, y);
+                    if on_loop.get(position) == Some(&true) {
+                        if self.tile(pipe_loop, position).connects(Direction::North) {
+                            inside = !inside;
+                        }
+                    } else if inside {
+                        enclosed.push(position);
+                    }
+                }
+            }
+            enclosed
+        }
+    }
+
+    /// The farthest tile of the loop from the start is halfway around it
+    fn farthest_steps(pipe_loop: &PipeLoop) -> u64 {
+        pipe_loop.tiles.len() as u64 / 2
+    }
+
+    /// The loop tiles are the vertices of a polygon, Pick's theorem gives the number of
+    /// tiles inside from the shoelace area
+    fn enclosed_count(pipe_loop: &PipeLoop) -> Result<u64> {
+        aocstd::math::interior_points(&pipe_loop.tiles)
+            .ok_or_else(|| Error::new("The loop folds back on itself"))
+    }
+
+    fn solve(field: &Field, part: Part) -> Result<Answer> {
+        let pipe_loop = field.find_loop()?;
+        log::debug!(
+            "Loop of {} tiles, the start is a {}",
+            pipe_loop.tiles.len(),
+            pipe_loop.start_pipe.symbol()
+        );
+        trace::emit("loop", || {
+            serde_json::json!({
+                "start": field.start,
+                "length": pipe_loop.tiles.len(),
+                "start_pipe": pipe_loop.start_pipe,
+            })
+        });
+        Ok(match part {
+            Part::Part1 => Answer::from(farthest_steps(&pipe_loop)),
+            Part::Part2 => Answer::from(enclosed_count(&pipe_loop)?),
+        })
+    }
+
+    /// The field with the loop drawn in box characters, the other pipes dimmed. Part 1 marks the
+    /// farthest tile of the loop and part 2 the enclosed tiles
+    fn draw(field: &Field, pipe_loop: &PipeLoop, part: Part) -> Frame {
+        let mut frame = Frame::new(field.grid.width(), field.grid.height());
+        for (position, tile) in field.grid.iter() {
+            frame.set(
+                position.x as usize,
+                position.y as usize,
+                tile.symbol(),
+                Style::fg(Color::GRAY),
+            );
+        }
+        for position in &pipe_loop.tiles {
+            let style = match field.grid.get(*position) {
+                Some(Tile::Start) => Style::fg(Color::YELLOW).bold(),
+                _ => Style::fg(Color::GREEN),
+            };
+            let symbol = field.tile(pipe_loop, *position).symbol();
+            frame.set(position.x as usize, position.y as usize, symbol, style);
+        }
+        match part {
+            Part::Part1 => {
+                let farthest = pipe_loop.tiles[pipe_loop.tiles.len() / 2];
+                frame.style(
+                    farthest.x as usize,
+                    farthest.y as usize,
+                    Style::fg(Color::RED).bold(),
+                );
+            }
+            Part::Part2 => {
+                for position in field.enclosed_tiles(pipe_loop) {
+                    let style = Style::fg(Color::BLUE).bold();
+                    frame.set(position.x as usize, position.y as usize, 'I', style);
+                }
+            }
+        }
+        frame
+    }
+
+    /// Render the loop in the terminal
+    pub fn visualize(input: &str, part: Part) -> Result<String> {
+        let field = parse_field(input)?;
+        let pipe_loop = field.find_loop()?;
+        viz::terminal_string(&[draw(&field, &pipe_loop, part)])
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let field = parse_field(input)?;
+        Ok(format!(
+            "{}x{} tiles, start at {}",
+            field.grid.width(),
+            field.grid.height(),
+            field.start
+        ))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        parse_field(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let field: Field = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        field.grid.check_size()?;
+        solve(&field, part)
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        solve(&parse_field(input)?, Part::Part1)
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        solve(&parse_field(input)?, Part::Part2)
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn test_farthest_point() {
+            aocstd::init_tests();
+
+            // The loop is surrounded by pipes that are not connected to it
+            let simple = "-L|F7\n\
+                          7S-7|\n\
+                          L|7||\n\
+                          -L-J|\n\
+                          L|-JF";
+            assert_eq!(solve_part1(simple).unwrap(), Answer::from(4u64));
+            let complex = "..F7.\n\
+                           .FJ|.\n\
+                           SJ.L7\n\
+                           |F--J\n\
+                           LJ...";
+            assert_eq!(solve_part1(complex).unwrap(), Answer::from(8u64));
+
+            let field = parse_field(complex).unwrap();
+            let pipe_loop = field.find_loop().unwrap();
+            use Direction::*;
+            assert!(
+                [Tile::Pipe(East, South), Tile::Pipe(South, East)].contains(&pipe_loop.start_pipe)
+            );
+            assert!(parse_field("..\n.X").is_err());
+            assert!(parse_field("S.\n.S").is_err());
+            assert!(solve_part1("S.\n..").is_err());
+        }
+
+        #[test]
+        fn test_enclosed_tiles() {
+            aocstd::init_tests();
+
+            let examples = [
+                (
+                    "...........\n\
+                     .S-------7.\n\
+                     .|F-----7|.\n\
+                     .||.....||.\n\
+                     .||.....||.\n\
+                     .|L-7.F-J|.\n\
+                     .|..|.|..|.\n\
+                     .L--J.L--J.\n\
+                     ...........",
+                    4u64,
+                ),
+                // The pipes squeezed together leave no gap to the outside
+                (
+                    "..........\n\
+                     .S------7.\n\
+                     .|F----7|.\n\
+                     .||OOOO||.\n\
+                     .||OOOO||.\n\
+                     .|L-7F-J|.\n\
+                     .|II||II|.\n\
+                     .L--JL--J.\n\
+                     ..........",
+                    4,
+                ),
+                (
+                    ".F----7F7F7F7F-7....\n\
+                     .|F--7||||||||FJ....\n\
+                     .||.FJ||||||||L7....\n\
+                     FJL7L7LJLJ||LJ.L-7..\n\
+                     L--J.L7...LJS7F-7L7.\n\
+                     ....F-J..F7FJ|L7L7L7\n\
+                     ....L7.F7||L7|.L7L7|\n\
+                     .....|FJLJ|FJ|F7|.LJ\n\
+                     ....FJL-7.||.||||...\n\
+                     ....L---J.LJ.LJLJ...",
+                    8,
+                ),
+                (
+                    "FF7FSF7F7F7F7F7F---7\n\
+                     L|LJ||||||||||||F--J\n\
+                     FL-7LJLJ||||||LJL-77\n\
+                     F--JF--7||LJLJ7F7FJ-\n\
+                     L---JF-JLJ.||-FJLJJ7\n\
+                     |F|F-JF---7F7-L7L|7|\n\
+                     |FFJF7L7F-JF7|JL---7\n\
+                     7-L-JL7||F7|L7F-7F7|\n\
+                     L.L7LFJ|||||FJL7||LJ\n\
+                     L7JLJL-JLJLJL--JLJ.L",
+                    10,
+                ),
+            ];
+            for (input, expected) in examples {
+                // The O and I markers of the puzzle are just ground
+                let input = input.replace(['O', 'I'], ".");
+                assert_eq!(solve_part2(&input).unwrap(), Answer::from(expected));
+
+                // Casting rays finds the same tiles as Pick's theorem
+                let field = parse_field(&input).unwrap();
+                let pipe_loop = field.find_loop().unwrap();
+                let enclosed = field.enclosed_tiles(&pipe_loop);
+                assert_eq!(enclosed.len() as u64, enclosed_count(&pipe_loop).unwrap());
+
+                let json = serde_json::to_string(&field).unwrap();
+                assert_eq!(
+                    solve_json(&json, Part::Part2).unwrap(),
+                    Answer::from(expected)
+                );
+            }
+        }
+    }
+}
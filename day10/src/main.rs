@@ -0,0 +1,39 @@
+use clap::Parser;
+use day10::pipe_maze;
+
+/// The shared options along with the ones of this day only
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(flatten)]
+    cli: aocstd::Cli,
+    /// Render the loop in the terminal instead of solving
+    #[arg(long, alias = "plot")]
+    visualize: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+    let cli = args.cli;
+    aocstd::init_logger(&cli);
+    aocstd::init_threads(&cli);
+    aocstd::init_trace(&cli);
+    let input = aocstd::get_input_string(&cli);
+    aocstd::init_profile(&cli);
+
+    if let Some(path) = &cli.dump_parsed {
+        aocstd::dump_parsed(path, pipe_maze::parsed(&input, cli.part));
+        return;
+    }
+    if args.visualize {
+        aocstd::print_visualization(pipe_maze::visualize(&input, cli.part));
+        return;
+    }
+
+    let answer = match (cli.input_format, cli.part) {
+        (aocstd::InputFormat::Json, part) => pipe_maze::solve_json(&input, part),
+        (aocstd::InputFormat::Text, aocstd::Part::Part1) => pipe_maze::solve_part1(&input),
+        (aocstd::InputFormat::Text, aocstd::Part::Part2) => pipe_maze::solve_part2(&input),
+    };
+    aocstd::report_answer(cli.part, answer);
+}
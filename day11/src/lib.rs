@@ -0,0 +1,217 @@
+pub mod cosmic_expansion {
+    use aocstd::geometry::Point;
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::Serialize;
+
+    /// How many times bigger the empty rows and columns are in each part
+    fn expansion_factor(part: Part) -> u64 {
+        match part {
+            Part::Part1 => 2,
+            Part::Part2 => 1_000_000,
+        }
+    }
+
+    /// The positions of the galaxies, the # of the image
+    fn parse_galaxies(input: &str) -> Result<Vec<Point>> {
+        let mut galaxies = Vec::new();
+        for (y, line) in aocstd::input::lines(input).enumerate() {
+            for (x, c) in line.trim_end().chars().enumerate() {
+                match c {
+                    '#' => galaxies.push(Point::new(x as i64, y as i64)),
+                    '.' => {}
+                    _ => {
+                        return Err(Error::new(format!(
+                            "Unknown character {:?} at {},{}",
+                            c, x, y
+                        )))
+                    }
+                }
+            }
+        }
+        Ok(galaxies)
+    }
+
+    /// The coordinates once the empty rows (or columns) before them have expanded. Only the
+    /// coordinates holding a galaxy matter, so the empty ones are counted from the sorted
+    /// occupied ones instead of growing the image. None when a coordinate goes over u64
+    fn expand(coordinates: &[i64], factor: u64) -> Option<Vec<u64>> {
+        let mut occupied = coordinates.to_vec();
+        occupied.sort_unstable();
+        occupied.dedup();
+        coordinates
+            .iter()
+            .map(|coordinate| {
+                let nb_occupied_before = occupied.partition_point(|other| other < coordinate);
+                let nb_empty_before = *coordinate as u64 - nb_occupied_before as u64;
+                nb_empty_before
+                    .checked_mul(factor - 1)?
+                    .checked_add(*coordinate as u64)
+            })
+            .collect()
+    }
+
+    /// The sum of the distances between every pair of values, each sorted value is ahead of the
+    /// ones before it by its value times their number minus their sum. None when the sum goes
+    /// over u64
+    fn sum_of_pair_distances(mut values: Vec<u64>) -> Option<u64> {
+        values.sort_unstable();
+        let mut sum_before: u64 = 0;
+        let mut total: u64 = 0;
+        for (index, value) in values.iter().enumerate() {
+            total = total.checked_add(value.checked_mul(index as u64)? - sum_before)?;
+            sum_before = sum_before.checked_add(*value)?;
+        }
+        Some(total)
+    }
+
+    /// The manhattan distances add up axis by axis
+    fn sum_of_shortest_paths(galaxies: &[Point], factor: u64) -> Result<u64> {
+        if factor == 0 {
+            return Err(Error::new(String::from(
+                "The expansion factor must be at least 1",
+            )));
+        }
+        if let Some(galaxy) = galaxies.iter().find(|galaxy| galaxy.x < 0 || galaxy.y < 0) {
+            return Err(Error::new(format!("Galaxy out of the image at {}", galaxy)));
+        }
+        let xs: Vec<i64> = galaxies.iter().map(|galaxy| galaxy.x).collect();
+        let ys: Vec<i64> = galaxies.iter().map(|galaxy| galaxy.y).collect();
+        let too_far = || {
+            Error::new(format!(
+                "The galaxies expanded by {} are too far apart for u64",
+                factor
+            ))
+        };
+        let xs = expand(&xs, factor).ok_or_else(too_far)?;
+        let ys = expand(&ys, factor).ok_or_else(too_far)?;
+        log::debug!(
+            "{} galaxies expanded by {} to a {}x{} image",
+            galaxies.len(),
+            factor,
+            xs.iter().max().map_or(0, |x| x + 1),
+            ys.iter().max().map_or(0, |y| y + 1)
+        );
+        trace::emit(
+            "expanded",
+            || serde_json::json!({ "factor": factor, "xs": xs, "ys": ys }),
+        );
+        sum_of_pair_distances(xs)
+            .zip(sum_of_pair_distances(ys))
+            .and_then(|(x, y)| x.checked_add(y))
+            .ok_or_else(too_far)
+    }
+
+    /// Solve with another expansion factor than the one of the part, for --expansion
+    pub fn solve_with_expansion(input: &str, factor: u64) -> Result<Answer> {
+        let galaxies = parse_galaxies(input)?;
+        Ok(Answer::from(sum_of_shortest_paths(&galaxies, factor)?))
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let galaxies = parse_galaxies(input)?;
+        Ok(format!("{} galaxies", galaxies.len()))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        parse_galaxies(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let galaxies: Vec<Point> = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        Ok(Answer::from(sum_of_shortest_paths(
+            &galaxies,
+            expansion_factor(part),
+        )?))
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        solve_with_expansion(input, expansion_factor(Part::Part1))
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        solve_with_expansion(input, expansion_factor(Part::Part2))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        const EXAMPLE: &str = "...#......\n\
+                               .......#..\n\
+                               #.........\n\
+                               ..........\n\
+                               ......#...\n\
+                               .#........\n\
+                               .........#\n\
+                               ..........\n\
+                               .......#..\n\
+                               #...#.....";
+
+        #[test]
+        fn test_expansion() {
+            aocstd::init_tests();
+
+            assert_eq!(solve_part1(EXAMPLE).unwrap(), Answer::from(374u64));
+            assert_eq!(
+                solve_with_expansion(EXAMPLE, 10).unwrap(),
+                Answer::from(1030u64)
+            );
+            assert_eq!(
+                solve_with_expansion(EXAMPLE, 100).unwrap(),
+                Answer::from(8410u64)
+            );
+            assert!(solve_with_expansion(EXAMPLE, 0).is_err());
+            assert!(solve_with_expansion("#.#", u64::MAX).is_err());
+            assert_eq!(
+                solve_with_expansion("#.#", u64::MAX - 2).unwrap(),
+                Answer::from(u64::MAX - 1)
+            );
+            // Each coordinate fits but not the distances
+            let far = u64::MAX / 4;
+            assert!(solve_with_expansion("#.#.#", far).is_err());
+            assert!(parse_galaxies("..#\n.x.").is_err());
+
+            let json = serde_json::to_string(&parse_galaxies(EXAMPLE).unwrap()).unwrap();
+            assert_eq!(
+                solve_json(&json, Part::Part1).unwrap(),
+                Answer::from(374u64)
+            );
+        }
+
+        #[test]
+        fn test_against_expanded_image() {
+            aocstd::init_tests();
+
+            // Grow the image for real and compare every pair of galaxies
+            let galaxies = parse_galaxies(EXAMPLE).unwrap();
+            let factor = 3;
+            let grown = |coordinate: i64, empty: &[i64]| {
+                coordinate + empty.iter().filter(|e| **e < coordinate).count() as i64 * (factor - 1)
+            };
+            let empty_xs: Vec<i64> = (0..10)
+                .filter(|x| galaxies.iter().all(|galaxy| galaxy.x != *x))
+                .collect();
+            let empty_ys: Vec<i64> = (0..10)
+                .filter(|y| galaxies.iter().all(|galaxy| galaxy.y != *y))
+                .collect();
+            let grown: Vec<Point> = galaxies
+                .iter()
+                .map(|galaxy| Point::new(grown(galaxy.x, &empty_xs), grown(galaxy.y, &empty_ys)))
+                .collect();
+            let mut expected = 0;
+            for (index, a) in grown.iter().enumerate() {
+                for b in &grown[index + 1..] {
+                    expected += a.manhattan_distance(*b);
+                }
+            }
+            assert_eq!(
+                sum_of_shortest_paths(&galaxies, factor as u64).unwrap(),
+                expected
+            );
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use clap::Parser;
+use day11::cosmic_expansion;
+
+/// The shared options along with the ones of this day only
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(flatten)]
+    cli: aocstd::Cli,
+    /// Factor by which the empty rows and columns grow instead of the one of the part
+    #[arg(long)]
+    expansion: Option<u64>,
+}
+
+fn main() {
+    let args = Args::parse();
+    let cli = args.cli;
+    aocstd::init_logger(&cli);
+    aocstd::init_threads(&cli);
+    aocstd::init_trace(&cli);
+    let input = aocstd::get_input_string(&cli);
+    aocstd::init_profile(&cli);
+
+    if let Some(path) = &cli.dump_parsed {
+        aocstd::dump_parsed(path, cosmic_expansion::parsed(&input, cli.part));
+        return;
+    }
+
+    let answer = match (cli.input_format, cli.part, args.expansion) {
+        (aocstd::InputFormat::Json, part, _) => cosmic_expansion::solve_json(&input, part),
+        (aocstd::InputFormat::Text, _, Some(factor)) => {
+            cosmic_expansion::solve_with_expansion(&input, factor)
+        }
+        (aocstd::InputFormat::Text, aocstd::Part::Part1, None) => {
+            cosmic_expansion::solve_part1(&input)
+        }
+        (aocstd::InputFormat::Text, aocstd::Part::Part2, None) => {
+            cosmic_expansion::solve_part2(&input)
+        }
+    };
+    aocstd::report_answer(cli.part, answer);
+}
@@ -0,0 +1,47 @@
+//! The puzzle input is not in the repository, the benches run on records of the same shape: a
+//! thousand rows of up to 20 springs, about half of them unknown. Part 2 should stay well under a
+//! second with the memoization.
+use criterion::{criterion_group, criterion_main, Criterion};
+use day12::hot_springs;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+fn generate_input(seed: u64, nb_of_records: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut input = String::new();
+    while input.lines().count() < nb_of_records {
+        let length = rng.gen_range(5..=20);
+        let springs: Vec<bool> = (0..length).map(|_| rng.gen_bool(0.5)).collect();
+        let groups: Vec<String> = springs
+            .split(|damaged| !damaged)
+            .filter(|group| !group.is_empty())
+            .map(|group| group.len().to_string())
+            .collect();
+        if groups.is_empty() {
+            continue;
+        }
+        let springs: String = springs
+            .iter()
+            .map(|damaged| match (rng.gen_bool(0.5), damaged) {
+                (true, _) => '?',
+                (false, true) => '#',
+                (false, false) => '.',
+            })
+            .collect();
+        input.push_str(&format!("{} {}\n", springs, groups.join(",")));
+    }
+    input
+}
+
+fn bench_hot_springs(c: &mut Criterion) {
+    let input = generate_input(12, 1000);
+    c.bench_function("day12 part1", |b| {
+        b.iter(|| hot_springs::solve_part1(&input).unwrap())
+    });
+    c.bench_function("day12 part2", |b| {
+        b.iter(|| hot_springs::solve_part2(&input).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_hot_springs);
+criterion_main!(benches);
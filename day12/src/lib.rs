@@ -0,0 +1,246 @@
+pub mod hot_springs {
+    use aocstd::memo::Memo;
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    enum Spring {
+        Operational,
+        Damaged,
+        Unknown,
+    }
+
+    /// The condition of a row of springs and the sizes of its groups of damaged springs
+    /// ex: ???.### 1,1,3
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    struct Record {
+        springs: Vec<Spring>,
+        groups: Vec<usize>,
+    }
+
+    impl Record {
+        fn from_line(line: &str) -> Result<Self> {
+            let (springs, groups) = line
+                .trim()
+                .split_once(' ')
+                .ok_or_else(|| Error::new(format!("No groups after the springs in {:?}", line)))?;
+            let springs = springs
+                .chars()
+                .map(|c| match c {
+                    '.' => Ok(Spring::Operational),
+                    '#' => Ok(Spring::Damaged),
+                    '?' => Ok(Spring::Unknown),
+                    _ => Err(Error::new(format!("Unknown spring {:?} in {:?}", c, line))),
+                })
+                .collect::<Result<Vec<Spring>>>()?;
+            let groups = groups
+                .split(',')
+                .map(|group| {
+                    group
+                        .parse()
+                        .map_err(|_| Error::new(format!("Invalid group {:?} in {:?}", group, line)))
+                })
+                .collect::<Result<Vec<usize>>>()?;
+            Ok(Record { springs, groups })
+        }
+
+        /// The record copied `times` times, the copies of the springs joined by an unknown one
+        fn unfold(&self, times: usize) -> Record {
+            let mut springs = Vec::with_capacity((self.springs.len() + 1) * times);
+            for copy in 0..times {
+                if copy > 0 {
+                    springs.push(Spring::Unknown);
+                }
+                springs.extend(&self.springs);
+            }
+            Record {
+                springs,
+                groups: self.groups.repeat(times),
+            }
+        }
+
+        /// Number of ways to replace the unknown springs that match the groups
+        fn arrangements(&self) -> u64 {
+            let mut memo = Memo::new();
+            let count = arrangements_from(&mut memo, &self.springs, &self.groups, 0, 0);
+            log::debug!(
+                "{} arrangements, {} states cached, {} cache hits",
+                count,
+                memo.len(),
+                memo.hits()
+            );
+            count
+        }
+    }
+
+    /// Number of arrangements of the springs from `spring` on with the groups from `group` on.
+    /// The first spring is either operational, or starts the next group which must then fit
+    /// with an operational spring (or the end) right after it
+    fn arrangements_from(
+        memo: &mut Memo<(usize, usize), u64>,
+        springs: &[Spring],
+        groups: &[usize],
+        spring: usize,
+        group: usize,
+    ) -> u64 {
+        memo.get((spring, group), |memo, (spring, group)| {
+            if spring >= springs.len() {
+                return (group == groups.len()) as u64;
+            }
+            let mut count = 0;
+            if springs[spring] != Spring::Damaged {
+                count += arrangements_from(memo, springs, groups, spring + 1, group);
+            }
+            if let Some(size) = groups.get(group) {
+                let end = spring + size;
+                let fits = end <= springs.len()
+                    && !springs[spring..end].contains(&Spring::Operational)
+                    && springs.get(end) != Some(&Spring::Damaged);
+                if fits {
+                    count += arrangements_from(memo, springs, groups, end + 1, group + 1);
+                }
+            }
+            count
+        })
+    }
+
+    fn parse_records(input: &str) -> Result<Vec<Record>> {
+        aocstd::input::lines(input)
+            .filter(|line| !line.trim().is_empty())
+            .map(Record::from_line)
+            .collect()
+    }
+
+    /// The records are unfolded five times in part 2
+    fn sum_of_arrangements(records: &[Record], part: Part) -> u64 {
+        records
+            .iter()
+            .map(|record| {
+                let record = match part {
+                    Part::Part1 => record.clone(),
+                    Part::Part2 => record.unfold(5),
+                };
+                let count = record.arrangements();
+                trace::emit(
+                    "record",
+                    || serde_json::json!({ "groups": record.groups, "arrangements": count }),
+                );
+                count
+            })
+            .sum()
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let records = parse_records(input)?;
+        let nb_of_unknowns = records
+            .iter()
+            .flat_map(|record| &record.springs)
+            .filter(|spring| **spring == Spring::Unknown)
+            .count();
+        Ok(format!(
+            "{} records, {} unknown springs",
+            records.len(),
+            nb_of_unknowns
+        ))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        parse_records(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let records: Vec<Record> = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        Ok(Answer::from(sum_of_arrangements(&records, part)))
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        let records = parse_records(input)?;
+        Ok(Answer::from(sum_of_arrangements(&records, Part::Part1)))
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        let records = parse_records(input)?;
+        Ok(Answer::from(sum_of_arrangements(&records, Part::Part2)))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        const EXAMPLE: &str = "???.### 1,1,3\n\
+                               .??..??...?##. 1,1,3\n\
+                               ?#?#?#?#?#?#?#? 1,3,1,6\n\
+                               ????.#...#... 4,1,1\n\
+                               ????.######..#####. 1,6,5\n\
+                               ?###???????? 3,2,1";
+
+        #[test]
+        fn test_arrangements() {
+            aocstd::init_tests();
+
+            let records = parse_records(EXAMPLE).unwrap();
+            let counts: Vec<u64> = records.iter().map(Record::arrangements).collect();
+            assert_eq!(counts, vec![1, 4, 1, 1, 4, 10]);
+            let unfolded: Vec<u64> = records
+                .iter()
+                .map(|record| record.unfold(5).arrangements())
+                .collect();
+            assert_eq!(unfolded, vec![1, 16384, 1, 16, 2500, 506250]);
+            assert_eq!(solve_part1(EXAMPLE).unwrap(), Answer::from(21u64));
+            assert_eq!(solve_part2(EXAMPLE).unwrap(), Answer::from(525152u64));
+
+            let unfolded = Record::from_line(".# 1").unwrap().unfold(5);
+            assert_eq!(
+                unfolded,
+                Record::from_line(".#?.#?.#?.#?.# 1,1,1,1,1").unwrap()
+            );
+            assert!(Record::from_line("??? 1,x").is_err());
+            assert!(Record::from_line("?!? 1").is_err());
+        }
+
+        #[test]
+        fn test_against_brute_force() {
+            aocstd::init_tests();
+
+            // Try every replacement of the unknown springs of small records
+            let brute_force = |record: &Record| {
+                let unknowns: Vec<usize> = (0..record.springs.len())
+                    .filter(|index| record.springs[*index] == Spring::Unknown)
+                    .collect();
+                (0u32..1 << unknowns.len())
+                    .filter(|replacement| {
+                        let mut springs = record.springs.clone();
+                        for (bit, index) in unknowns.iter().enumerate() {
+                            springs[*index] = if replacement & (1 << bit) != 0 {
+                                Spring::Damaged
+                            } else {
+                                Spring::Operational
+                            };
+                        }
+                        let groups: Vec<usize> = springs
+                            .split(|spring| *spring == Spring::Operational)
+                            .map(|group| group.len())
+                            .filter(|size| *size > 0)
+                            .collect();
+                        groups == record.groups
+                    })
+                    .count() as u64
+            };
+            for line in [
+                "???.### 1,1,3",
+                "?###???????? 3,2,1",
+                "?????????? 1,1,1",
+                "#?#?#? 1,1",
+                "?.?.? 2",
+                "???????????? 2,1,3",
+            ] {
+                let record = Record::from_line(line).unwrap();
+                assert_eq!(record.arrangements(), brute_force(&record), "{}", line);
+            }
+        }
+    }
+}
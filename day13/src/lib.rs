@@ -0,0 +1,165 @@
+pub mod point_of_incidence {
+    use aocstd::grid::Grid;
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::Serialize;
+
+    /// A pattern of ash (false) and rocks (true), the patterns are separated by blank lines
+    /// ex: #.##..##.
+    type Pattern = Grid<bool>;
+
+    fn parse_patterns(input: &str) -> Result<Vec<Pattern>> {
+        aocstd::input::sections(input)
+            .map(|section| {
+                if let Some(c) = section.chars().find(|c| !".#\r\n".contains(*c)) {
+                    return Err(Error::new(format!(
+                        "Unknown character {:?} in a pattern",
+                        c
+                    )));
+                }
+                Ok(Grid::from_lines(section, false, |c| c == '#'))
+            })
+            .collect()
+    }
+
+    /// Number of cells that differ between two rows
+    fn differences(pattern: &Pattern, a: usize, b: usize) -> usize {
+        match (pattern.row(a), pattern.row(b)) {
+            (Some(a), Some(b)) => a.iter().zip(b).filter(|(a, b)| a != b).count(),
+            _ => 0,
+        }
+    }
+
+    /// The number of rows above the horizontal line that reflects the pattern with exactly
+    /// `smudges` cells differing. The rows past an edge of the pattern do not count
+    fn horizontal_reflection(pattern: &Pattern, smudges: usize) -> Option<usize> {
+        (1..pattern.height()).find(|line| {
+            let mut nb_of_differences = 0;
+            for offset in 0..(*line).min(pattern.height() - line) {
+                nb_of_differences += differences(pattern, line - 1 - offset, line + offset);
+                if nb_of_differences > smudges {
+                    return false;
+                }
+            }
+            nb_of_differences == smudges
+        })
+    }
+
+    /// The rows above a horizontal line times 100, or the columns left of a vertical line
+    /// which are the rows of the transposed pattern
+    fn summarize(pattern: &Pattern, smudges: usize) -> Result<u64> {
+        if let Some(rows) = horizontal_reflection(pattern, smudges) {
+            log::debug!("Horizontal reflection below row {}", rows);
+            return Ok(100 * rows as u64);
+        }
+        let mut transposed = pattern.clone();
+        transposed.transpose();
+        if let Some(columns) = horizontal_reflection(&transposed, smudges) {
+            log::debug!("Vertical reflection after column {}", columns);
+            return Ok(columns as u64);
+        }
+        Err(Error::new(format!(
+            "No reflection with {} smudges in a {}x{} pattern",
+            smudges,
+            pattern.width(),
+            pattern.height()
+        )))
+    }
+
+    /// In part 2 every mirror has exactly one smudge
+    fn sum_of_summaries(patterns: &[Pattern], part: Part) -> Result<u64> {
+        let smudges = match part {
+            Part::Part1 => 0,
+            Part::Part2 => 1,
+        };
+        let mut total = 0;
+        for (index, pattern) in patterns.iter().enumerate() {
+            let summary = summarize(pattern, smudges)?;
+            trace::emit(
+                "pattern",
+                || serde_json::json!({ "pattern": index, "summary": summary }),
+            );
+            total += summary;
+        }
+        Ok(total)
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let patterns = parse_patterns(input)?;
+        Ok(format!("{} patterns", patterns.len()))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        parse_patterns(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let patterns: Vec<Pattern> = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        for pattern in &patterns {
+            pattern.check_size()?;
+        }
+        Ok(Answer::from(sum_of_summaries(&patterns, part)?))
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        let patterns = parse_patterns(input)?;
+        Ok(Answer::from(sum_of_summaries(&patterns, Part::Part1)?))
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        let patterns = parse_patterns(input)?;
+        Ok(Answer::from(sum_of_summaries(&patterns, Part::Part2)?))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        const EXAMPLE: &str = "#.##..##.\n\
+                               ..#.##.#.\n\
+                               ##......#\n\
+                               ##......#\n\
+                               ..#.##.#.\n\
+                               ..##..##.\n\
+                               #.#.##.#.\n\
+                               \n\
+                               #...##..#\n\
+                               #....#..#\n\
+                               ..##..###\n\
+                               #####.##.\n\
+                               #####.##.\n\
+                               ..##..###\n\
+                               #....#..#\n";
+
+        #[test]
+        fn test_reflections() {
+            aocstd::init_tests();
+
+            let patterns = parse_patterns(EXAMPLE).unwrap();
+            assert_eq!(patterns.len(), 2);
+            let summaries = |smudges| -> Vec<u64> {
+                patterns
+                    .iter()
+                    .map(|pattern| summarize(pattern, smudges).unwrap())
+                    .collect()
+            };
+            assert_eq!(summaries(0), vec![5, 400]);
+            assert_eq!(summaries(1), vec![300, 100]);
+            assert_eq!(solve_part1(EXAMPLE).unwrap(), Answer::from(405u64));
+            assert_eq!(solve_part2(EXAMPLE).unwrap(), Answer::from(400u64));
+
+            let json = serde_json::to_string(&patterns).unwrap();
+            assert_eq!(
+                solve_json(&json, Part::Part2).unwrap(),
+                Answer::from(400u64)
+            );
+            assert!(parse_patterns("#.\n.x").is_err());
+            // No line reflects a single row or a row of different cells
+            assert!(solve_part1("#.#\n").is_err());
+            assert!(solve_part1("#.\n##\n").is_err());
+        }
+    }
+}
@@ -0,0 +1,254 @@
+pub mod parabolic_reflector_dish {
+    use aocstd::geometry::{Direction, Point};
+    use aocstd::grid::Grid;
+    use aocstd::viz::{self, Color, Frame, Style};
+    use aocstd::{cycle, trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashSet;
+
+    const NB_OF_SPIN_CYCLES: u64 = 1_000_000_000;
+
+    /// The animation of part 2 stops at the first repeated platform, or after this many cycles
+    const MAX_ANIMATED_CYCLES: usize = 200;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    enum Rock {
+        Empty,
+        Rounded,
+        Cube,
+    }
+
+    /// The rounded rocks (O) roll when the platform is tilted, the cube rocks (#) stay in place
+    type Platform = Grid<Rock>;
+
+    fn parse_platform(input: &str) -> Result<Platform> {
+        if let Some(c) = input.chars().find(|c| !".O#\r\n".contains(*c)) {
+            return Err(Error::new(format!("Unknown rock {:?} on the platform", c)));
+        }
+        let input = input.trim_end_matches(['\n', '\r']);
+        Ok(Grid::from_lines(input, Rock::Empty, |c| match c {
+            'O' => Rock::Rounded,
+            '#' => Rock::Cube,
+            _ => Rock::Empty,
+        }))
+    }
+
+    /// Roll every rounded rock to the North until it hits the edge, a cube rock or another
+    /// rounded rock. Each column is scanned from the North keeping the first free row
+    fn tilt_north(platform: &mut Platform) {
+        for x in 0..platform.width() as i64 {
+            let mut free = 0;
+            for y in 0..platform.height() as i64 {
+                let position = Point::new(x, y);
+                match platform[position] {
+                    Rock::Cube => free = y + 1,
+                    Rock::Rounded => {
+                        if free != y {
+                            platform[Point::new(x, free)] = Rock::Rounded;
+                            platform[position] = Rock::Empty;
+                        }
+                        free += 1;
+                    }
+                    Rock::Empty => {}
+                }
+            }
+        }
+    }
+
+    /// Tilt North, West, South then East. Turning the platform a quarter clockwise brings the
+    /// next side to the North, so only the North tilt is needed and four turns restore it
+    fn spin_cycle(platform: &Platform) -> Platform {
+        let mut platform = platform.clone();
+        for _ in 0..4 {
+            tilt_north(&mut platform);
+            platform.rotate_clockwise();
+        }
+        platform
+    }
+
+    /// Each rounded rock weighs the number of rows from it to the South edge, its own included
+    fn north_load(platform: &Platform) -> u64 {
+        platform
+            .iter()
+            .filter(|(_, rock)| **rock == Rock::Rounded)
+            .map(|(position, _)| platform.height() as u64 - position.y as u64)
+            .sum()
+    }
+
+    fn solve(platform: &Platform, part: Part) -> u64 {
+        let platform = match part {
+            Part::Part1 => {
+                let mut platform = platform.clone();
+                tilt_north(&mut platform);
+                platform
+            }
+            // The platform starts repeating itself long before the billionth cycle
+            Part::Part2 => cycle::fast_forward(
+                platform.clone(),
+                NB_OF_SPIN_CYCLES,
+                spin_cycle,
+                |platform| platform.clone(),
+            ),
+        };
+        let load = north_load(&platform);
+        log::debug!("North load of {} after the tilts", load);
+        trace::emit("load", || serde_json::json!({ "load": load }));
+        load
+    }
+
+    /// Move the rounded rocks that can by a single row to the North, false when none could
+    fn roll_one_row(platform: &mut Platform) -> bool {
+        let mut rolled = false;
+        for y in 1..platform.height() as i64 {
+            for x in 0..platform.width() as i64 {
+                let position = Point::new(x, y);
+                let north = position + Direction::North;
+                if platform[position] == Rock::Rounded && platform[north] == Rock::Empty {
+                    platform[north] = Rock::Rounded;
+                    platform[position] = Rock::Empty;
+                    rolled = true;
+                }
+            }
+        }
+        rolled
+    }
+
+    fn draw(platform: &Platform) -> Frame {
+        let mut frame = Frame::new(platform.width(), platform.height());
+        for (position, rock) in platform.iter() {
+            let (character, style) = match rock {
+                Rock::Empty => ('.', Style::fg(Color::GRAY)),
+                Rock::Rounded => ('O', Style::fg(Color::YELLOW).bold()),
+                Rock::Cube => ('#', Style::fg(Color::BLUE)),
+            };
+            frame.set(position.x as usize, position.y as usize, character, style);
+        }
+        frame
+    }
+
+    /// Part 1 rolls the rocks one row at a time to the North, part 2 shows the platform after
+    /// each spin cycle until it repeats
+    pub fn frames(input: &str, part: Part) -> Result<Vec<Frame>> {
+        let mut platform = parse_platform(input)?;
+        let mut frames = vec![draw(&platform)];
+        match part {
+            Part::Part1 => {
+                while roll_one_row(&mut platform) {
+                    frames.push(draw(&platform));
+                }
+            }
+            Part::Part2 => {
+                let mut seen = HashSet::new();
+                while seen.insert(platform.clone()) && frames.len() <= MAX_ANIMATED_CYCLES {
+                    platform = spin_cycle(&platform);
+                    frames.push(draw(&platform));
+                }
+            }
+        }
+        Ok(frames)
+    }
+
+    /// The platform at the end of the animation of the part
+    pub fn visualize(input: &str, part: Part) -> Result<String> {
+        let frames = frames(input, part)?;
+        viz::terminal_string(&frames[frames.len() - 1..])
+    }
+
+    pub fn render_svg(input: &str, part: Part) -> Result<String> {
+        viz::svg_string(&frames(input, part)?)
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let platform = parse_platform(input)?;
+        let count = |kind| platform.iter().filter(|(_, rock)| **rock == kind).count();
+        Ok(format!(
+            "{}x{} platform, {} rounded rocks, {} cube rocks",
+            platform.width(),
+            platform.height(),
+            count(Rock::Rounded),
+            count(Rock::Cube)
+        ))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        parse_platform(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let platform: Platform = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        platform.check_size()?;
+        Ok(Answer::from(solve(&platform, part)))
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        Ok(Answer::from(solve(&parse_platform(input)?, Part::Part1)))
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        Ok(Answer::from(solve(&parse_platform(input)?, Part::Part2)))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        const EXAMPLE: &str = "O....#....\n\
+                               O.OO#....#\n\
+                               .....##...\n\
+                               OO.#O....O\n\
+                               .O.....O#.\n\
+                               O.#..O.#.#\n\
+                               ..O..#O..O\n\
+                               .......O..\n\
+                               #....###..\n\
+                               #OO..#....\n";
+
+        #[test]
+        fn test_tilts() {
+            aocstd::init_tests();
+
+            let platform = parse_platform(EXAMPLE).unwrap();
+            let mut tilted = platform.clone();
+            tilt_north(&mut tilted);
+            assert_eq!(north_load(&tilted), 136);
+            // Rolling a row at a time ends with the same platform
+            let mut rolled = platform.clone();
+            while roll_one_row(&mut rolled) {}
+            assert_eq!(rolled, tilted);
+
+            let after_one_cycle = parse_platform(
+                ".....#....\n\
+                 ....#...O#\n\
+                 ...OO##...\n\
+                 .OO#......\n\
+                 .....OOO#.\n\
+                 .O#...O#.#\n\
+                 ....O#....\n\
+                 ......OOOO\n\
+                 #...O###..\n\
+                 #..OO#....",
+            )
+            .unwrap();
+            assert_eq!(spin_cycle(&platform), after_one_cycle);
+            assert!(parse_platform("O.\n.x").is_err());
+        }
+
+        #[test]
+        fn test_spin_cycles() {
+            aocstd::init_tests();
+
+            assert_eq!(solve_part1(EXAMPLE).unwrap(), Answer::from(136u64));
+            assert_eq!(solve_part2(EXAMPLE).unwrap(), Answer::from(64u64));
+
+            let json = serde_json::to_string(&parse_platform(EXAMPLE).unwrap()).unwrap();
+            assert_eq!(solve_json(&json, Part::Part2).unwrap(), Answer::from(64u64));
+            // The example repeats from the third cycle every 7 cycles
+            let frames = frames(EXAMPLE, Part::Part2).unwrap();
+            assert_eq!(frames.len(), 11);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use clap::Parser;
+use day14::parabolic_reflector_dish;
+use std::path::PathBuf;
+
+/// The shared options along with the ones of this day only
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(flatten)]
+    cli: aocstd::Cli,
+    /// Render the platform in the terminal instead of solving
+    #[arg(long, alias = "plot")]
+    visualize: bool,
+    /// Write an SVG rendering of the platform to this file instead of solving
+    #[arg(long)]
+    svg: Option<PathBuf>,
+    /// Write the animation to this file instead of solving, as an animated GIF for a .gif path
+    /// and as numbered PNG files otherwise
+    #[arg(long)]
+    image: Option<PathBuf>,
+}
+
+fn main() {
+    let args = Args::parse();
+    let cli = args.cli;
+    aocstd::init_logger(&cli);
+    aocstd::init_threads(&cli);
+    aocstd::init_trace(&cli);
+    let input = aocstd::get_input_string(&cli);
+    aocstd::init_profile(&cli);
+
+    if let Some(path) = &cli.dump_parsed {
+        aocstd::dump_parsed(path, parabolic_reflector_dish::parsed(&input, cli.part));
+        return;
+    }
+    if args.visualize {
+        aocstd::print_visualization(parabolic_reflector_dish::visualize(&input, cli.part));
+        return;
+    }
+    if let Some(path) = &args.svg {
+        aocstd::write_svg(path, parabolic_reflector_dish::render_svg(&input, cli.part));
+        return;
+    }
+    if let Some(path) = &args.image {
+        aocstd::write_image(path, parabolic_reflector_dish::frames(&input, cli.part));
+        return;
+    }
+
+    let answer = match (cli.input_format, cli.part) {
+        (aocstd::InputFormat::Json, part) => parabolic_reflector_dish::solve_json(&input, part),
+        (aocstd::InputFormat::Text, aocstd::Part::Part1) => {
+            parabolic_reflector_dish::solve_part1(&input)
+        }
+        (aocstd::InputFormat::Text, aocstd::Part::Part2) => {
+            parabolic_reflector_dish::solve_part2(&input)
+        }
+    };
+    aocstd::report_answer(cli.part, answer);
+}
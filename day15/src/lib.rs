@@ -0,0 +1,162 @@
+pub mod lens_library {
+    use aocstd::hash::{holiday_hash, LabelBoxes};
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize};
+
+    /// A step of the initialization sequence, the steps are separated by commas
+    /// ex: rn=1 puts a lens of focal length 1 labeled rn in its box, cm- takes the lens cm out
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    enum Operation {
+        Insert(u8),
+        Remove,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    struct Step {
+        text: String,
+        label: String,
+        operation: Operation,
+    }
+
+    impl Step {
+        fn from_text(text: &str) -> Result<Self> {
+            let (label, operation) = if let Some(label) = text.strip_suffix('-') {
+                (label, Operation::Remove)
+            } else if let Some((label, focal_length)) = text.split_once('=') {
+                let focal_length = focal_length
+                    .parse()
+                    .map_err(|_| Error::new(format!("Invalid focal length in {:?}", text)))?;
+                (label, Operation::Insert(focal_length))
+            } else {
+                return Err(Error::new(format!("No operation in the step {:?}", text)));
+            };
+            Ok(Step {
+                text: text.to_string(),
+                label: label.to_string(),
+                operation,
+            })
+        }
+    }
+
+    /// The newlines are ignored, the puzzle input is a single long line
+    fn parse_steps(input: &str) -> Result<Vec<Step>> {
+        input
+            .trim()
+            .split(',')
+            .map(|step| step.replace(['\n', '\r'], ""))
+            .filter(|step| !step.is_empty())
+            .map(|step| Step::from_text(&step))
+            .collect()
+    }
+
+    fn sum_of_hashes(steps: &[Step]) -> u64 {
+        steps
+            .iter()
+            .map(|step| holiday_hash(&step.text) as u64)
+            .sum()
+    }
+
+    /// Arrange the lenses in their boxes then add up the power of each lens: the number of its
+    /// box times its slot in the box times its focal length, counting from 1
+    fn focusing_power(steps: &[Step]) -> u64 {
+        let mut boxes = LabelBoxes::new();
+        for step in steps {
+            match step.operation {
+                Operation::Insert(focal_length) => boxes.insert(&step.label, focal_length),
+                Operation::Remove => {
+                    boxes.remove(&step.label);
+                }
+            }
+        }
+        boxes
+            .iter()
+            .map(|(box_index, slot, label, focal_length)| {
+                log::debug!(
+                    "Lens {} {} in slot {} of box {}",
+                    label,
+                    focal_length,
+                    slot,
+                    box_index
+                );
+                trace::emit("lens", || {
+                    serde_json::json!({
+                        "label": label,
+                        "box": box_index,
+                        "slot": slot,
+                        "focal_length": focal_length,
+                    })
+                });
+                (box_index as u64 + 1) * (slot as u64 + 1) * *focal_length as u64
+            })
+            .sum()
+    }
+
+    fn solve(steps: &[Step], part: Part) -> u64 {
+        match part {
+            Part::Part1 => sum_of_hashes(steps),
+            Part::Part2 => focusing_power(steps),
+        }
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let steps = parse_steps(input)?;
+        let nb_of_removals = steps
+            .iter()
+            .filter(|step| step.operation == Operation::Remove)
+            .count();
+        Ok(format!(
+            "{} steps, {} removals",
+            steps.len(),
+            nb_of_removals
+        ))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        parse_steps(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let steps: Vec<Step> = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        Ok(Answer::from(solve(&steps, part)))
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        Ok(Answer::from(solve(&parse_steps(input)?, Part::Part1)))
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        Ok(Answer::from(solve(&parse_steps(input)?, Part::Part2)))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        const EXAMPLE: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7\n";
+
+        #[test]
+        fn test_lens_library() {
+            aocstd::init_tests();
+
+            assert_eq!(solve_part1(EXAMPLE).unwrap(), Answer::from(1320u64));
+            assert_eq!(solve_part2(EXAMPLE).unwrap(), Answer::from(145u64));
+            // A newline in the middle of the sequence is ignored
+            assert_eq!(
+                solve_part1("rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=\n6,ot=7").unwrap(),
+                Answer::from(1320u64)
+            );
+
+            let json = serde_json::to_string(&parse_steps(EXAMPLE).unwrap()).unwrap();
+            assert_eq!(
+                solve_json(&json, Part::Part2).unwrap(),
+                Answer::from(145u64)
+            );
+            assert!(Step::from_text("rn").is_err());
+            assert!(Step::from_text("rn=x").is_err());
+        }
+    }
+}
@@ -0,0 +1,220 @@
+pub mod floor_will_be_lava {
+    use aocstd::bitset::BitSet;
+    use aocstd::geometry::{Direction, Point};
+    use aocstd::grid::Grid;
+    use aocstd::{parallel, trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize};
+
+    /// The edge starts tried by each parallel task in part 2
+    const STARTS_PER_CHUNK: u64 = 8;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    enum Tile {
+        Empty,
+        /// The / mirror
+        MirrorUp,
+        /// The \ mirror
+        MirrorDown,
+        /// The | splitter
+        SplitterVertical,
+        /// The - splitter
+        SplitterHorizontal,
+    }
+
+    type Contraption = Grid<Tile>;
+
+    /// A beam entering a tile going in a direction
+    type Beam = (Point, Direction);
+
+    fn parse_contraption(input: &str) -> Result<Contraption> {
+        if let Some(c) = input.chars().find(|c| !"./\\|-\r\n".contains(*c)) {
+            return Err(Error::new(format!(
+                "Unknown tile {:?} in the contraption",
+                c
+            )));
+        }
+        let input = input.trim_end_matches(['\n', '\r']);
+        Ok(Grid::from_lines(input, Tile::Empty, |c| match c {
+            '/' => Tile::MirrorUp,
+            '\\' => Tile::MirrorDown,
+            '|' => Tile::SplitterVertical,
+            '-' => Tile::SplitterHorizontal,
+            _ => Tile::Empty,
+        }))
+    }
+
+    impl Tile {
+        /// The directions the beam leaves the tile towards
+        fn deflect(&self, direction: Direction) -> Vec<Direction> {
+            use Direction::*;
+            match (self, direction) {
+                (Tile::MirrorUp, East) => vec![North],
+                (Tile::MirrorUp, North) => vec![East],
+                (Tile::MirrorUp, West) => vec![South],
+                (Tile::MirrorUp, South) => vec![West],
+                (Tile::MirrorDown, East) => vec![South],
+                (Tile::MirrorDown, South) => vec![East],
+                (Tile::MirrorDown, West) => vec![North],
+                (Tile::MirrorDown, North) => vec![West],
+                (Tile::SplitterVertical, East | West) => vec![North, South],
+                (Tile::SplitterHorizontal, North | South) => vec![East, West],
+                _ => vec![direction],
+            }
+        }
+    }
+
+    fn cardinal_index(direction: Direction) -> usize {
+        Direction::CARDINALS
+            .iter()
+            .position(|cardinal| *cardinal == direction)
+            .unwrap_or(0)
+    }
+
+    /// Number of tiles a beam goes through. A beam entering a tile in the same direction as an
+    /// earlier one follows the same path, so it is dropped, which also ends the loops
+    fn energized(contraption: &Contraption, start: Beam) -> usize {
+        let width = contraption.width();
+        let mut seen = BitSet::with_capacity(contraption.width() * contraption.height() * 4);
+        let mut energized = BitSet::with_capacity(contraption.width() * contraption.height());
+        let mut beams = vec![start];
+        while let Some((position, direction)) = beams.pop() {
+            let Some(tile) = contraption.get(position) else {
+                continue;
+            };
+            let cell = position.y as usize * width + position.x as usize;
+            if !seen.insert(cell * 4 + cardinal_index(direction)) {
+                continue;
+            }
+            energized.insert(cell);
+            for next in tile.deflect(direction) {
+                beams.push((position + next, next));
+            }
+        }
+        energized.len()
+    }
+
+    /// Every beam entering the contraption from one of its edges
+    fn edge_starts(contraption: &Contraption) -> Vec<Beam> {
+        let (width, height) = (contraption.width() as i64, contraption.height() as i64);
+        let mut starts = Vec::new();
+        for x in 0..width {
+            starts.push((Point::new(x, 0), Direction::South));
+            starts.push((Point::new(x, height - 1), Direction::North));
+        }
+        for y in 0..height {
+            starts.push((Point::new(0, y), Direction::East));
+            starts.push((Point::new(width - 1, y), Direction::West));
+        }
+        starts
+    }
+
+    /// The beam enters from the top left corner going East in part 1, part 2 looks for the
+    /// best edge to enter from, the starts being tried in parallel
+    fn solve(contraption: &Contraption, part: Part) -> usize {
+        match part {
+            Part::Part1 => energized(contraption, (Point::ORIGIN, Direction::East)),
+            Part::Part2 => {
+                let starts = edge_starts(contraption);
+                parallel::reduce_chunks(
+                    std::iter::once(0..starts.len() as u64),
+                    STARTS_PER_CHUNK,
+                    |chunk| {
+                        chunk
+                            .map(|index| {
+                                let start = starts[index as usize];
+                                let count = energized(contraption, start);
+                                log::debug!("{} tiles from {} {:?}", count, start.0, start.1);
+                                trace::emit("start", || {
+                                    serde_json::json!({
+                                        "position": start.0,
+                                        "direction": start.1,
+                                        "energized": count,
+                                    })
+                                });
+                                count
+                            })
+                            .max()
+                            .unwrap_or(0)
+                    },
+                    usize::max,
+                )
+                .unwrap_or(0)
+            }
+        }
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let contraption = parse_contraption(input)?;
+        let nb_of_devices = contraption
+            .iter()
+            .filter(|(_, tile)| **tile != Tile::Empty)
+            .count();
+        Ok(format!(
+            "{}x{} contraption, {} mirrors and splitters",
+            contraption.width(),
+            contraption.height(),
+            nb_of_devices
+        ))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        parse_contraption(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let contraption: Contraption = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        contraption.check_size()?;
+        Ok(Answer::from(solve(&contraption, part) as u64))
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        let contraption = parse_contraption(input)?;
+        Ok(Answer::from(solve(&contraption, Part::Part1) as u64))
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        let contraption = parse_contraption(input)?;
+        Ok(Answer::from(solve(&contraption, Part::Part2) as u64))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        const EXAMPLE: &str = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....
+";
+
+        #[test]
+        fn test_beams() {
+            aocstd::init_tests();
+
+            assert_eq!(solve_part1(EXAMPLE).unwrap(), Answer::from(46u64));
+            assert_eq!(solve_part2(EXAMPLE).unwrap(), Answer::from(51u64));
+            let contraption = parse_contraption(EXAMPLE).unwrap();
+            assert_eq!(
+                energized(&contraption, (Point::new(3, 0), Direction::South)),
+                51
+            );
+            // The beam going around in a loop stops once back to the start
+            let looping = parse_contraption("/.\\\n...\n\\./").unwrap();
+            assert_eq!(energized(&looping, (Point::new(1, 0), Direction::East)), 8);
+
+            let json = serde_json::to_string(&contraption).unwrap();
+            assert_eq!(solve_json(&json, Part::Part2).unwrap(), Answer::from(51u64));
+            assert!(parse_contraption("./\n.x").is_err());
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! The puzzle input is not in the repository, the benches run on a city of the same size: 141x141
+//! blocks of random heat losses. Part 2 is the slowest, the ultra crucible has more than three
+//! times as many states.
+use criterion::{criterion_group, criterion_main, Criterion};
+use day17::clumsy_crucible;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+fn generate_input(seed: u64, size: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut input = String::new();
+    for _ in 0..size {
+        let row: String = (0..size)
+            .map(|_| char::from(b'0' + rng.gen_range(1..=9)))
+            .collect();
+        input.push_str(&row);
+        input.push('\n');
+    }
+    input
+}
+
+fn bench_clumsy_crucible(c: &mut Criterion) {
+    let input = generate_input(17, 141);
+    let mut group = c.benchmark_group("day17");
+    group.sample_size(10);
+    group.bench_function("part1", |b| {
+        b.iter(|| clumsy_crucible::solve_part1(&input).unwrap())
+    });
+    group.bench_function("part2", |b| {
+        b.iter(|| clumsy_crucible::solve_part2(&input).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_clumsy_crucible);
+criterion_main!(benches);
@@ -0,0 +1,203 @@
+pub mod clumsy_crucible {
+    use aocstd::geometry::{Direction, Point};
+    use aocstd::grid::Grid;
+    use aocstd::pathfinding::{self, Parents};
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::Serialize;
+
+    /// The heat lost in each city block, a digit per block
+    type City = Grid<u8>;
+
+    /// A crucible must move at least `min_run` blocks in a straight line before it can turn or
+    /// stop, and at most `max_run`
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum CrucibleKind {
+        Normal,
+        Ultra,
+    }
+
+    impl CrucibleKind {
+        fn of(part: Part) -> Self {
+            match part {
+                Part::Part1 => CrucibleKind::Normal,
+                Part::Part2 => CrucibleKind::Ultra,
+            }
+        }
+
+        fn min_run(&self) -> u8 {
+            match self {
+                CrucibleKind::Normal => 1,
+                CrucibleKind::Ultra => 4,
+            }
+        }
+
+        fn max_run(&self) -> u8 {
+            match self {
+                CrucibleKind::Normal => 3,
+                CrucibleKind::Ultra => 10,
+            }
+        }
+    }
+
+    /// The state of the crucible: where it is, where it is heading and how many blocks it has
+    /// moved in that direction
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct Crucible {
+        position: Point,
+        direction: Direction,
+        run: u8,
+    }
+
+    fn parse_city(input: &str) -> Result<City> {
+        if let Some(c) = input
+            .chars()
+            .find(|c| !matches!(c, '1'..='9' | '\r' | '\n'))
+        {
+            return Err(Error::new(format!("Invalid heat loss {:?} in the city", c)));
+        }
+        let input = input.trim_end_matches(['\n', '\r']);
+        let city = Grid::from_lines(input, 0, |c| c.to_digit(10).unwrap_or(0) as u8);
+        if city.iter().any(|(_, heat_loss)| *heat_loss == 0) {
+            return Err(Error::new(String::from(
+                "The rows of the city differ in length",
+            )));
+        }
+        Ok(city)
+    }
+
+    /// Going straight on, or turning left or right when the run is long enough
+    fn moves(city: &City, kind: CrucibleKind, crucible: &Crucible) -> Vec<(Crucible, u64)> {
+        let mut moves = Vec::with_capacity(3);
+        let mut try_move = |direction: Direction, run: u8| {
+            let position = crucible.position + direction;
+            if let Some(heat_loss) = city.get(position) {
+                let next = Crucible {
+                    position,
+                    direction,
+                    run,
+                };
+                moves.push((next, *heat_loss as u64));
+            }
+        };
+        if crucible.run < kind.max_run() {
+            try_move(crucible.direction, crucible.run + 1);
+        }
+        if crucible.run >= kind.min_run() {
+            try_move(crucible.direction.turn_left(), 1);
+            try_move(crucible.direction.turn_right(), 1);
+        }
+        moves
+    }
+
+    /// The least heat lost from the top left block to the bottom right one, the heat of the
+    /// first block is not lost. Every block loses at least 1 so the manhattan distance to the
+    /// end guides the search
+    fn least_heat_loss(city: &City, kind: CrucibleKind) -> Result<u64> {
+        let end = Point::new(city.width() as i64 - 1, city.height() as i64 - 1);
+        let starts = [Direction::East, Direction::South].map(|direction| Crucible {
+            position: Point::ORIGIN,
+            direction,
+            run: 0,
+        });
+        let search = pathfinding::astar(
+            starts,
+            |crucible| moves(city, kind, crucible),
+            |crucible| crucible.position.manhattan_distance(end),
+            |crucible| crucible.position == end && crucible.run >= kind.min_run(),
+            Parents::Skip,
+        );
+        let heat_loss = search
+            .goal_distance()
+            .ok_or_else(|| Error::new(format!("The {:?} crucible cannot reach {}", kind, end)))?;
+        log::debug!(
+            "{} heat lost by the {:?} crucible, {} states settled",
+            heat_loss,
+            kind,
+            search.distances.len()
+        );
+        trace::emit("search", || {
+            serde_json::json!({
+                "crucible": format!("{:?}", kind),
+                "heat_loss": heat_loss,
+                "settled": search.distances.len(),
+            })
+        });
+        Ok(heat_loss)
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let city = parse_city(input)?;
+        Ok(format!("{}x{} city blocks", city.width(), city.height()))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        parse_city(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let city: City = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        city.check_size()?;
+        Ok(Answer::from(least_heat_loss(
+            &city,
+            CrucibleKind::of(part),
+        )?))
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        let city = parse_city(input)?;
+        Ok(Answer::from(least_heat_loss(&city, CrucibleKind::Normal)?))
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        let city = parse_city(input)?;
+        Ok(Answer::from(least_heat_loss(&city, CrucibleKind::Ultra)?))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        const EXAMPLE: &str = "2413432311323\n\
+                               3215453535623\n\
+                               3255245654254\n\
+                               3446585845452\n\
+                               4546657867536\n\
+                               1438598798454\n\
+                               4457876987766\n\
+                               3637877979653\n\
+                               4654967986887\n\
+                               4564679986453\n\
+                               1224686865563\n\
+                               2546548887735\n\
+                               4322674655533\n";
+
+        #[test]
+        fn test_crucibles() {
+            aocstd::init_tests();
+
+            assert_eq!(solve_part1(EXAMPLE).unwrap(), Answer::from(102u64));
+            assert_eq!(solve_part2(EXAMPLE).unwrap(), Answer::from(94u64));
+            // The ultra crucible goes the long way to be allowed to stop
+            let unfortunate = "111111111111\n\
+                               999999999991\n\
+                               999999999991\n\
+                               999999999991\n\
+                               999999999991";
+            assert_eq!(solve_part2(unfortunate).unwrap(), Answer::from(71u64));
+
+            let json = serde_json::to_string(&parse_city(EXAMPLE).unwrap()).unwrap();
+            assert_eq!(
+                solve_json(&json, Part::Part1).unwrap(),
+                Answer::from(102u64)
+            );
+            assert!(parse_city("12\n30").is_err());
+            assert!(parse_city("12\n3").is_err());
+            // Too small for the ultra crucible to stop at the end
+            assert!(solve_part2("123\n456").is_err());
+        }
+    }
+}
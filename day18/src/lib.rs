@@ -0,0 +1,194 @@
+pub mod lavaduct_lagoon {
+    use aocstd::geometry::{Direction, Point};
+    use aocstd::math;
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize};
+
+    /// A digging instruction: a direction and a number of meters
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    struct Instruction {
+        direction: Direction,
+        meters: i64,
+    }
+
+    /// A line of the dig plan, the color actually hides the instruction of part 2
+    /// ex: R 6 (#70c710) digs 6 meters right in part 1 and 461937 meters right in part 2
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    struct PlanLine {
+        instruction: Instruction,
+        color: Instruction,
+    }
+
+    impl PlanLine {
+        fn from_line(line: &str) -> Result<Self> {
+            let invalid = || Error::new(format!("Invalid dig plan line {:?}", line));
+            let mut fields = line.split_whitespace();
+            let (Some(direction), Some(meters), Some(color), None) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                return Err(invalid());
+            };
+            let direction = match direction {
+                "U" => Direction::North,
+                "R" => Direction::East,
+                "D" => Direction::South,
+                "L" => Direction::West,
+                _ => return Err(invalid()),
+            };
+            let meters = meters.parse().map_err(|_| invalid())?;
+            let hex = color
+                .strip_prefix("(#")
+                .and_then(|color| color.strip_suffix(')'))
+                .filter(|hex| hex.len() == 6 && hex.is_ascii())
+                .ok_or_else(invalid)?;
+            // 5 digits of meters then the direction: 0 means R, 1 D, 2 L and 3 U
+            let color = Instruction {
+                direction: match &hex[5..] {
+                    "0" => Direction::East,
+                    "1" => Direction::South,
+                    "2" => Direction::West,
+                    "3" => Direction::North,
+                    _ => return Err(invalid()),
+                },
+                meters: i64::from_str_radix(&hex[..5], 16).map_err(|_| invalid())?,
+            };
+            Ok(PlanLine {
+                instruction: Instruction { direction, meters },
+                color,
+            })
+        }
+
+        fn instruction(&self, part: Part) -> Instruction {
+            match part {
+                Part::Part1 => self.instruction,
+                Part::Part2 => self.color,
+            }
+        }
+    }
+
+    fn parse_plan(input: &str) -> Result<Vec<PlanLine>> {
+        aocstd::input::lines(input)
+            .filter(|line| !line.trim().is_empty())
+            .map(PlanLine::from_line)
+            .collect()
+    }
+
+    /// The cubic meters of the lagoon: the trench and the inside it encloses. The trench goes
+    /// through the centers of its blocks, so Pick's theorem gives the blocks inside from the
+    /// shoelace area, and the trench adds its own blocks. Only the corners are kept so millions
+    /// of meters cost nothing
+    fn lagoon_volume(instructions: &[Instruction]) -> Result<u64> {
+        let mut position = Point::ORIGIN;
+        let mut corners = Vec::with_capacity(instructions.len());
+        for instruction in instructions {
+            position += instruction.direction.offset() * instruction.meters;
+            corners.push(position);
+        }
+        if position != Point::ORIGIN {
+            return Err(Error::new(format!(
+                "The trench ends at {} instead of its start",
+                position
+            )));
+        }
+        let trench = math::boundary_points(&corners);
+        let inside = math::pick_interior_points(math::double_area(&corners), trench)
+            .ok_or_else(|| Error::new("The trench folds back on itself"))?;
+        log::debug!(
+            "{} corners, {} blocks of trench and {} inside",
+            corners.len(),
+            trench,
+            inside
+        );
+        trace::emit(
+            "lagoon",
+            || serde_json::json!({ "corners": corners.len(), "trench": trench, "inside": inside }),
+        );
+        Ok(trench + inside)
+    }
+
+    fn solve(plan: &[PlanLine], part: Part) -> Result<u64> {
+        let instructions: Vec<Instruction> =
+            plan.iter().map(|line| line.instruction(part)).collect();
+        lagoon_volume(&instructions)
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let plan = parse_plan(input)?;
+        let meters = |part| -> i64 { plan.iter().map(|line| line.instruction(part).meters).sum() };
+        Ok(format!(
+            "{} instructions, {} meters of trench, {} with the colors",
+            plan.len(),
+            meters(Part::Part1),
+            meters(Part::Part2)
+        ))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        parse_plan(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let plan: Vec<PlanLine> = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        Ok(Answer::from(solve(&plan, part)?))
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        Ok(Answer::from(solve(&parse_plan(input)?, Part::Part1)?))
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        Ok(Answer::from(solve(&parse_plan(input)?, Part::Part2)?))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        const EXAMPLE: &str = "R 6 (#70c710)\n\
+                               D 5 (#0dc571)\n\
+                               L 2 (#5713f0)\n\
+                               D 2 (#d2c081)\n\
+                               R 2 (#59c680)\n\
+                               D 2 (#411b91)\n\
+                               L 5 (#8ceee2)\n\
+                               U 2 (#caa173)\n\
+                               L 1 (#1b58a2)\n\
+                               U 2 (#caa171)\n\
+                               R 2 (#7807d2)\n\
+                               U 3 (#a77fa3)\n\
+                               L 2 (#015232)\n\
+                               U 2 (#7a21e3)\n";
+
+        #[test]
+        fn test_lagoon() {
+            aocstd::init_tests();
+
+            assert_eq!(solve_part1(EXAMPLE).unwrap(), Answer::from(62u64));
+            assert_eq!(solve_part2(EXAMPLE).unwrap(), Answer::from(952408144115u64));
+            let line = PlanLine::from_line("R 6 (#70c710)").unwrap();
+            assert_eq!(
+                line.color,
+                Instruction {
+                    direction: Direction::East,
+                    meters: 461937
+                }
+            );
+            // A 3x3 square dug around a single block
+            let square = "R 2 (#000020)\nD 2 (#000021)\nL 2 (#000022)\nU 2 (#000023)";
+            assert_eq!(solve_part1(square).unwrap(), Answer::from(9u64));
+            assert_eq!(solve_part2(square).unwrap(), Answer::from(9u64));
+
+            let json = serde_json::to_string(&parse_plan(EXAMPLE).unwrap()).unwrap();
+            assert_eq!(solve_json(&json, Part::Part1).unwrap(), Answer::from(62u64));
+            assert!(PlanLine::from_line("X 6 (#70c710)").is_err());
+            assert!(PlanLine::from_line("R 6 (#70c714)").is_err());
+            assert!(PlanLine::from_line("R 6").is_err());
+            assert!(solve_part1("R 6 (#70c710)").is_err());
+            assert!(solve_part1("R 2 (#000020)\nL 2 (#000022)").is_err());
+        }
+    }
+}
@@ -0,0 +1,381 @@
+pub mod aplenty {
+    use aocstd::ranges::IntervalSet;
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+    use std::ops::Range;
+
+    /// The ratings go from 1 to 4000 in each category
+    const RATINGS: Range<u64> = 1..4001;
+
+    const FIRST_WORKFLOW: &str = "in";
+
+    /// The four categories of ratings: x, m, a and s
+    const CATEGORIES: &str = "xmas";
+
+    /// The ratings of a machine part by category, in the order of CATEGORIES
+    /// ex: {x=787,m=2655,a=1222,s=2876}
+    type Ratings = [u64; 4];
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    enum Comparison {
+        Less,
+        Greater,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    struct Condition {
+        category: usize,
+        comparison: Comparison,
+        value: u64,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    enum Target {
+        Accept,
+        Reject,
+        Workflow(String),
+    }
+
+    /// A rule sends the part to its target when the condition holds, the last rule of a
+    /// workflow has no condition
+    /// ex: a<2006:qkq
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    struct Rule {
+        condition: Option<Condition>,
+        target: Target,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    struct System {
+        workflows: HashMap<String, Vec<Rule>>,
+        parts: Vec<Ratings>,
+    }
+
+    impl Condition {
+        fn from_text(text: &str) -> Result<Self> {
+            let invalid = || Error::new(format!("Invalid condition {:?}", text));
+            let mut chars = text.chars();
+            let category = chars
+                .next()
+                .and_then(|c| CATEGORIES.find(c))
+                .ok_or_else(invalid)?;
+            let comparison = match chars.next() {
+                Some('<') => Comparison::Less,
+                Some('>') => Comparison::Greater,
+                _ => return Err(invalid()),
+            };
+            let value = chars.as_str().parse().map_err(|_| invalid())?;
+            Ok(Condition {
+                category,
+                comparison,
+                value,
+            })
+        }
+
+        fn holds(&self, ratings: &Ratings) -> bool {
+            match self.comparison {
+                Comparison::Less => ratings[self.category] < self.value,
+                Comparison::Greater => ratings[self.category] > self.value,
+            }
+        }
+
+        /// The ratings of its category for which the condition holds, the values beyond the
+        /// ratings being clamped so a condition can hold for all of them or none
+        fn matching(&self) -> IntervalSet<u64> {
+            let range = match self.comparison {
+                Comparison::Less => RATINGS.start..self.value.clamp(RATINGS.start, RATINGS.end),
+                Comparison::Greater => {
+                    self.value
+                        .saturating_add(1)
+                        .clamp(RATINGS.start, RATINGS.end)..RATINGS.end
+                }
+            };
+            IntervalSet::from_iter([range])
+        }
+    }
+
+    impl Target {
+        fn from_text(text: &str) -> Self {
+            match text {
+                "A" => Target::Accept,
+                "R" => Target::Reject,
+                name => Target::Workflow(name.to_string()),
+            }
+        }
+    }
+
+    /// ex: px{a<2006:qkq,m>2090:A,rfg}
+    fn parse_workflow(line: &str) -> Result<(String, Vec<Rule>)> {
+        let invalid = || Error::new(format!("Invalid workflow {:?}", line));
+        let (name, rules) = line
+            .trim()
+            .strip_suffix('}')
+            .and_then(|line| line.split_once('{'))
+            .ok_or_else(invalid)?;
+        let rules = rules
+            .split(',')
+            .map(|rule| match rule.split_once(':') {
+                Some((condition, target)) => Ok(Rule {
+                    condition: Some(Condition::from_text(condition)?),
+                    target: Target::from_text(target),
+                }),
+                None => Ok(Rule {
+                    condition: None,
+                    target: Target::from_text(rule),
+                }),
+            })
+            .collect::<Result<Vec<Rule>>>()?;
+        if rules.last().is_none_or(|rule| rule.condition.is_some()) {
+            return Err(invalid());
+        }
+        Ok((name.to_string(), rules))
+    }
+
+    /// ex: {x=787,m=2655,a=1222,s=2876}
+    fn parse_ratings(line: &str) -> Result<Ratings> {
+        let invalid = || Error::new(format!("Invalid part ratings {:?}", line));
+        let ratings = line
+            .trim()
+            .strip_prefix('{')
+            .and_then(|line| line.strip_suffix('}'))
+            .ok_or_else(invalid)?;
+        let mut parsed = [None; 4];
+        for rating in ratings.split(',') {
+            let (category, value) = rating.split_once('=').ok_or_else(invalid)?;
+            let category = CATEGORIES.find(category).ok_or_else(invalid)?;
+            parsed[category] = Some(value.parse().map_err(|_| invalid())?);
+        }
+        let mut result = [0; 4];
+        for (rating, parsed) in result.iter_mut().zip(parsed) {
+            *rating = parsed.ok_or_else(invalid)?;
+        }
+        Ok(result)
+    }
+
+    fn parse_system(input: &str) -> Result<System> {
+        let mut sections = aocstd::input::sections(input);
+        let workflows = sections
+            .next()
+            .map(|section| aocstd::input::lines(section).map(parse_workflow).collect())
+            .unwrap_or_else(|| Ok(HashMap::new()))?;
+        let parts = sections
+            .next()
+            .map(|section| aocstd::input::lines(section).map(parse_ratings).collect())
+            .unwrap_or_else(|| Ok(Vec::new()))?;
+        Ok(System { workflows, parts })
+    }
+
+    impl System {
+        fn rules(&self, name: &str) -> Result<&[Rule]> {
+            self.workflows
+                .get(name)
+                .map(Vec::as_slice)
+                .ok_or_else(|| Error::new(format!("Unknown workflow {}", name)))
+        }
+
+        /// Follow the workflows from "in" until the part is accepted or rejected. A part still
+        /// going after as many workflows as there are can only be going around in circles
+        fn is_accepted(&self, ratings: &Ratings) -> Result<bool> {
+            let mut name = FIRST_WORKFLOW;
+            for _ in 0..=self.workflows.len() {
+                let rule = self
+                    .rules(name)?
+                    .iter()
+                    .find(|rule| rule.condition.is_none_or(|c| c.holds(ratings)))
+                    .ok_or_else(|| Error::new(format!("No rule applies in {}", name)))?;
+                match &rule.target {
+                    Target::Accept => return Ok(true),
+                    Target::Reject => return Ok(false),
+                    Target::Workflow(next) => name = next,
+                }
+            }
+            Err(Error::new(format!("The workflows loop for {:?}", ratings)))
+        }
+
+        fn sum_of_accepted_ratings(&self) -> Result<u64> {
+            let mut total = 0;
+            for ratings in &self.parts {
+                let accepted = self.is_accepted(ratings)?;
+                log::debug!("{:?} accepted: {}", ratings, accepted);
+                trace::emit(
+                    "part",
+                    || serde_json::json!({ "ratings": ratings, "accepted": accepted }),
+                );
+                if accepted {
+                    total += ratings.iter().sum::<u64>();
+                }
+            }
+            Ok(total)
+        }
+
+        /// Push every combination of ratings through the workflows at once, as a set of ratings
+        /// per category: each condition splits the set of its category between the ratings
+        /// sent to its target and the ones going on to the next rule
+        fn accepted_combinations(
+            &self,
+            target: &Target,
+            mut sets: [IntervalSet<u64>; 4],
+            depth: usize,
+        ) -> Result<u64> {
+            let name = match target {
+                Target::Accept => return Ok(sets.iter().map(|set| set.covered()).product()),
+                Target::Reject => return Ok(0),
+                Target::Workflow(name) => name,
+            };
+            if depth > self.workflows.len() {
+                return Err(Error::new(format!("The workflows loop through {}", name)));
+            }
+            let mut total = 0;
+            for rule in self.rules(name)? {
+                let Some(condition) = rule.condition else {
+                    total += self.accepted_combinations(&rule.target, sets, depth + 1)?;
+                    break;
+                };
+                let set = &sets[condition.category];
+                let (matching, rest) = (
+                    set.intersection(&condition.matching()),
+                    set.difference(&condition.matching()),
+                );
+                if !matching.is_empty() {
+                    let mut matching_sets = sets.clone();
+                    matching_sets[condition.category] = matching;
+                    total += self.accepted_combinations(&rule.target, matching_sets, depth + 1)?;
+                }
+                if rest.is_empty() {
+                    break;
+                }
+                sets[condition.category] = rest;
+            }
+            Ok(total)
+        }
+    }
+
+    fn solve(system: &System, part: Part) -> Result<u64> {
+        match part {
+            Part::Part1 => system.sum_of_accepted_ratings(),
+            Part::Part2 => {
+                let sets = std::array::from_fn(|_| IntervalSet::from_iter([RATINGS]));
+                system.accepted_combinations(&Target::Workflow(FIRST_WORKFLOW.to_string()), sets, 0)
+            }
+        }
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let system = parse_system(input)?;
+        let nb_of_rules: usize = system.workflows.values().map(Vec::len).sum();
+        Ok(format!(
+            "{} workflows, {} rules, {} parts",
+            system.workflows.len(),
+            nb_of_rules,
+            system.parts.len()
+        ))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        parse_system(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let system: System = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        Ok(Answer::from(solve(&system, part)?))
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        Ok(Answer::from(solve(&parse_system(input)?, Part::Part1)?))
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        Ok(Answer::from(solve(&parse_system(input)?, Part::Part2)?))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        const EXAMPLE: &str = "px{a<2006:qkq,m>2090:A,rfg}\n\
+                               pv{a>1716:R,A}\n\
+                               lnx{m>1548:A,A}\n\
+                               rfg{s<537:gd,x>2440:R,A}\n\
+                               qs{s>3448:A,lnx}\n\
+                               qkq{x<1416:A,crn}\n\
+                               crn{x>2662:A,R}\n\
+                               in{s<1351:px,qqz}\n\
+                               qqz{s>2770:qs,m<1801:hdj,R}\n\
+                               gd{a>3333:R,R}\n\
+                               hdj{m>838:A,pv}\n\
+                               \n\
+                               {x=787,m=2655,a=1222,s=2876}\n\
+                               {x=1679,m=44,a=2067,s=496}\n\
+                               {x=2036,m=264,a=79,s=2244}\n\
+                               {x=2461,m=1339,a=466,s=291}\n\
+                               {x=2127,m=1623,a=2188,s=1013}\n";
+
+        #[test]
+        fn test_example() {
+            aocstd::init_tests();
+
+            let system = parse_system(EXAMPLE).unwrap();
+            let accepted: Vec<bool> = system
+                .parts
+                .iter()
+                .map(|ratings| system.is_accepted(ratings).unwrap())
+                .collect();
+            assert_eq!(accepted, vec![true, false, true, false, true]);
+            assert_eq!(solve_part1(EXAMPLE).unwrap(), Answer::from(19114u64));
+            assert_eq!(
+                solve_part2(EXAMPLE).unwrap(),
+                Answer::from(167409079868000u64)
+            );
+
+            let json = serde_json::to_string(&system).unwrap();
+            assert_eq!(
+                solve_json(&json, Part::Part1).unwrap(),
+                Answer::from(19114u64)
+            );
+        }
+
+        #[test]
+        fn test_workflows() {
+            aocstd::init_tests();
+
+            // Everything accepted, and half of the x ratings
+            let everything = "in{A}\n\n{x=1,m=2,a=3,s=4}";
+            assert_eq!(solve_part1(everything).unwrap(), Answer::from(10u64));
+            assert_eq!(
+                solve_part2(everything).unwrap(),
+                Answer::from(4000u64.pow(4))
+            );
+            let half = "in{x>2000:A,R}";
+            assert_eq!(
+                solve_part2(half).unwrap(),
+                Answer::from(2000 * 4000u64.pow(3))
+            );
+
+            // Conditions beyond the ratings hold for none or all of them
+            assert_eq!(solve_part2("in{x<0:A,R}").unwrap(), Answer::from(0u64));
+            assert_eq!(
+                solve_part2("in{x>18446744073709551615:A,R}").unwrap(),
+                Answer::from(0u64)
+            );
+            assert_eq!(
+                solve_part2("in{x>0:A,R}").unwrap(),
+                Answer::from(4000u64.pow(4))
+            );
+            assert_eq!(
+                solve_part2("in{x<18446744073709551615:A,R}").unwrap(),
+                Answer::from(4000u64.pow(4))
+            );
+
+            assert!(solve_part1("in{x>2000:out,R}\n\n{x=3000,m=0,a=0,s=0}").is_err());
+            assert!(solve_part2("in{x>2000:in,R}").is_err());
+            assert!(parse_workflow("in{x>2000:A}").is_err());
+            assert!(parse_workflow("in{y>2000:A,R}").is_err());
+            assert!(parse_ratings("{x=1,m=2,a=3}").is_err());
+        }
+    }
+}
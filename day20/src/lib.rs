@@ -0,0 +1,416 @@
+pub mod pulse_propagation {
+    use aocstd::{math, trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize};
+    use std::collections::{HashMap, VecDeque};
+
+    const BROADCASTER: &str = "broadcaster";
+    const FINAL_MACHINE: &str = "rx";
+    const NB_OF_PRESSES: u64 = 1000;
+
+    /// Part 2 gives up when the inputs of the conjunction feeding rx have not all been high
+    /// twice after this many presses
+    const MAX_PRESSES: u64 = 1 << 20;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    enum Kind {
+        Broadcaster,
+        /// %: ignores the high pulses, a low pulse flips it and it sends its new state
+        FlipFlop,
+        /// &: remembers the last pulse from each input, sends low when they were all high
+        Conjunction,
+        /// Only a destination (ex: rx), it receives the pulses and does nothing with them
+        Untyped,
+    }
+
+    /// A line of the module configuration
+    /// ex: %a -> inv, con
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    struct Module {
+        name: String,
+        kind: Kind,
+        destinations: Vec<String>,
+    }
+
+    impl Module {
+        fn from_line(line: &str) -> Result<Self> {
+            let (module, destinations) = line
+                .split_once("->")
+                .ok_or_else(|| Error::new(format!("No destinations in {:?}", line)))?;
+            let module = module.trim();
+            let (kind, name) = if let Some(name) = module.strip_prefix('%') {
+                (Kind::FlipFlop, name)
+            } else if let Some(name) = module.strip_prefix('&') {
+                (Kind::Conjunction, name)
+            } else if module == BROADCASTER {
+                (Kind::Broadcaster, module)
+            } else {
+                return Err(Error::new(format!("Unknown module {:?}", module)));
+            };
+            let destinations = destinations
+                .split(',')
+                .map(|destination| destination.trim().to_string())
+                .filter(|destination| !destination.is_empty())
+                .collect();
+            Ok(Module {
+                name: name.to_string(),
+                kind,
+                destinations,
+            })
+        }
+    }
+
+    fn parse_modules(input: &str) -> Result<Vec<Module>> {
+        aocstd::input::lines(input)
+            .filter(|line| !line.trim().is_empty())
+            .map(Module::from_line)
+            .collect()
+    }
+
+    /// The modules by index, each destination along with the slot of the sender in the memory
+    /// of the destination (only the conjunctions use it)
+    struct Network {
+        names: Vec<String>,
+        kinds: Vec<Kind>,
+        destinations: Vec<Vec<(usize, usize)>>,
+        inputs: Vec<Vec<usize>>,
+        broadcaster: usize,
+    }
+
+    /// A pulse sent by a module, `slot` is the input of the destination it arrives through
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Pulse {
+        from: usize,
+        to: usize,
+        slot: usize,
+        high: bool,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct State {
+        flip_flops: Vec<bool>,
+        memories: Vec<Vec<bool>>,
+    }
+
+    impl Network {
+        fn new(modules: &[Module]) -> Result<Self> {
+            let mut indexes: HashMap<&str, usize> = HashMap::new();
+            let mut names = Vec::new();
+            let mut kinds = Vec::new();
+            for module in modules {
+                if indexes.insert(&module.name, names.len()).is_some() {
+                    return Err(Error::new(format!("Module {} defined twice", module.name)));
+                }
+                names.push(module.name.clone());
+                kinds.push(module.kind);
+            }
+            for destination in modules.iter().flat_map(|module| &module.destinations) {
+                if !indexes.contains_key(destination.as_str()) {
+                    indexes.insert(destination, names.len());
+                    names.push(destination.clone());
+                    kinds.push(Kind::Untyped);
+                }
+            }
+            let broadcaster = *indexes
+                .get(BROADCASTER)
+                .ok_or_else(|| Error::new(String::from("No broadcaster")))?;
+            let mut destinations = vec![Vec::new(); names.len()];
+            let mut inputs = vec![Vec::new(); names.len()];
+            for (from, module) in modules.iter().enumerate() {
+                for destination in &module.destinations {
+                    let to = indexes[destination.as_str()];
+                    destinations[from].push((to, inputs[to].len()));
+                    inputs[to].push(from);
+                }
+            }
+            Ok(Network {
+                names,
+                kinds,
+                destinations,
+                inputs,
+                broadcaster,
+            })
+        }
+
+        /// Every flip-flop off and every conjunction remembering low pulses
+        fn initial_state(&self) -> State {
+            State {
+                flip_flops: vec![false; self.names.len()],
+                memories: self
+                    .inputs
+                    .iter()
+                    .map(|inputs| vec![false; inputs.len()])
+                    .collect(),
+            }
+        }
+
+        /// Send a low pulse to the broadcaster and handle the pulses in the order they are sent
+        /// until none is left, `observe` sees every pulse (the one of the button has no sender)
+        fn push_button(&self, state: &mut State, mut observe: impl FnMut(&Pulse)) {
+            let mut pulses = VecDeque::from([Pulse {
+                from: usize::MAX,
+                to: self.broadcaster,
+                slot: 0,
+                high: false,
+            }]);
+            while let Some(pulse) = pulses.pop_front() {
+                observe(&pulse);
+                let to = pulse.to;
+                let high = match self.kinds[to] {
+                    Kind::Broadcaster => pulse.high,
+                    Kind::FlipFlop if pulse.high => continue,
+                    Kind::FlipFlop => {
+                        state.flip_flops[to] = !state.flip_flops[to];
+                        state.flip_flops[to]
+                    }
+                    Kind::Conjunction => {
+                        state.memories[to][pulse.slot] = pulse.high;
+                        !state.memories[to].iter().all(|high| *high)
+                    }
+                    Kind::Untyped => continue,
+                };
+                pulses.extend(self.destinations[to].iter().map(|(next, slot)| Pulse {
+                    from: to,
+                    to: *next,
+                    slot: *slot,
+                    high,
+                }));
+            }
+        }
+
+        /// The number of low pulses times the number of high pulses sent in 1000 presses
+        fn pulse_product(&self) -> u64 {
+            let mut state = self.initial_state();
+            let (mut low, mut high) = (0, 0);
+            for _ in 0..NB_OF_PRESSES {
+                self.push_button(&mut state, |pulse| match pulse.high {
+                    true => high += 1,
+                    false => low += 1,
+                });
+            }
+            log::debug!("{} low pulses and {} high pulses", low, high);
+            low * high
+        }
+
+        /// The number of presses until rx gets a low pulse. In the puzzle rx is fed by a single
+        /// conjunction, which sends a low pulse when its inputs were all high during the same
+        /// press. Each input is high every so many presses, so the first press when they all are
+        /// is the lcm of these periods
+        fn presses_until_rx(&self) -> Result<u64> {
+            let rx = self
+                .names
+                .iter()
+                .position(|name| name == FINAL_MACHINE)
+                .ok_or_else(|| Error::new(format!("No {} module", FINAL_MACHINE)))?;
+            let hub = match self.inputs[rx][..] {
+                [hub] if self.kinds[hub] == Kind::Conjunction => hub,
+                _ => {
+                    return Err(Error::new(format!(
+                        "{} is not fed by a single conjunction",
+                        FINAL_MACHINE
+                    )))
+                }
+            };
+            let nb_of_inputs = self.inputs[hub].len();
+            // The presses when each input of the hub sent it a high pulse, the first two
+            let mut highs: Vec<Vec<u64>> = vec![Vec::new(); nb_of_inputs];
+            let mut state = self.initial_state();
+            for press in 1..=MAX_PRESSES {
+                let mut rx_low = false;
+                self.push_button(&mut state, |pulse| {
+                    if pulse.to == rx && !pulse.high {
+                        rx_low = true;
+                    }
+                    if pulse.to == hub && pulse.high && highs[pulse.slot].len() < 2 {
+                        highs[pulse.slot].push(press);
+                    }
+                });
+                if rx_low {
+                    return Ok(press);
+                }
+                if highs.iter().all(|presses| presses.len() == 2) {
+                    break;
+                }
+            }
+            let mut periods = Vec::with_capacity(nb_of_inputs);
+            for (slot, presses) in highs.iter().enumerate() {
+                let input = &self.names[self.inputs[hub][slot]];
+                match presses[..] {
+                    [first, second] if second == 2 * first => {
+                        log::debug!("{} is high every {} presses", input, first);
+                        trace::emit(
+                            "period",
+                            || serde_json::json!({ "module": input, "period": first }),
+                        );
+                        periods.push(first);
+                    }
+                    [first, second] => {
+                        return Err(Error::new(format!(
+                            "{} is high after {} then {} presses, not periodic from the start",
+                            input, first, second
+                        )))
+                    }
+                    _ => {
+                        return Err(Error::new(format!(
+                            "{} is not high twice in {} presses",
+                            input, MAX_PRESSES
+                        )))
+                    }
+                }
+            }
+            math::checked_lcm_all(periods.iter().copied()).ok_or_else(|| {
+                Error::new(format!(
+                    "The lcm of the periods {:?} overflows u64",
+                    periods
+                ))
+            })
+        }
+    }
+
+    fn solve(modules: &[Module], part: Part) -> Result<u64> {
+        let network = Network::new(modules)?;
+        match part {
+            Part::Part1 => Ok(network.pulse_product()),
+            Part::Part2 => network.presses_until_rx(),
+        }
+    }
+
+    /// The module graph in the Graphviz DOT format: flip-flops are boxes, conjunctions are
+    /// diamonds, and the broadcaster and the untyped modules are circles
+    pub fn export_dot(input: &str, _part: Part) -> Result<String> {
+        let network = Network::new(&parse_modules(input)?)?;
+        let mut dot = String::from("digraph modules {\n");
+        for (name, kind) in network.names.iter().zip(&network.kinds) {
+            let (shape, prefix) = match kind {
+                Kind::Broadcaster => ("doublecircle", ""),
+                Kind::FlipFlop => ("box", "%"),
+                Kind::Conjunction => ("diamond", "&"),
+                Kind::Untyped => ("circle", ""),
+            };
+            dot.push_str(&format!(
+                "    \"{}\" [shape={}, label=\"{}{}\"];\n",
+                name, shape, prefix, name
+            ));
+        }
+        for (from, destinations) in network.destinations.iter().enumerate() {
+            for (to, _) in destinations {
+                dot.push_str(&format!(
+                    "    \"{}\" -> \"{}\";\n",
+                    network.names[from], network.names[*to]
+                ));
+            }
+        }
+        dot.push_str("}\n");
+        Ok(dot)
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let modules = parse_modules(input)?;
+        let count = |kind| modules.iter().filter(|module| module.kind == kind).count();
+        Ok(format!(
+            "{} modules, {} flip-flops, {} conjunctions",
+            modules.len(),
+            count(Kind::FlipFlop),
+            count(Kind::Conjunction)
+        ))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        parse_modules(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let modules: Vec<Module> = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        Ok(Answer::from(solve(&modules, part)?))
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        Ok(Answer::from(solve(&parse_modules(input)?, Part::Part1)?))
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        Ok(Answer::from(solve(&parse_modules(input)?, Part::Part2)?))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        const EXAMPLE: &str = "broadcaster -> a, b, c\n\
+                               %a -> b\n\
+                               %b -> c\n\
+                               %c -> inv\n\
+                               &inv -> a\n";
+
+        const INTERESTING_EXAMPLE: &str = "broadcaster -> a\n\
+                                           %a -> inv, con\n\
+                                           &inv -> b\n\
+                                           %b -> con\n\
+                                           &con -> output\n";
+
+        /// Two counters like the ones of the puzzle, of 3 and 5 presses, feeding rx through
+        /// inverters and a conjunction
+        const COUNTERS: &str = "broadcaster -> a0, b0\n\
+                                %a0 -> a1, ca\n\
+                                %a1 -> ca\n\
+                                &ca -> a0, ia\n\
+                                &ia -> hub\n\
+                                %b0 -> b1, cb\n\
+                                %b1 -> b2\n\
+                                %b2 -> cb\n\
+                                &cb -> b1, b0, ib\n\
+                                &ib -> hub\n\
+                                &hub -> rx\n";
+
+        #[test]
+        fn test_pulses() {
+            aocstd::init_tests();
+
+            assert_eq!(solve_part1(EXAMPLE).unwrap(), Answer::from(32000000u64));
+            assert_eq!(
+                solve_part1(INTERESTING_EXAMPLE).unwrap(),
+                Answer::from(11687500u64)
+            );
+            let json = serde_json::to_string(&parse_modules(EXAMPLE).unwrap()).unwrap();
+            assert_eq!(
+                solve_json(&json, Part::Part1).unwrap(),
+                Answer::from(32000000u64)
+            );
+            assert!(solve_part2(EXAMPLE).is_err());
+            assert!(Module::from_line("?a -> b").is_err());
+            assert!(solve_part1("%a -> b").is_err());
+
+            let dot = export_dot(INTERESTING_EXAMPLE, Part::Part1).unwrap();
+            assert!(dot.starts_with("digraph modules {\n"));
+            assert!(dot.contains("\"con\" [shape=diamond, label=\"&con\"];"));
+            assert!(dot.contains("\"output\" [shape=circle, label=\"output\"];"));
+            assert!(dot.contains("\"a\" -> \"inv\";"));
+        }
+
+        #[test]
+        fn test_presses_until_rx() {
+            aocstd::init_tests();
+
+            // Press until rx gets a low pulse for real
+            let network = Network::new(&parse_modules(COUNTERS).unwrap()).unwrap();
+            let rx = network.names.iter().position(|name| name == "rx").unwrap();
+            let mut state = network.initial_state();
+            let mut presses = 0;
+            let mut rx_low = false;
+            while !rx_low {
+                presses += 1;
+                network.push_button(&mut state, |pulse| {
+                    rx_low |= pulse.to == rx && !pulse.high;
+                });
+            }
+            assert_eq!(presses, 15);
+
+            // Both inputs of the hub are high twice after 10 presses, before rx gets its low
+            // pulse, so the answer comes from the lcm of the periods
+            assert_eq!(network.presses_until_rx().unwrap(), presses);
+        }
+    }
+}
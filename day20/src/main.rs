@@ -0,0 +1,40 @@
+use clap::Parser;
+use day20::pulse_propagation;
+use std::path::PathBuf;
+
+/// The shared options along with the ones of this day only
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(flatten)]
+    cli: aocstd::Cli,
+    /// Write the graph of the modules to this file in the Graphviz DOT format instead of solving
+    #[arg(long)]
+    dot: Option<PathBuf>,
+}
+
+fn main() {
+    let args = Args::parse();
+    let cli = args.cli;
+    aocstd::init_logger(&cli);
+    aocstd::init_threads(&cli);
+    aocstd::init_trace(&cli);
+    let input = aocstd::get_input_string(&cli);
+    aocstd::init_profile(&cli);
+
+    if let Some(path) = &cli.dump_parsed {
+        aocstd::dump_parsed(path, pulse_propagation::parsed(&input, cli.part));
+        return;
+    }
+    if let Some(path) = &args.dot {
+        aocstd::write_dot(path, pulse_propagation::export_dot(&input, cli.part));
+        return;
+    }
+
+    let answer = match (cli.input_format, cli.part) {
+        (aocstd::InputFormat::Json, part) => pulse_propagation::solve_json(&input, part),
+        (aocstd::InputFormat::Text, aocstd::Part::Part1) => pulse_propagation::solve_part1(&input),
+        (aocstd::InputFormat::Text, aocstd::Part::Part2) => pulse_propagation::solve_part2(&input),
+    };
+    aocstd::report_answer(cli.part, answer);
+}
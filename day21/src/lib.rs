@@ -0,0 +1,282 @@
+pub mod step_counter {
+    use aocstd::geometry::Point;
+    use aocstd::grid::Grid;
+    use aocstd::pathfinding::{self, Parents};
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    const PART1_STEPS: u64 = 64;
+    const PART2_STEPS: u64 = 26501365;
+
+    /// The garden of the elf, the rocks are true
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct Garden {
+        rocks: Grid<bool>,
+        start: Point,
+    }
+
+    fn parse_garden(input: &str) -> Result<Garden> {
+        let mut rows = Vec::new();
+        let mut start = None;
+        for (y, line) in aocstd::input::lines(input)
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+        {
+            let mut row = Vec::new();
+            for (x, c) in line.trim_end().chars().enumerate() {
+                let rock = match c {
+                    '.' => false,
+                    '#' => true,
+                    'S' if start.is_none() => {
+                        start = Some(Point::new(x as i64, y as i64));
+                        false
+                    }
+                    'S' => return Err(Error::new(format!("A second start at {},{}", x, y))),
+                    _ => return Err(Error::new(format!("Unknown tile {:?} at {},{}", c, x, y))),
+                };
+                row.push(rock);
+            }
+            rows.push(row);
+        }
+        let start = start.ok_or_else(|| Error::new(String::from("No start in the garden")))?;
+        Ok(Garden {
+            rocks: Grid::from_rows(rows, true),
+            start,
+        })
+    }
+
+    impl Garden {
+        /// Whether a rock is at a position, the garden repeats forever in every direction when
+        /// it is infinite and is surrounded by rocks otherwise
+        fn is_rock(&self, position: Point, infinite: bool) -> bool {
+            if !infinite {
+                return self.rocks.get(position).copied().unwrap_or(true);
+            }
+            let x = position.x.rem_euclid(self.rocks.width() as i64);
+            let y = position.y.rem_euclid(self.rocks.height() as i64);
+            self.rocks[Point::new(x, y)]
+        }
+
+        /// The number of steps to every plot reachable in at most `max_steps`. The plots
+        /// farther than that from the start as the crow flies are never explored, so the
+        /// infinite garden stays finite
+        fn distances(&self, max_steps: u64, infinite: bool) -> HashMap<Point, u64> {
+            let search = pathfinding::bfs(
+                [self.start],
+                |position: &Point| {
+                    let position = *position;
+                    position
+                        .neighbors4()
+                        .filter(move |next| {
+                            !self.is_rock(*next, infinite)
+                                && next.manhattan_distance(self.start) <= max_steps
+                        })
+                        .collect::<Vec<_>>()
+                },
+                |_| false,
+                Parents::Skip,
+            );
+            search.distances
+        }
+    }
+
+    /// The plots where the elf can end after exactly `steps` steps: the ones reachable in fewer
+    /// steps with the same parity, since the elf can step back and forth to waste two steps
+    fn reachable(distances: &HashMap<Point, u64>, steps: u64) -> u64 {
+        distances
+            .values()
+            .filter(|distance| **distance <= steps && **distance % 2 == steps % 2)
+            .count() as u64
+    }
+
+    /// Walk the garden, the infinite garden is simulated plot by plot
+    fn walk(garden: &Garden, steps: u64, infinite: bool) -> u64 {
+        reachable(&garden.distances(steps, infinite), steps)
+    }
+
+    /// The plots reachable in the infinite garden, too many steps to simulate. In the puzzle
+    /// the garden is square, the start is at its center and its row and column are free of
+    /// rocks, so the walk enters the copies of the garden at a regular pace: a new ring of
+    /// copies every `size` steps. The plots reachable after `rest + n * size` steps are then
+    /// a quadratic function of n, found by simulating three values of n
+    fn extrapolate(garden: &Garden, steps: u64) -> Result<u64> {
+        let size = garden.rocks.width();
+        if garden.rocks.height() != size {
+            return Err(Error::new(format!(
+                "The garden is not square: {}x{}",
+                size,
+                garden.rocks.height()
+            )));
+        }
+        let center = (size / 2) as i64;
+        if garden.start != Point::new(center, center) {
+            return Err(Error::new(format!(
+                "The start {} is not at the center of the garden",
+                garden.start
+            )));
+        }
+        let row_clear = (0..size as i64).all(|x| !garden.rocks[Point::new(x, center)]);
+        let column_clear = (0..size as i64).all(|y| !garden.rocks[Point::new(center, y)]);
+        if !row_clear || !column_clear {
+            return Err(Error::new(String::from(
+                "The row and the column of the start have rocks",
+            )));
+        }
+
+        let size = size as u64;
+        let rest = steps % size;
+        let samples = [rest + size, rest + 2 * size, rest + 3 * size];
+        if steps <= samples[2] {
+            return Ok(walk(garden, steps, true));
+        }
+        let distances = garden.distances(samples[2], true);
+        let [a, b, c] = samples.map(|steps| reachable(&distances, steps) as i128);
+        log::debug!("{}, {} and {} plots after {:?} steps", a, b, c, samples);
+        trace::emit(
+            "samples",
+            || serde_json::json!({ "steps": samples, "plots": [a, b, c] }),
+        );
+        // Newton's forward differences from n = 1
+        let n = (steps / size) as i128 - 1;
+        let first = b - a;
+        let second = c - 2 * b + a;
+        let plots = n
+            .checked_mul(n - 1)
+            .and_then(|product| (product / 2).checked_mul(second))
+            .zip(n.checked_mul(first))
+            .and_then(|(quadratic, linear)| quadratic.checked_add(linear)?.checked_add(a))
+            .ok_or_else(|| {
+                Error::new(format!(
+                    "The extrapolation gives too many plots after {} steps",
+                    steps
+                ))
+            })?;
+        u64::try_from(plots)
+            .map_err(|_| Error::new(format!("The extrapolation gives {} plots", plots)))
+    }
+
+    fn solve(garden: &Garden, part: Part, steps: u64) -> Result<u64> {
+        match part {
+            Part::Part1 => Ok(walk(garden, steps, false)),
+            Part::Part2 => extrapolate(garden, steps),
+        }
+    }
+
+    fn default_steps(part: Part) -> u64 {
+        match part {
+            Part::Part1 => PART1_STEPS,
+            Part::Part2 => PART2_STEPS,
+        }
+    }
+
+    /// Solve a part with another number of steps, part 1 walks the garden as it is and part 2
+    /// the infinite one
+    pub fn solve_with_steps(input: &str, part: Part, steps: u64) -> Result<Answer> {
+        Ok(Answer::from(solve(&parse_garden(input)?, part, steps)?))
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let garden = parse_garden(input)?;
+        let rocks = garden.rocks.iter().filter(|(_, rock)| **rock).count();
+        Ok(format!(
+            "{}x{} garden, {} rocks, start at {}",
+            garden.rocks.width(),
+            garden.rocks.height(),
+            rocks,
+            garden.start
+        ))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        parse_garden(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let garden: Garden = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        garden.rocks.check_size()?;
+        Ok(Answer::from(solve(&garden, part, default_steps(part))?))
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        solve_with_steps(input, Part::Part1, PART1_STEPS)
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        solve_with_steps(input, Part::Part2, PART2_STEPS)
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        const EXAMPLE: &str = "...........\n\
+                               .....###.#.\n\
+                               .###.##..#.\n\
+                               ..#.#...#..\n\
+                               ....#.#....\n\
+                               .##..S####.\n\
+                               .##..#...#.\n\
+                               .......##..\n\
+                               .##.#.####.\n\
+                               .##..##.##.\n\
+                               ...........\n";
+
+        /// Shaped like the puzzle input: the start at the center, its row, its column and the
+        /// border free of rocks
+        const CLEAR_LINES: &str = "...........\n\
+                                   .##.....#..\n\
+                                   ..#....##..\n\
+                                   .#.......#.\n\
+                                   ...#...#...\n\
+                                   .....S.....\n\
+                                   ..##....#..\n\
+                                   .#.....#.#.\n\
+                                   ..#.....##.\n\
+                                   .#.#.......\n\
+                                   ...........\n";
+
+        #[test]
+        fn test_walk() {
+            aocstd::init_tests();
+
+            assert_eq!(
+                solve_with_steps(EXAMPLE, Part::Part1, 6).unwrap(),
+                Answer::from(16u64)
+            );
+            let garden = parse_garden(EXAMPLE).unwrap();
+            for (steps, plots) in [(6, 16), (10, 50), (50, 1594), (100, 6536)] {
+                assert_eq!(walk(&garden, steps, true), plots);
+            }
+            // The example does not have the shape the extrapolation needs
+            assert!(solve_part2(EXAMPLE).is_err());
+            let json = serde_json::to_string(&garden).unwrap();
+            assert!(solve_json(&json, Part::Part1).is_ok());
+            assert!(parse_garden("..S\n.S.").is_err());
+            assert!(parse_garden("...\n.x.").is_err());
+        }
+
+        #[test]
+        fn test_extrapolation() {
+            aocstd::init_tests();
+
+            let garden = parse_garden(CLEAR_LINES).unwrap();
+            assert!(extrapolate(&garden, 0).is_ok());
+            // Past 43 steps the extrapolation kicks in, check it against the simulation
+            let distances = garden.distances(150, true);
+            for steps in 40..=150 {
+                assert_eq!(
+                    extrapolate(&garden, steps).unwrap(),
+                    reachable(&distances, steps),
+                    "{} steps",
+                    steps
+                );
+            }
+            assert!(extrapolate(&garden, u64::MAX).is_err());
+        }
+    }
+}
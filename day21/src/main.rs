@@ -0,0 +1,38 @@
+use clap::Parser;
+use day21::step_counter;
+
+/// The shared options along with the ones of this day only
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(flatten)]
+    cli: aocstd::Cli,
+    /// Number of steps of the walk instead of the one of the part
+    #[arg(long)]
+    steps: Option<u64>,
+}
+
+fn main() {
+    let args = Args::parse();
+    let cli = args.cli;
+    aocstd::init_logger(&cli);
+    aocstd::init_threads(&cli);
+    aocstd::init_trace(&cli);
+    let input = aocstd::get_input_string(&cli);
+    aocstd::init_profile(&cli);
+
+    if let Some(path) = &cli.dump_parsed {
+        aocstd::dump_parsed(path, step_counter::parsed(&input, cli.part));
+        return;
+    }
+
+    let answer = match (cli.input_format, cli.part, args.steps) {
+        (aocstd::InputFormat::Json, part, _) => step_counter::solve_json(&input, part),
+        (aocstd::InputFormat::Text, part, Some(steps)) => {
+            step_counter::solve_with_steps(&input, part, steps)
+        }
+        (aocstd::InputFormat::Text, aocstd::Part::Part1, None) => step_counter::solve_part1(&input),
+        (aocstd::InputFormat::Text, aocstd::Part::Part2, None) => step_counter::solve_part2(&input),
+    };
+    aocstd::report_answer(cli.part, answer);
+}
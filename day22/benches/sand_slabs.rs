@@ -0,0 +1,50 @@
+//! The puzzle input is not in the repository, the benches run on generated piles: bricks of one
+//! to four cubes dropped over a 10x10 area, like the puzzle, and a stress pile five times as big.
+//! Part 2 makes a chain reaction per brick so it grows with the square of the bricks.
+use criterion::{criterion_group, criterion_main, Criterion};
+use day22::sand_slabs;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+fn generate_input(seed: u64, nb_of_bricks: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut input = String::new();
+    for _ in 0..nb_of_bricks {
+        // Along x, y or z, always inside the 10x10 area
+        let axis = rng.gen_range(0..3);
+        let length = rng.gen_range(0..4);
+        let mut start = [
+            rng.gen_range(0..10),
+            rng.gen_range(0..10),
+            rng.gen_range(1..400),
+        ];
+        if axis < 2 {
+            start[axis] = rng.gen_range(0..10 - length);
+        }
+        let mut end = start;
+        end[axis] += length;
+        input.push_str(&format!(
+            "{},{},{}~{},{},{}\n",
+            start[0], start[1], start[2], end[0], end[1], end[2]
+        ));
+    }
+    input
+}
+
+fn bench_sand_slabs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day22");
+    group.sample_size(10);
+    for nb_of_bricks in [1500, 7500] {
+        let input = generate_input(22, nb_of_bricks);
+        group.bench_function(format!("part1/{}", nb_of_bricks), |b| {
+            b.iter(|| sand_slabs::solve_part1(&input).unwrap())
+        });
+        group.bench_function(format!("part2/{}", nb_of_bricks), |b| {
+            b.iter(|| sand_slabs::solve_part2(&input).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_sand_slabs);
+criterion_main!(benches);
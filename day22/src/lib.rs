@@ -0,0 +1,246 @@
+pub mod sand_slabs {
+    use aocstd::geometry::{Axis, Point3};
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    /// A brick of sand, the cubes from `start` to `end` included. The start is the corner with
+    /// the lowest coordinates
+    /// ex: 1,0,1~1,2,1 is three cubes long along y
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    struct Brick {
+        start: Point3,
+        end: Point3,
+    }
+
+    impl Brick {
+        fn from_line(line: &str) -> Result<Self> {
+            let invalid = || Error::new(format!("Invalid brick {:?}", line));
+            let (start, end) = line.split_once('~').ok_or_else(invalid)?;
+            let corner = |text: &str| -> Result<Point3> {
+                let coordinates = text
+                    .split(',')
+                    .map(|coordinate| coordinate.trim().parse().map_err(|_| invalid()))
+                    .collect::<Result<Vec<i128>>>()?;
+                match coordinates[..] {
+                    [x, y, z] => Ok(Point3::new(x, y, z)),
+                    _ => Err(invalid()),
+                }
+            };
+            let (a, b) = (corner(start)?, corner(end)?);
+            let brick = Brick {
+                start: Point3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+                end: Point3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+            };
+            if brick.start.z < 1 {
+                return Err(Error::new(format!("The brick {:?} is in the ground", line)));
+            }
+            Ok(brick)
+        }
+
+        /// The columns of the brick seen from above
+        fn footprint(&self) -> impl Iterator<Item = (i128, i128)> + '_ {
+            (self.start.x..=self.end.x)
+                .flat_map(move |x| (self.start.y..=self.end.y).map(move |y| (x, y)))
+        }
+
+        fn height(&self) -> i128 {
+            self.end.z - self.start.z + 1
+        }
+
+        /// The same brick moved down until its bottom is at `z`
+        fn lowered_to(&self, z: i128) -> Brick {
+            let mut offset = Point3::ORIGIN;
+            offset[Axis::Z] = z - self.start.z;
+            Brick {
+                start: self.start + offset,
+                end: self.end + offset,
+            }
+        }
+    }
+
+    fn parse_bricks(input: &str) -> Result<Vec<Brick>> {
+        aocstd::input::lines(input)
+            .filter(|line| !line.trim().is_empty())
+            .map(Brick::from_line)
+            .collect()
+    }
+
+    /// The bricks once they all fell, along with who rests on whom. The bricks are in the order
+    /// they settled, lowest first, so a brick always comes after the ones supporting it: the
+    /// order is topological
+    #[derive(Debug, Clone)]
+    struct Pile {
+        bricks: Vec<Brick>,
+        /// The bricks right under each brick, touching it
+        supported_by: Vec<Vec<usize>>,
+        /// The bricks right above each brick, touching it
+        supporting: Vec<Vec<usize>>,
+    }
+
+    /// Let the bricks fall, lowest first, each one stops on the highest brick under its
+    /// footprint or on the ground
+    fn settle(bricks: &[Brick]) -> Pile {
+        let mut falling = bricks.to_vec();
+        falling.sort_by_key(|brick| brick.start.z);
+        // The top of each column seen from above, with the brick there
+        let mut tops: HashMap<(i128, i128), (i128, usize)> = HashMap::new();
+        let mut pile = Pile {
+            bricks: Vec::with_capacity(falling.len()),
+            supported_by: Vec::with_capacity(falling.len()),
+            supporting: vec![Vec::new(); falling.len()],
+        };
+        for (index, brick) in falling.iter().enumerate() {
+            let floor = brick
+                .footprint()
+                .filter_map(|column| tops.get(&column).map(|(z, _)| *z))
+                .max()
+                .unwrap_or(0);
+            let mut supported_by: Vec<usize> = brick
+                .footprint()
+                .filter_map(|column| tops.get(&column))
+                .filter(|(z, _)| *z == floor)
+                .map(|(_, below)| *below)
+                .collect();
+            supported_by.sort_unstable();
+            supported_by.dedup();
+            for below in &supported_by {
+                pile.supporting[*below].push(index);
+            }
+            let settled = brick.lowered_to(floor + 1);
+            for column in settled.footprint() {
+                tops.insert(column, (settled.end.z, index));
+            }
+            pile.bricks.push(settled);
+            pile.supported_by.push(supported_by);
+        }
+        pile
+    }
+
+    impl Pile {
+        /// Whether removing a brick lets nothing fall: every brick on it rests on another one too
+        fn is_safe_to_disintegrate(&self, brick: usize) -> bool {
+            self.supporting[brick]
+                .iter()
+                .all(|above| self.supported_by[*above].len() > 1)
+        }
+
+        /// The other bricks falling when a brick is disintegrated. In the topological order a
+        /// brick is looked at after all its supports, so it falls when they all already fell
+        fn chain_reaction(&self, brick: usize, fallen: &mut [bool]) -> u64 {
+            fallen.fill(false);
+            fallen[brick] = true;
+            let mut count = 0;
+            for above in brick + 1..self.bricks.len() {
+                let supports = &self.supported_by[above];
+                if !supports.is_empty() && supports.iter().all(|below| fallen[*below]) {
+                    fallen[above] = true;
+                    count += 1;
+                }
+            }
+            count
+        }
+    }
+
+    fn solve(bricks: &[Brick], part: Part) -> u64 {
+        let pile = settle(bricks);
+        let top = pile
+            .bricks
+            .iter()
+            .map(|brick| brick.end.z)
+            .max()
+            .unwrap_or(0);
+        log::debug!("{} bricks settled, up to z={}", pile.bricks.len(), top);
+        trace::emit(
+            "pile",
+            || serde_json::json!({ "bricks": pile.bricks.len(), "top": top }),
+        );
+        match part {
+            Part::Part1 => (0..pile.bricks.len())
+                .filter(|brick| pile.is_safe_to_disintegrate(*brick))
+                .count() as u64,
+            Part::Part2 => {
+                let mut fallen = vec![false; pile.bricks.len()];
+                (0..pile.bricks.len())
+                    .map(|brick| pile.chain_reaction(brick, &mut fallen))
+                    .sum()
+            }
+        }
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let bricks = parse_bricks(input)?;
+        let cubes: i128 = bricks
+            .iter()
+            .map(|brick| brick.footprint().count() as i128 * brick.height())
+            .sum();
+        Ok(format!("{} bricks, {} cubes", bricks.len(), cubes))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        parse_bricks(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let bricks: Vec<Brick> = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        let is_invalid = |brick: &&Brick| {
+            brick.start.z < 1
+                || Axis::ALL
+                    .iter()
+                    .any(|axis| brick.start[*axis] > brick.end[*axis])
+        };
+        if let Some(brick) = bricks.iter().find(is_invalid) {
+            return Err(Error::new(format!("Invalid brick {:?}", brick)));
+        }
+        Ok(Answer::from(solve(&bricks, part)))
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        Ok(Answer::from(solve(&parse_bricks(input)?, Part::Part1)))
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        Ok(Answer::from(solve(&parse_bricks(input)?, Part::Part2)))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        const EXAMPLE: &str = "1,0,1~1,2,1\n\
+                               0,0,2~2,0,2\n\
+                               0,2,3~2,2,3\n\
+                               0,0,4~0,2,4\n\
+                               2,0,5~2,2,5\n\
+                               0,1,6~2,1,6\n\
+                               1,1,8~1,1,9\n";
+
+        #[test]
+        fn test_pile() {
+            aocstd::init_tests();
+
+            assert_eq!(solve_part1(EXAMPLE).unwrap(), Answer::from(5u64));
+            assert_eq!(solve_part2(EXAMPLE).unwrap(), Answer::from(7u64));
+            let pile = settle(&parse_bricks(EXAMPLE).unwrap());
+            // The vertical brick G falls to rest on F at z=5
+            assert_eq!(pile.bricks[6].start, Point3::new(1, 1, 5));
+            assert_eq!(pile.supported_by[6], vec![5]);
+            for (index, supports) in pile.supported_by.iter().enumerate() {
+                assert!(supports.iter().all(|below| *below < index));
+            }
+
+            let json = serde_json::to_string(&parse_bricks(EXAMPLE).unwrap()).unwrap();
+            assert_eq!(solve_json(&json, Part::Part2).unwrap(), Answer::from(7u64));
+            assert_eq!(
+                Brick::from_line("1,2,1~1,0,1").unwrap(),
+                Brick::from_line("1,0,1~1,2,1").unwrap()
+            );
+            assert!(Brick::from_line("1,0,0~1,2,0").is_err());
+            assert!(Brick::from_line("1,0~1,2").is_err());
+        }
+    }
+}
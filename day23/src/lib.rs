@@ -0,0 +1,257 @@
+pub mod a_long_walk {
+    use aocstd::geometry::{Direction, Point};
+    use aocstd::graph::Graph;
+    use aocstd::grid::Grid;
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize};
+
+    /// The contracted graph is searched with the visited junctions in a u64
+    const MAX_JUNCTIONS: usize = 64;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    enum Tile {
+        Path,
+        Forest,
+        /// Icy, in part 1 the next step has to go downhill
+        Slope(Direction),
+    }
+
+    impl Tile {
+        fn from_char(c: char) -> Option<Tile> {
+            let tile = match c {
+                '.' => Tile::Path,
+                '#' => Tile::Forest,
+                '^' => Tile::Slope(Direction::North),
+                '>' => Tile::Slope(Direction::East),
+                'v' => Tile::Slope(Direction::South),
+                '<' => Tile::Slope(Direction::West),
+                _ => return None,
+            };
+            Some(tile)
+        }
+    }
+
+    /// The hiking trails, from the path of the top row to the one of the bottom row
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct Map {
+        grid: Grid<Tile>,
+        start: Point,
+        end: Point,
+    }
+
+    fn parse_map(input: &str) -> Result<Map> {
+        let mut rows = Vec::new();
+        for (y, line) in aocstd::input::lines(input)
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+        {
+            let row = line
+                .trim_end()
+                .chars()
+                .enumerate()
+                .map(|(x, c)| {
+                    Tile::from_char(c)
+                        .ok_or_else(|| Error::new(format!("Unknown tile {:?} at {},{}", c, x, y)))
+                })
+                .collect::<Result<Vec<Tile>>>()?;
+            rows.push(row);
+        }
+        let grid = Grid::from_rows(rows, Tile::Forest);
+        let path_in_row = |y: usize| -> Result<Point> {
+            let x = grid
+                .row(y)
+                .and_then(|row| row.iter().position(|tile| *tile == Tile::Path))
+                .ok_or_else(|| Error::new(format!("No path in the row {}", y)))?;
+            Ok(Point::new(x as i64, y as i64))
+        };
+        let start = path_in_row(0)?;
+        let end = path_in_row(grid.height().saturating_sub(1))?;
+        Ok(Map { grid, start, end })
+    }
+
+    impl Map {
+        /// Every step between two trail tiles. In part 1 the slopes only go downhill so the
+        /// graph is directed, in part 2 they are as good as paths
+        fn trails(&self, part: Part) -> Graph<Point> {
+            let mut graph = match part {
+                Part::Part1 => Graph::directed(),
+                Part::Part2 => Graph::undirected(),
+            };
+            for (position, tile) in self.grid.iter() {
+                if *tile == Tile::Forest {
+                    continue;
+                }
+                graph.add_node(position);
+                for direction in Direction::CARDINALS {
+                    let next = position + direction;
+                    if self.grid.get(next).is_none_or(|tile| *tile == Tile::Forest) {
+                        continue;
+                    }
+                    let allowed = match (part, tile) {
+                        (Part::Part1, Tile::Slope(slope)) => *slope == direction,
+                        (Part::Part1, _) => true,
+                        // Each step once, the graph goes both ways
+                        (Part::Part2, _) => {
+                            matches!(direction, Direction::East | Direction::South)
+                        }
+                    };
+                    if allowed {
+                        graph.add_edge(position, next);
+                    }
+                }
+            }
+            graph
+        }
+    }
+
+    /// The longest walk from `node` to `end` through the junctions not visited yet. Once at
+    /// the last junction before the end the walk has to go to the end: going anywhere else
+    /// would block the way to it
+    fn longest_from(
+        junctions: &Graph<Point>,
+        node: usize,
+        end: usize,
+        last: Option<(usize, u64)>,
+        visited: u64,
+    ) -> Option<u64> {
+        if node == end {
+            return Some(0);
+        }
+        if let Some((last, steps)) = last {
+            if node == last {
+                return Some(steps);
+            }
+        }
+        let visited = visited | (1 << node);
+        junctions
+            .weighted_neighbors(node)
+            .filter(|(next, _)| visited & (1 << next) == 0)
+            .filter_map(|(next, steps)| {
+                Some(steps + longest_from(junctions, next, end, last, visited)?)
+            })
+            .max()
+    }
+
+    fn longest_walk(map: &Map, part: Part) -> Result<u64> {
+        let trails = map.trails(part);
+        let junctions = trails.contract(|position| *position == map.start || *position == map.end);
+        log::debug!(
+            "{} trail tiles contracted to {} junctions and {} trails",
+            trails.len(),
+            junctions.len(),
+            junctions.edge_count()
+        );
+        trace::emit(
+            "junctions",
+            || serde_json::json!({ "junctions": junctions.len(), "trails": junctions.edge_count() }),
+        );
+        if junctions.len() > MAX_JUNCTIONS {
+            return Err(Error::new(format!(
+                "{} junctions, the search handles up to {}",
+                junctions.len(),
+                MAX_JUNCTIONS
+            )));
+        }
+        let start = junctions.index_of(&map.start).unwrap_or_default();
+        let end = junctions.index_of(&map.end).unwrap_or_default();
+        let mut before_end = (0..junctions.len()).filter_map(|node| {
+            let (_, steps) = junctions
+                .weighted_neighbors(node)
+                .filter(|(next, _)| *next == end)
+                .max_by_key(|(_, steps)| *steps)?;
+            Some((node, steps))
+        });
+        let last = match (before_end.next(), before_end.next()) {
+            (Some(last), None) => Some(last),
+            _ => None,
+        };
+        longest_from(&junctions, start, end, last, 0)
+            .ok_or_else(|| Error::new(format!("No walk from {} to {}", map.start, map.end)))
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let map = parse_map(input)?;
+        let count = |f: fn(&Tile) -> bool| map.grid.iter().filter(|(_, tile)| f(tile)).count();
+        Ok(format!(
+            "{}x{} map, {} trail tiles, {} slopes, from {} to {}",
+            map.grid.width(),
+            map.grid.height(),
+            count(|tile| *tile != Tile::Forest),
+            count(|tile| matches!(tile, Tile::Slope(_))),
+            map.start,
+            map.end
+        ))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        parse_map(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let map: Map = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        map.grid.check_size()?;
+        Ok(Answer::from(longest_walk(&map, part)?))
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        Ok(Answer::from(longest_walk(&parse_map(input)?, Part::Part1)?))
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        Ok(Answer::from(longest_walk(&parse_map(input)?, Part::Part2)?))
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        const EXAMPLE: &str = "#.#####################\n\
+                               #.......#########...###\n\
+                               #######.#########.#.###\n\
+                               ###.....#.>.>.###.#.###\n\
+                               ###v#####.#v#.###.#.###\n\
+                               ###.>...#.#.#.....#...#\n\
+                               ###v###.#.#.#########.#\n\
+                               ###...#.#.#.......#...#\n\
+                               #####.#.#.#######.#.###\n\
+                               #.....#.#.#.......#...#\n\
+                               #.#####.#.#.#########v#\n\
+                               #.#...#...#...###...>.#\n\
+                               #.#.#v#######v###.###v#\n\
+                               #...#.>.#...>.>.#.###.#\n\
+                               #####v#.#.###v#.#.###.#\n\
+                               #.....#...#...#.#.#...#\n\
+                               #.#########.###.#.#.###\n\
+                               #...###...#...#...#.###\n\
+                               ###.###.#.###v#####v###\n\
+                               #...#...#.#.>.>.#.>.###\n\
+                               #.###.###.#.###.#.#v###\n\
+                               #.....###...###...#...#\n\
+                               #####################.#\n";
+
+        #[test]
+        fn test_longest_walk() {
+            aocstd::init_tests();
+
+            assert_eq!(solve_part1(EXAMPLE).unwrap(), Answer::from(94u64));
+            assert_eq!(solve_part2(EXAMPLE).unwrap(), Answer::from(154u64));
+            let map = parse_map(EXAMPLE).unwrap();
+            let junctions = map
+                .trails(Part::Part2)
+                .contract(|position| *position == map.start || *position == map.end);
+            assert_eq!(junctions.len(), 9);
+            assert_eq!(junctions.edge_count(), 12);
+
+            let json = serde_json::to_string(&map).unwrap();
+            assert_eq!(solve_json(&json, Part::Part1).unwrap(), Answer::from(94u64));
+            // The slope sends the walk back up
+            assert!(solve_part1("#.#\n#^#\n#.#").is_err());
+            assert_eq!(solve_part2("#.#\n#^#\n#.#").unwrap(), Answer::from(2u64));
+            assert!(parse_map("#.#\n#x#\n#.#").is_err());
+        }
+    }
+}
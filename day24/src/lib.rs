@@ -0,0 +1,511 @@
+pub mod never_tell_me_the_odds {
+    use aocstd::geometry::{Axis, Point3, Vec3};
+    use aocstd::linalg::{Fraction, Matrix};
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize};
+    use std::ops::RangeInclusive;
+
+    /// Where the paths of part 1 have to cross, on x and y
+    const TEST_AREA: RangeInclusive<i128> = 200000000000000..=400000000000000;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    struct Hailstone {
+        position: Point3,
+        velocity: Vec3,
+    }
+
+    impl Hailstone {
+        /// ex: 19, 13, 30 @ -2,  1, -2
+        fn from_line(line: &str) -> Result<Self> {
+            let invalid = || Error::new(format!("Invalid hailstone {:?}", line));
+            let (position, velocity) = line.split_once('@').ok_or_else(invalid)?;
+            let vector = |text: &str| -> Result<Point3> {
+                let coordinates = text
+                    .split(',')
+                    .map(|coordinate| coordinate.trim().parse().map_err(|_| invalid()))
+                    .collect::<Result<Vec<i128>>>()?;
+                match coordinates[..] {
+                    [x, y, z] => Ok(Point3::new(x, y, z)),
+                    _ => Err(invalid()),
+                }
+            };
+            Ok(Hailstone {
+                position: vector(position)?,
+                velocity: vector(velocity)?,
+            })
+        }
+    }
+
+    fn parse_hailstones(input: &str) -> Result<Vec<Hailstone>> {
+        aocstd::input::lines(input)
+            .filter(|line| !line.trim().is_empty())
+            .map(Hailstone::from_line)
+            .collect()
+    }
+
+    /// `a + b` on every axis, None when a coordinate overflows
+    fn checked_sum(a: Point3, b: Vec3) -> Option<Point3> {
+        Some(Point3::new(
+            a.x.checked_add(b.x)?,
+            a.y.checked_add(b.y)?,
+            a.z.checked_add(b.z)?,
+        ))
+    }
+
+    /// `a - b` on every axis, None when a coordinate overflows
+    fn checked_difference(a: Point3, b: Point3) -> Option<Vec3> {
+        Some(Vec3::new(
+            a.x.checked_sub(b.x)?,
+            a.y.checked_sub(b.y)?,
+            a.z.checked_sub(b.z)?,
+        ))
+    }
+
+    /// Whether the paths of two hailstones cross inside the area in the future of both, looking
+    /// only at x and y. Solving a.position + a.velocity * t = b.position + b.velocity * s with
+    /// Cramer's rule, the crossing point is compared to the area multiplied by the determinant
+    /// so everything stays in integers. None when the products overflow i128
+    fn paths_cross(a: &Hailstone, b: &Hailstone, area: &RangeInclusive<i128>) -> Option<bool> {
+        let cross = |u: Vec3, v: Vec3| u.x.checked_mul(v.y)?.checked_sub(u.y.checked_mul(v.x)?);
+        let determinant = cross(a.velocity, b.velocity)?;
+        if determinant == 0 {
+            // Parallel paths
+            return Some(false);
+        }
+        let offset = checked_difference(b.position, a.position)?;
+        let (t, s) = (cross(offset, b.velocity)?, cross(offset, a.velocity)?);
+        // Flip the signs so the determinant is positive and the comparisons keep their way
+        let sign = determinant.signum();
+        let (determinant, t, s) = (
+            determinant.checked_mul(sign)?,
+            t.checked_mul(sign)?,
+            s.checked_mul(sign)?,
+        );
+        if t < 0 || s < 0 {
+            return Some(false);
+        }
+        for axis in [Axis::X, Axis::Y] {
+            let scaled = a.position[axis]
+                .checked_mul(determinant)?
+                .checked_add(a.velocity[axis].checked_mul(t)?)?;
+            let inside = area.start().checked_mul(determinant)? <= scaled
+                && scaled <= area.end().checked_mul(determinant)?;
+            if !inside {
+                return Some(false);
+            }
+        }
+        Some(true)
+    }
+
+    fn count_crossings(hailstones: &[Hailstone], area: &RangeInclusive<i128>) -> Result<u64> {
+        let mut count = 0;
+        for (index, a) in hailstones.iter().enumerate() {
+            for b in &hailstones[index + 1..] {
+                let cross = paths_cross(a, b, area).ok_or_else(|| {
+                    Error::new(format!(
+                        "The paths of {:?} and {:?} are too large for i128",
+                        a, b
+                    ))
+                })?;
+                if cross {
+                    count += 1;
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    /// The row and the value of the difference of two hailstones in the system of `solve_plane`,
+    /// None when they overflow
+    fn plane_equation(
+        first: &Hailstone,
+        other: &Hailstone,
+        a: Axis,
+        b: Axis,
+    ) -> Option<(Vec<i128>, i128)> {
+        let (p, v) = (first.position, first.velocity);
+        let (q, w) = (other.position, other.velocity);
+        // The cross product of a position and a velocity on the plane
+        let moment =
+            |p: Point3, v: Vec3| p[a].checked_mul(v[b])?.checked_sub(p[b].checked_mul(v[a])?);
+        let row = vec![
+            v[b].checked_sub(w[b])?,
+            w[a].checked_sub(v[a])?,
+            q[b].checked_sub(p[b])?,
+            p[a].checked_sub(q[a])?,
+        ];
+        Some((row, moment(p, v)?.checked_sub(moment(q, w)?)?))
+    }
+
+    /// The rock along two axes `a` and `b`: (position a, position b, velocity a, velocity b).
+    /// The rock and a hailstone meet when (P - p) x (V - v) = 0, on the plane of the two axes:
+    ///   (Pb - pb)(Va - va) = (Pa - pa)(Vb - vb)
+    /// The only non linear term Pb Va - Pa Vb is the same for every hailstone, so it cancels
+    /// in the difference of two hailstones. Four differences make a linear system of the four
+    /// unknowns. Plane by plane the systems stay 4x4 and the fractions fit in i128 with the
+    /// numbers of the puzzle. Fails when the coefficients of the system overflow i128, None
+    /// when the system has no single integer solution
+    fn solve_plane(hailstones: &[Hailstone], a: Axis, b: Axis) -> Result<Option<[i128; 4]>> {
+        let first = hailstones[0];
+        let mut rows = Vec::new();
+        let mut values = Vec::new();
+        for other in &hailstones[1..5] {
+            let (row, value) = plane_equation(&first, other, a, b).ok_or_else(|| {
+                Error::new(format!(
+                    "The system of {:?} and {:?} is too large for i128",
+                    first, other
+                ))
+            })?;
+            rows.push(row);
+            values.push(Fraction::from(value));
+        }
+        let Some(solution) = Matrix::from_integers(rows).solve(&values)? else {
+            return Ok(None);
+        };
+        let mut unknowns = [0; 4];
+        for (unknown, value) in unknowns.iter_mut().zip(solution) {
+            let Some(integer) = value.to_integer() else {
+                return Ok(None);
+            };
+            *unknown = integer;
+        }
+        Ok(Some(unknowns))
+    }
+
+    /// The position and the velocity of the rock hitting every hailstone, from the first five
+    /// hailstones giving a regular system. The positions are moved so that the first
+    /// hailstone starts at the origin, to keep the numbers small
+    fn throw_rock(hailstones: &[Hailstone]) -> Result<(Point3, Vec3)> {
+        if hailstones.len() < 5 {
+            return Err(Error::new(format!(
+                "{} hailstones, the rock needs 5",
+                hailstones.len()
+            )));
+        }
+        let origin = hailstones[0].position;
+        let moved = hailstones
+            .iter()
+            .map(|hailstone| {
+                let position = checked_difference(hailstone.position, origin).ok_or_else(|| {
+                    Error::new(format!(
+                        "The hailstone {:?} is too far from the first one",
+                        hailstone
+                    ))
+                })?;
+                Ok(Hailstone {
+                    position,
+                    velocity: hailstone.velocity,
+                })
+            })
+            .collect::<Result<Vec<Hailstone>>>()?;
+        let mut rock = None;
+        for window in moved.windows(5) {
+            let Some([x, y, vx, vy]) = solve_plane(window, Axis::X, Axis::Y)? else {
+                continue;
+            };
+            let Some([_, z, _, vz]) = solve_plane(window, Axis::X, Axis::Z)? else {
+                continue;
+            };
+            rock = Some((Point3::new(x, y, z), Vec3::new(vx, vy, vz)));
+            break;
+        }
+        let (position, velocity) =
+            rock.ok_or_else(|| Error::new(String::from("No rock hits every hailstone")))?;
+        let position = checked_sum(position, origin)
+            .ok_or_else(|| Error::new(format!("The rock is thrown beyond i128 from {}", origin)))?;
+        check_rock(hailstones, position, velocity)?;
+        log::debug!("Rock thrown from {} at {}", position, velocity);
+        trace::emit(
+            "rock",
+            || serde_json::json!({ "position": position, "velocity": velocity }),
+        );
+        Ok((position, velocity))
+    }
+
+    /// Whether the rock hits the hailstone, None when the products overflow i128
+    fn hits(hailstone: &Hailstone, position: Point3, velocity: Vec3) -> Option<bool> {
+        let offset = checked_difference(hailstone.position, position)?;
+        let relative = checked_difference(velocity, hailstone.velocity)?;
+        if relative == Vec3::ORIGIN {
+            return Some(offset == Point3::ORIGIN);
+        }
+        // The offset and the relative velocity are parallel and in the same direction
+        let product = |a: i128, b: i128| a.checked_mul(b);
+        let minor = |a: Axis, b: Axis| {
+            product(offset[a], relative[b])?.checked_sub(product(offset[b], relative[a])?)
+        };
+        let parallel = minor(Axis::Y, Axis::Z)? == 0
+            && minor(Axis::Z, Axis::X)? == 0
+            && minor(Axis::X, Axis::Y)? == 0;
+        let dot = Axis::ALL.into_iter().try_fold(0i128, |dot, axis| {
+            dot.checked_add(product(offset[axis], relative[axis])?)
+        })?;
+        Some(parallel && dot >= 0)
+    }
+
+    /// The rock found from a few hailstones has to hit the others too
+    fn check_rock(hailstones: &[Hailstone], position: Point3, velocity: Vec3) -> Result<()> {
+        for hailstone in hailstones {
+            let hits = hits(hailstone, position, velocity).ok_or_else(|| {
+                Error::new(format!(
+                    "The rock from {} at {} is too far from the hailstone {:?}",
+                    position, velocity, hailstone
+                ))
+            })?;
+            if !hits {
+                return Err(Error::new(format!(
+                    "The rock from {} at {} misses the hailstone {:?}",
+                    position, velocity, hailstone
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn coordinates_sum(position: Point3) -> Result<Answer> {
+        position
+            .x
+            .checked_add(position.y)
+            .and_then(|sum| sum.checked_add(position.z))
+            .and_then(|sum| i64::try_from(sum).ok())
+            .map(Answer::from)
+            .ok_or_else(|| {
+                Error::new(format!(
+                    "The sum of the rock position {} overflows",
+                    position
+                ))
+            })
+    }
+
+    fn solve(hailstones: &[Hailstone], part: Part) -> Result<Answer> {
+        match part {
+            Part::Part1 => Ok(Answer::from(count_crossings(hailstones, &TEST_AREA)?)),
+            Part::Part2 => coordinates_sum(throw_rock(hailstones)?.0),
+        }
+    }
+
+    /// The rock solved by Z3 from the first three hailstones: one time per hailstone when the
+    /// rock is where the hailstone is
+    #[cfg(feature = "smt")]
+    fn throw_rock_with_z3(hailstones: &[Hailstone]) -> Result<Point3> {
+        use aocstd::smt::{Relation, System, Term};
+
+        let mut system = System::new();
+        let positions = Axis::ALL.map(|axis| system.variable(format!("p{:?}", axis)));
+        let velocities = Axis::ALL.map(|axis| system.variable(format!("v{:?}", axis)));
+        for (index, hailstone) in hailstones.iter().take(3).enumerate() {
+            let time = system.variable(format!("t{}", index));
+            system.constrain(vec![Term::new(1, &[time])], Relation::GreaterOrEqual, 0);
+            for (axis, (position, velocity)) in
+                Axis::ALL.into_iter().zip(positions.iter().zip(&velocities))
+            {
+                // P + V t - v t = p
+                system.constrain(
+                    vec![
+                        Term::new(1, &[*position]),
+                        Term::new(1, &[*velocity, time]),
+                        Term::new(-hailstone.velocity[axis], &[time]),
+                    ],
+                    Relation::Equal,
+                    hailstone.position[axis],
+                );
+            }
+        }
+        log::debug!("Query:\n{}", system);
+        let values = system
+            .solve()?
+            .ok_or_else(|| Error::new(String::from("No rock hits the first hailstones")))?;
+        let position = Point3::new(values[0], values[1], values[2]);
+        let velocity = Vec3::new(values[3], values[4], values[5]);
+        check_rock(hailstones, position, velocity)?;
+        Ok(position)
+    }
+
+    #[cfg(not(feature = "smt"))]
+    fn throw_rock_with_z3(_hailstones: &[Hailstone]) -> Result<Point3> {
+        Err(Error::new(String::from(
+            "Built without Z3, enable the smt feature",
+        )))
+    }
+
+    /// The algorithms of --algorithm for part 2: `elimination` solves the linear systems of the
+    /// planes (the default), `z3` hands the equations of the first hailstones to Z3 (needs the
+    /// smt feature)
+    pub const ALGORITHMS: [&str; 2] = ["elimination", "z3"];
+
+    pub fn solve_with_algorithm(input: &str, part: Part, algorithm: &str) -> Result<Answer> {
+        let hailstones = parse_hailstones(input)?;
+        match (part, algorithm) {
+            (Part::Part1, _) | (Part::Part2, "elimination") => solve(&hailstones, part),
+            (Part::Part2, "z3") => coordinates_sum(throw_rock_with_z3(&hailstones)?),
+            _ => Err(Error::new(format!(
+                "Unknown algorithm {}, expected one of: {}",
+                algorithm,
+                ALGORITHMS.join(", ")
+            ))),
+        }
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let hailstones = parse_hailstones(input)?;
+        let fastest = hailstones
+            .iter()
+            .map(|hailstone| hailstone.velocity.manhattan_distance(Vec3::ORIGIN))
+            .max()
+            .unwrap_or(0);
+        Ok(format!(
+            "{} hailstones, the fastest moves {} per nanosecond",
+            hailstones.len(),
+            fastest
+        ))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        parse_hailstones(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let hailstones: Vec<Hailstone> = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        solve(&hailstones, part)
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        solve(&parse_hailstones(input)?, Part::Part1)
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        solve(&parse_hailstones(input)?, Part::Part2)
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        const EXAMPLE: &str = "19, 13, 30 @ -2,  1, -2\n\
+                               18, 19, 22 @ -1, -1, -2\n\
+                               20, 25, 34 @ -2, -2, -4\n\
+                               12, 31, 28 @ -1, -2, -1\n\
+                               20, 19, 15 @  1, -5, -3\n";
+
+        /// Hailstones of the size of the puzzle ones, thrown so that a rock hits them all
+        fn hailstones_of_puzzle_size() -> Vec<Hailstone> {
+            let rock = Hailstone {
+                position: Point3::new(191146615936494, 342596108503183, 131079628110881),
+                velocity: Vec3::new(139, -93, 245),
+            };
+            let velocities = [
+                (-57, 23, -2),
+                (41, -100, 76),
+                (212, 57, -145),
+                (9, -62, 230),
+            ];
+            (0..300)
+                .map(|index: i128| {
+                    let (vx, vy, vz) = velocities[index as usize % velocities.len()];
+                    let velocity = Vec3::new(vx + index, vy - index, vz + 2 * index);
+                    let time = 100000000000 + 2718281828 * index;
+                    Hailstone {
+                        position: rock.position + (rock.velocity - velocity) * time,
+                        velocity,
+                    }
+                })
+                .collect()
+        }
+
+        #[test]
+        fn test_crossings() {
+            aocstd::init_tests();
+
+            let hailstones = parse_hailstones(EXAMPLE).unwrap();
+            assert_eq!(count_crossings(&hailstones, &(7..=27)).unwrap(), 2);
+            // A and B cross inside, A and E cross in the past of A
+            assert_eq!(
+                paths_cross(&hailstones[0], &hailstones[1], &(7..=27)),
+                Some(true)
+            );
+            assert_eq!(
+                paths_cross(&hailstones[0], &hailstones[4], &(7..=27)),
+                Some(false)
+            );
+            assert_eq!(
+                paths_cross(&hailstones[1], &hailstones[2], &(7..=27)),
+                Some(false)
+            );
+            assert!(Hailstone::from_line("19, 13 @ -2, 1, -2").is_err());
+
+            // The products of velocities beyond u64 do not fit in i128
+            let fast = "19, 13, 30 @ 99999999999999999999, 1, -2\n\
+                        18, 19, 22 @ -1, 99999999999999999999, -2";
+            assert!(solve_part1(fast).is_err());
+        }
+
+        #[test]
+        fn test_rock() {
+            aocstd::init_tests();
+
+            assert_eq!(solve_part2(EXAMPLE).unwrap(), Answer::from(47i64));
+            let hailstones = parse_hailstones(EXAMPLE).unwrap();
+            let (position, velocity) = throw_rock(&hailstones).unwrap();
+            assert_eq!(position, Point3::new(24, 13, 10));
+            assert_eq!(velocity, Vec3::new(-3, 1, 2));
+
+            let hailstones = hailstones_of_puzzle_size();
+            let (position, velocity) = throw_rock(&hailstones).unwrap();
+            assert_eq!(
+                position,
+                Point3::new(191146615936494, 342596108503183, 131079628110881)
+            );
+            assert_eq!(velocity, Vec3::new(139, -93, 245));
+
+            let json = serde_json::to_string(&hailstones).unwrap();
+            assert_eq!(
+                solve_json(&json, Part::Part2).unwrap(),
+                Answer::from(664822352550558i64)
+            );
+            assert!(throw_rock(&hailstones[..4]).is_err());
+
+            // A rock far enough for its coordinates to overflow i128 once added, or for the
+            // elimination to overflow with fast hailstones
+            let far = |position: i128, speed: i128| -> Vec<Hailstone> {
+                let rock = Hailstone {
+                    position: Point3::new(position, position, position),
+                    velocity: Vec3::new(speed, 2, 3),
+                };
+                [(-5, 3, 7), (4, -9, 2), (8, 1, -6), (3, 3, -3), (-7, 2, 5)]
+                    .into_iter()
+                    .zip(0..)
+                    .map(|((vx, vy, vz), index): ((i128, i128, i128), i128)| {
+                        let velocity = Vec3::new(vx, vy, vz);
+                        Hailstone {
+                            position: rock.position + (rock.velocity - velocity) * (10 + 7 * index),
+                            velocity,
+                        }
+                    })
+                    .collect()
+            };
+            assert!(solve(&far(1 << 126, 1), Part::Part2).is_err());
+            assert!(solve(&far(10_i128.pow(36), 10_i128.pow(30)), Part::Part2).is_err());
+            assert!(solve_with_algorithm(EXAMPLE, Part::Part2, "guess").is_err());
+        }
+
+        #[cfg(feature = "smt")]
+        #[test]
+        fn test_z3() {
+            aocstd::init_tests();
+
+            assert_eq!(
+                solve_with_algorithm(EXAMPLE, Part::Part2, "z3").unwrap(),
+                Answer::from(47i64)
+            );
+            let hailstones = hailstones_of_puzzle_size();
+            assert_eq!(
+                throw_rock_with_z3(&hailstones).unwrap(),
+                throw_rock(&hailstones).unwrap().0
+            );
+        }
+    }
+}
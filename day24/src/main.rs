@@ -0,0 +1,42 @@
+use clap::Parser;
+use day24::never_tell_me_the_odds;
+
+/// The shared options along with the ones of this day only
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(flatten)]
+    cli: aocstd::Cli,
+    /// Algorithm of the part 2 solver: elimination (the default) or z3 (needs the smt feature)
+    #[arg(long)]
+    algorithm: Option<String>,
+}
+
+fn main() {
+    let args = Args::parse();
+    let cli = args.cli;
+    aocstd::init_logger(&cli);
+    aocstd::init_threads(&cli);
+    aocstd::init_trace(&cli);
+    let input = aocstd::get_input_string(&cli);
+    aocstd::init_profile(&cli);
+
+    if let Some(path) = &cli.dump_parsed {
+        aocstd::dump_parsed(path, never_tell_me_the_odds::parsed(&input, cli.part));
+        return;
+    }
+
+    let answer = match (cli.input_format, cli.part, args.algorithm.as_deref()) {
+        (aocstd::InputFormat::Json, part, _) => never_tell_me_the_odds::solve_json(&input, part),
+        (aocstd::InputFormat::Text, part, Some(algorithm)) => {
+            never_tell_me_the_odds::solve_with_algorithm(&input, part, algorithm)
+        }
+        (aocstd::InputFormat::Text, aocstd::Part::Part1, None) => {
+            never_tell_me_the_odds::solve_part1(&input)
+        }
+        (aocstd::InputFormat::Text, aocstd::Part::Part2, None) => {
+            never_tell_me_the_odds::solve_part2(&input)
+        }
+    };
+    aocstd::report_answer(cli.part, answer);
+}
@@ -0,0 +1,154 @@
+pub mod snowverload {
+    use aocstd::graph::Graph;
+    use aocstd::{trace, Answer, Error, Part, Result};
+    use serde::{Deserialize, Serialize};
+
+    /// The number of wires to disconnect
+    const WIRES_TO_CUT: u64 = 3;
+
+    /// A line of the wiring diagram, the connections go both ways
+    /// ex: jqt: rhn xhk nvd
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    struct Component {
+        name: String,
+        connections: Vec<String>,
+    }
+
+    impl Component {
+        fn from_line(line: &str) -> Result<Self> {
+            let (name, connections) = line
+                .split_once(':')
+                .ok_or_else(|| Error::new(format!("No connections in {:?}", line)))?;
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(Error::new(format!("No component name in {:?}", line)));
+            }
+            Ok(Component {
+                name: name.to_string(),
+                connections: connections.split_whitespace().map(String::from).collect(),
+            })
+        }
+    }
+
+    fn parse_components(input: &str) -> Result<Vec<Component>> {
+        aocstd::input::lines(input)
+            .filter(|line| !line.trim().is_empty())
+            .map(Component::from_line)
+            .collect()
+    }
+
+    fn wiring(components: &[Component]) -> Graph<&str> {
+        let mut graph = Graph::undirected();
+        for component in components {
+            graph.add_node(component.name.as_str());
+            for connection in &component.connections {
+                graph.add_edge(component.name.as_str(), connection.as_str());
+            }
+        }
+        graph
+    }
+
+    /// The sizes of the two groups left once the three wires are cut, with the minimum cut of
+    /// the wiring: it has to be exactly three wires
+    fn split_groups(components: &[Component]) -> Result<(usize, usize)> {
+        let graph = wiring(components);
+        let (wires, side) = graph
+            .min_cut()
+            .ok_or_else(|| Error::new(String::from("Less than two components")))?;
+        if wires != WIRES_TO_CUT {
+            return Err(Error::new(format!(
+                "The smallest cut is {} wires instead of {}",
+                wires, WIRES_TO_CUT
+            )));
+        }
+        let groups = (side.len(), graph.len() - side.len());
+        log::debug!(
+            "{} components and {} wires, split in {} and {}",
+            graph.len(),
+            graph.edge_count(),
+            groups.0,
+            groups.1
+        );
+        trace::emit(
+            "groups",
+            || serde_json::json!({ "sizes": [groups.0, groups.1] }),
+        );
+        Ok(groups)
+    }
+
+    fn solve(components: &[Component], part: Part) -> Result<Answer> {
+        match part {
+            Part::Part1 => {
+                let (first, second) = split_groups(components)?;
+                Ok(Answer::from((first * second) as u64))
+            }
+            // The last day only has one puzzle, the second star comes with the 49 others
+            Part::Part2 => Ok(Answer::from(String::from("Merry Christmas!"))),
+        }
+    }
+
+    /// Parse the input without solving it, returning a short description of what was parsed
+    pub fn parse_only(input: &str) -> Result<String> {
+        let components = parse_components(input)?;
+        let graph = wiring(&components);
+        Ok(format!(
+            "{} components, {} wires",
+            graph.len(),
+            graph.edge_count()
+        ))
+    }
+
+    /// The structured form of the input for --dump-parsed
+    pub fn parsed(input: &str, _part: Part) -> Result<impl Serialize> {
+        parse_components(input)
+    }
+
+    /// Solve a part from the JSON form written by --dump-parsed instead of the text input
+    pub fn solve_json(input: &str, part: Part) -> Result<Answer> {
+        let components: Vec<Component> = serde_json::from_str(input)
+            .map_err(|e| Error::new(format!("Invalid parsed input: {}", e)))?;
+        solve(&components, part)
+    }
+
+    pub fn solve_part1(input: &str) -> Result<Answer> {
+        solve(&parse_components(input)?, Part::Part1)
+    }
+
+    pub fn solve_part2(input: &str) -> Result<Answer> {
+        solve(&parse_components(input)?, Part::Part2)
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        const EXAMPLE: &str = "jqt: rhn xhk nvd\n\
+                               rsh: frs pzl lsr\n\
+                               xhk: hfx\n\
+                               cmg: qnr nvd lhk bvb\n\
+                               rhn: xhk bvb hfx\n\
+                               bvb: xhk hfx\n\
+                               pzl: lsr hfx nvd\n\
+                               qnr: nvd\n\
+                               ntq: jqt hfx bvb xhk\n\
+                               nvd: lhk\n\
+                               lsr: lhk\n\
+                               rzs: qnr cmg lsr rsh\n\
+                               frs: qnr lhk lsr\n";
+
+        #[test]
+        fn test_groups() {
+            aocstd::init_tests();
+
+            assert_eq!(solve_part1(EXAMPLE).unwrap(), Answer::from(54u64));
+            let components = parse_components(EXAMPLE).unwrap();
+            let (first, second) = split_groups(&components).unwrap();
+            assert_eq!((first.min(second), first.max(second)), (6, 9));
+            let json = serde_json::to_string(&components).unwrap();
+            assert_eq!(solve_json(&json, Part::Part1).unwrap(), Answer::from(54u64));
+            // The single wire c-d is enough to split these ones
+            assert!(solve_part1("a: b c\nb: c\nc: d\nd: e f\ne: f").is_err());
+            assert!(Component::from_line("jqt rhn").is_err());
+        }
+    }
+}